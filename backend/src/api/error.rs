@@ -0,0 +1,114 @@
+use crate::domain::feeds::FeedRepositoryError;
+use crate::domain::pipeline::PipelineRepositoryError;
+use crate::domain::posts::{PostRepositoryError, PostTransitionError};
+use crate::domain::sources::SourceRepositoryError;
+use crate::domain::themes::ThemeRepositoryError;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use thiserror::Error;
+use tracing::error;
+
+/// Error of an API handler, rendered as `{"error": "..."}` with a matching status code.
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    Conflict(String),
+    /// A remote service the request depends on (a scraped site, Telegram) failed.
+    #[error("{0}")]
+    Upstream(String),
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            ApiError::Internal(e) => {
+                error!("API error: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        (status, Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                ApiError::Conflict(format!("Still referenced: {}", db.message()))
+            }
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                ApiError::Conflict(format!("Already exists: {}", db.message()))
+            }
+            _ => ApiError::Internal(e.into()),
+        }
+    }
+}
+
+impl From<PostRepositoryError> for ApiError {
+    fn from(e: PostRepositoryError) -> Self {
+        match e {
+            PostRepositoryError::NotFound { .. } => ApiError::NotFound(e.to_string()),
+            PostRepositoryError::StatusChanged { .. } => ApiError::Conflict(e.to_string()),
+            PostRepositoryError::DatabaseError(e) => e.into(),
+        }
+    }
+}
+
+impl From<PipelineRepositoryError> for ApiError {
+    fn from(e: PipelineRepositoryError) -> Self {
+        match e {
+            PipelineRepositoryError::NotFound { .. } => ApiError::NotFound(e.to_string()),
+            PipelineRepositoryError::DatabaseError(e) => e.into(),
+        }
+    }
+}
+
+impl From<SourceRepositoryError> for ApiError {
+    fn from(e: SourceRepositoryError) -> Self {
+        match e {
+            SourceRepositoryError::NotFound { .. } => ApiError::NotFound(e.to_string()),
+            SourceRepositoryError::DatabaseError(e) => e.into(),
+        }
+    }
+}
+
+impl From<FeedRepositoryError> for ApiError {
+    fn from(e: FeedRepositoryError) -> Self {
+        match e {
+            FeedRepositoryError::NotFound { .. } => ApiError::NotFound(e.to_string()),
+            FeedRepositoryError::DatabaseError(e) => e.into(),
+        }
+    }
+}
+
+impl From<ThemeRepositoryError> for ApiError {
+    fn from(e: ThemeRepositoryError) -> Self {
+        match e {
+            ThemeRepositoryError::NotFound { .. } => ApiError::NotFound(e.to_string()),
+            ThemeRepositoryError::DatabaseError(e) => e.into(),
+        }
+    }
+}
+
+impl From<PostTransitionError> for ApiError {
+    fn from(e: PostTransitionError) -> Self {
+        match e {
+            PostTransitionError::ScheduledInPast(_) => ApiError::BadRequest(e.to_string()),
+            _ => ApiError::Conflict(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use super::{ApiError, ApiState};
+use crate::domain::feeds::{FeedPollReport, FeedPoller, FeedRepository, FeedRepositoryError};
+use crate::domain::infra::postgres::feeds_repo::FeedRepositoryPostgres;
+use adco_shared::feed::Feed;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+pub fn routes() -> Router<ApiState> {
+    Router::new()
+        .route("/api/feeds", get(list_feeds).post(add_feed))
+        .route(
+            "/api/feeds/{id}",
+            get(get_feed).patch(update_feed).delete(delete_feed),
+        )
+        .route("/api/feeds/{id}/poll", post(poll_feed))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddFeedRequest {
+    pub url: String,
+    pub title: Option<String>,
+    pub poll_interval_minutes: Option<i32>,
+}
+
+/// Fields of a feed that can be edited; missing fields stay as they are.
+#[derive(Debug, Deserialize)]
+pub struct UpdateFeedRequest {
+    pub title: Option<String>,
+    pub poll_interval_minutes: Option<i32>,
+    pub enabled: Option<bool>,
+}
+
+fn check_interval(minutes: Option<i32>) -> Result<(), ApiError> {
+    if minutes.is_some_and(|m| m <= 0) {
+        return Err(ApiError::BadRequest(
+            "Poll interval must be positive".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn load_feed(repo: &FeedRepositoryPostgres, id: Uuid) -> Result<Feed, ApiError> {
+    repo.get_feed_by_id(id)
+        .await?
+        .ok_or(FeedRepositoryError::NotFound { id }.into())
+}
+
+async fn list_feeds(State(state): State<ApiState>) -> Result<Json<Vec<Feed>>, ApiError> {
+    let repo = FeedRepositoryPostgres::new(&state.pg_pool);
+    Ok(Json(repo.list_feeds().await?))
+}
+
+async fn add_feed(
+    State(state): State<ApiState>,
+    Json(request): Json<AddFeedRequest>,
+) -> Result<(StatusCode, Json<Feed>), ApiError> {
+    if reqwest::Url::parse(&request.url).is_err() {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid URL '{}'",
+            request.url
+        )));
+    }
+    check_interval(request.poll_interval_minutes)?;
+    let mut feed = Feed::new(&request.url);
+    feed.title = request.title;
+    feed.poll_interval_minutes = request
+        .poll_interval_minutes
+        .unwrap_or(feed.poll_interval_minutes);
+    let repo = FeedRepositoryPostgres::new(&state.pg_pool);
+    feed.id = Some(repo.create_feed(&feed).await?);
+    Ok((StatusCode::CREATED, Json(feed)))
+}
+
+async fn get_feed(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Feed>, ApiError> {
+    let repo = FeedRepositoryPostgres::new(&state.pg_pool);
+    Ok(Json(load_feed(&repo, id).await?))
+}
+
+async fn update_feed(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateFeedRequest>,
+) -> Result<Json<Feed>, ApiError> {
+    check_interval(request.poll_interval_minutes)?;
+    let repo = FeedRepositoryPostgres::new(&state.pg_pool);
+    let mut feed = load_feed(&repo, id).await?;
+    if let Some(title) = request.title {
+        feed.title = Some(title);
+    }
+    if let Some(minutes) = request.poll_interval_minutes {
+        feed.poll_interval_minutes = minutes;
+    }
+    if let Some(enabled) = request.enabled {
+        feed.enabled = enabled;
+    }
+    repo.update_feed(&feed).await?;
+    Ok(Json(feed))
+}
+
+async fn delete_feed(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let repo = FeedRepositoryPostgres::new(&state.pg_pool);
+    repo.delete_feed(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Polls the feed right away, regardless of its interval.
+async fn poll_feed(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<FeedPollReport>, ApiError> {
+    let repo = FeedRepositoryPostgres::new(&state.pg_pool);
+    let mut feed = load_feed(&repo, id).await?;
+    let report = FeedPoller::new(&state.pg_pool)
+        .poll_feed(&mut feed)
+        .await
+        .map_err(|e| ApiError::Upstream(format!("Failed to poll {}: {}", feed.url, e)))?;
+    Ok(Json(report))
+}
@@ -0,0 +1,58 @@
+pub mod error;
+pub mod feeds;
+pub mod posts;
+pub mod runs;
+pub mod search;
+pub mod sources;
+pub mod themes;
+
+pub use error::*;
+
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct ApiState {
+    pub pg_pool: PgPool,
+}
+
+/// One page of a list endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// `limit`/`offset` query parameters, `limit` defaults to 20 and is capped at 100.
+#[derive(Debug, Default, Deserialize)]
+pub struct Pagination {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl Pagination {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(20).clamp(1, 100)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0).max(0)
+    }
+}
+
+/// All JSON endpoints under `/api`.
+pub fn router(pg_pool: &PgPool) -> Router {
+    Router::new()
+        .merge(posts::routes())
+        .merge(runs::routes())
+        .merge(sources::routes())
+        .merge(search::routes())
+        .merge(themes::routes())
+        .merge(feeds::routes())
+        .with_state(ApiState {
+            pg_pool: pg_pool.clone(),
+        })
+}
@@ -0,0 +1,220 @@
+use super::{ApiError, ApiState, Page, Pagination};
+use crate::domain::infra::postgres::posts_repo::PostsRepositoryPostgres;
+use crate::domain::posts::{self, PostRepository, PostRepositoryError};
+use crate::domain::publishing::html_sanitizer::sanitize_telegram_html;
+use crate::domain::publishing::publisher::Publisher;
+use adco_shared::post::{Post, PostStatus};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub fn routes() -> Router<ApiState> {
+    Router::new()
+        .route("/api/posts", get(list_posts).post(create_post))
+        .route(
+            "/api/posts/{id}",
+            get(get_post).patch(update_post).delete(delete_post),
+        )
+        .route("/api/posts/{id}/schedule", post(schedule_post))
+        .route("/api/posts/{id}/unschedule", post(unschedule_post))
+        .route("/api/posts/{id}/publish", post(publish_post))
+        .route("/api/posts/{id}/reject", post(reject_post))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PostFilter {
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePostRequest {
+    pub topic: String,
+    #[serde(default)]
+    pub draft: String,
+    #[serde(default)]
+    pub post_text: String,
+}
+
+/// Fields of a post that can be edited; missing fields stay as they are.
+#[derive(Debug, Deserialize)]
+pub struct UpdatePostRequest {
+    pub topic: Option<String>,
+    pub draft: Option<String>,
+    pub post_text: Option<String>,
+}
+
+/// The edited post and what the sanitizer fixed in its `post_text`.
+#[derive(Debug, Serialize)]
+pub struct UpdatePostResponse {
+    #[serde(flatten)]
+    pub post: Post,
+    pub html_changes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SchedulePostRequest {
+    pub scheduled_at: DateTime<Utc>,
+}
+
+/// `PostStatus::from` falls back to draft, the API rejects unknown values instead.
+fn parse_status(status: &str) -> Result<PostStatus, ApiError> {
+    [
+        PostStatus::Draft,
+        PostStatus::Scheduled,
+        PostStatus::Publishing,
+        PostStatus::Published,
+        PostStatus::Rejected,
+    ]
+    .into_iter()
+    .find(|s| s.to_string() == status)
+    .ok_or_else(|| ApiError::BadRequest(format!("Unknown post status '{}'", status)))
+}
+
+async fn load_post(repo: &PostsRepositoryPostgres, id: Uuid) -> Result<Post, ApiError> {
+    repo.get_post_by_id(id)
+        .await?
+        .ok_or(PostRepositoryError::NotFound { id }.into())
+}
+
+async fn list_posts(
+    State(state): State<ApiState>,
+    Query(filter): Query<PostFilter>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<Page<Post>>, ApiError> {
+    let status = filter.status.as_deref().map(parse_status).transpose()?;
+    let (limit, offset) = (pagination.limit(), pagination.offset());
+    let repo = PostsRepositoryPostgres::new(&state.pg_pool);
+    let items = repo.list_posts(status.clone(), limit, offset).await?;
+    let total = repo.count_posts(status).await?;
+    Ok(Json(Page {
+        items,
+        total,
+        limit,
+        offset,
+    }))
+}
+
+async fn create_post(
+    State(state): State<ApiState>,
+    Json(request): Json<CreatePostRequest>,
+) -> Result<(StatusCode, Json<Post>), ApiError> {
+    if request.topic.trim().is_empty() {
+        return Err(ApiError::BadRequest("Topic must not be empty".to_string()));
+    }
+    let mut post = Post::new(
+        request.topic,
+        request.draft,
+        request.post_text,
+        String::new(),
+    );
+    let repo = PostsRepositoryPostgres::new(&state.pg_pool);
+    post.id = Some(repo.create_post(&post).await?);
+    Ok((StatusCode::CREATED, Json(post)))
+}
+
+async fn get_post(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Post>, ApiError> {
+    let repo = PostsRepositoryPostgres::new(&state.pg_pool);
+    Ok(Json(load_post(&repo, id).await?))
+}
+
+async fn update_post(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdatePostRequest>,
+) -> Result<Json<UpdatePostResponse>, ApiError> {
+    let repo = PostsRepositoryPostgres::new(&state.pg_pool);
+    let mut post = load_post(&repo, id).await?;
+    posts::check_editable(&post)?;
+    if let Some(topic) = request.topic {
+        post.topic = topic;
+    }
+    if let Some(draft) = request.draft {
+        post.draft = draft;
+    }
+    let mut html_changes = Vec::new();
+    if let Some(post_text) = request.post_text {
+        let sanitized = sanitize_telegram_html(&post_text);
+        html_changes = sanitized.changes.iter().map(ToString::to_string).collect();
+        post.post_text = sanitized.html;
+    }
+    // A publisher may have claimed the post since it was loaded
+    repo.update_post_if_status(&post, post.status.clone()).await?;
+    Ok(Json(UpdatePostResponse { post, html_changes }))
+}
+
+async fn delete_post(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let repo = PostsRepositoryPostgres::new(&state.pg_pool);
+    let post = load_post(&repo, id).await?;
+    posts::check_editable(&post)?;
+    // A publisher may have claimed the post since it was loaded
+    repo.delete_post_if_status(id, post.status).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn schedule_post(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<SchedulePostRequest>,
+) -> Result<Json<Post>, ApiError> {
+    let repo = PostsRepositoryPostgres::new(&state.pg_pool);
+    let mut post = load_post(&repo, id).await?;
+    let status = post.status.clone();
+    posts::schedule(&mut post, request.scheduled_at, Utc::now())?;
+    repo.update_post_if_status(&post, status).await?;
+    Ok(Json(post))
+}
+
+async fn unschedule_post(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Post>, ApiError> {
+    let repo = PostsRepositoryPostgres::new(&state.pg_pool);
+    let mut post = load_post(&repo, id).await?;
+    let status = post.status.clone();
+    posts::unschedule(&mut post)?;
+    repo.update_post_if_status(&post, status).await?;
+    Ok(Json(post))
+}
+
+async fn publish_post(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Post>, ApiError> {
+    let repo = PostsRepositoryPostgres::new(&state.pg_pool);
+    let mut post = load_post(&repo, id).await?;
+    posts::check_transition(&post, PostStatus::Published)?;
+    let publisher = Publisher::from_env()?;
+    // The claim fails when the post was published or changed since it was loaded
+    publisher
+        .publish_post(&repo, &mut post)
+        .await
+        .map_err(|e| match e.downcast::<PostRepositoryError>() {
+            Ok(e) => e.into(),
+            Err(e) => ApiError::Upstream(format!("Failed to publish post: {}", e)),
+        })?;
+    Ok(Json(post))
+}
+
+async fn reject_post(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Post>, ApiError> {
+    let repo = PostsRepositoryPostgres::new(&state.pg_pool);
+    let mut post = load_post(&repo, id).await?;
+    let status = post.status.clone();
+    posts::reject(&mut post)?;
+    repo.update_post_if_status(&post, status).await?;
+    Ok(Json(post))
+}
@@ -0,0 +1,167 @@
+use super::{ApiError, ApiState};
+use crate::domain::infra::postgres::pipeline_repo::PipelineRepositoryPostgres;
+use crate::domain::pipeline::{events, PipelineRepository, PipelineRepositoryError};
+use crate::domain::scheduler::{start_pipeline_run, start_pipeline_runs};
+use adco_shared::pipeline::{
+    PipelineEvent, PipelineRun, PipelineRunStatus, PipelineStage, RunRequest,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
+use tracing::warn;
+use uuid::Uuid;
+
+pub fn routes() -> Router<ApiState> {
+    Router::new()
+        .route("/api/runs", post(start_run))
+        .route("/api/runs/batch", post(start_batch))
+        .route("/api/runs/{id}", get(get_run))
+        .route("/api/runs/{id}/events", get(run_events))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartedRun {
+    pub run_id: Uuid,
+}
+
+/// Topics to write about, one run each.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchRequest {
+    pub topics: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartedBatch {
+    pub run_ids: Vec<Uuid>,
+}
+
+/// Upper bound of a batch, each run takes minutes and several paid model calls.
+const MAX_BATCH_SIZE: usize = 20;
+
+/// A run with all its stage checkpoints.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunDetails {
+    pub run: PipelineRun,
+    pub stages: Vec<PipelineStage>,
+}
+
+fn validate_request(request: &RunRequest) -> Result<(), ApiError> {
+    request.validate().map_err(ApiError::BadRequest)
+}
+
+async fn load_run(state: &ApiState, id: Uuid) -> Result<RunDetails, ApiError> {
+    let repo = PipelineRepositoryPostgres::new(&state.pg_pool);
+    let run = repo
+        .get_run_by_id(id)
+        .await?
+        .ok_or(PipelineRepositoryError::NotFound { id })?;
+    let stages = repo.get_stages(id).await?;
+    Ok(RunDetails { run, stages })
+}
+
+fn sse_event<T: Serialize>(name: &str, data: &T) -> Event {
+    Event::default()
+        .event(name)
+        .json_data(data)
+        .unwrap_or_else(|e| Event::default().event("error").data(e.to_string()))
+}
+
+async fn start_run(
+    State(state): State<ApiState>,
+    Json(request): Json<RunRequest>,
+) -> Result<(StatusCode, Json<StartedRun>), ApiError> {
+    validate_request(&request)?;
+    match start_pipeline_run(&state.pg_pool, request).await? {
+        Some(run_id) => Ok((StatusCode::ACCEPTED, Json(StartedRun { run_id }))),
+        None => Err(ApiError::Conflict(
+            "A pipeline run is already in progress".to_string(),
+        )),
+    }
+}
+
+async fn start_batch(
+    State(state): State<ApiState>,
+    Json(batch): Json<BatchRequest>,
+) -> Result<(StatusCode, Json<StartedBatch>), ApiError> {
+    if batch.topics.is_empty() || batch.topics.len() > MAX_BATCH_SIZE {
+        return Err(ApiError::BadRequest(format!(
+            "A batch needs 1 to {} topics",
+            MAX_BATCH_SIZE
+        )));
+    }
+    let requests: Vec<RunRequest> = batch.topics.iter().map(|t| RunRequest::topic(t)).collect();
+    for request in &requests {
+        validate_request(request)?;
+    }
+    match start_pipeline_runs(&state.pg_pool, requests).await? {
+        Some(run_ids) => Ok((StatusCode::ACCEPTED, Json(StartedBatch { run_ids }))),
+        None => Err(ApiError::Conflict(
+            "A pipeline run is already in progress".to_string(),
+        )),
+    }
+}
+
+async fn get_run(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RunDetails>, ApiError> {
+    Ok(Json(load_run(&state, id).await?))
+}
+
+/// First sends a `run` event with the current state of the run, then its live events
+/// until it finishes. Runs executing in another process only get the snapshot.
+async fn run_events(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    // Subscribe before reading the snapshot so no transition falls in between
+    let live = events::subscribe(id);
+    let details = load_run(&state, id).await?;
+    let (sender, receiver) = mpsc::channel(16);
+    tokio::spawn(async move {
+        let finished = matches!(
+            details.run.status,
+            PipelineRunStatus::Completed | PipelineRunStatus::Failed
+        );
+        let mut initial = vec![sse_event("run", &details)];
+        if finished && live.is_none() {
+            let event = PipelineEvent::RunFinished {
+                status: details.run.status.clone(),
+                post_id: details.run.post_id,
+                error: details.run.error.clone(),
+            };
+            initial.push(sse_event(event.name(), &event));
+        }
+        for event in initial {
+            if sender.send(event).await.is_err() {
+                return;
+            }
+        }
+        let Some(mut live) = live else {
+            return;
+        };
+        loop {
+            match live.recv().await {
+                Ok(event) => {
+                    if sender.send(sse_event(event.name(), &event)).await.is_err() {
+                        return;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Run {} event stream lagged, {} events skipped", id, skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    });
+    let stream = ReceiverStream::new(receiver).map(Ok);
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
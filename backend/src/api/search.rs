@@ -0,0 +1,52 @@
+use super::{ApiError, ApiState};
+use crate::domain::content::EmbeddingsRepository;
+use adco_shared::search::{SearchQuery, SearchResult};
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use sqlx::PgPool;
+
+pub fn routes() -> Router<ApiState> {
+    Router::new().route("/api/search", get(search_chunks))
+}
+
+/// Validates the query and runs it against the knowledge base.
+/// Also used by the frontend server function.
+pub async fn search(pg_pool: &PgPool, query: &SearchQuery) -> Result<Vec<SearchResult>, ApiError> {
+    if query.query.trim().is_empty() {
+        return Err(ApiError::BadRequest("Query must not be empty".to_string()));
+    }
+    if let (Some(after), Some(before)) = (query.fetched_after, query.fetched_before) {
+        if after > before {
+            return Err(ApiError::BadRequest(
+                "fetched_after must not be later than fetched_before".to_string(),
+            ));
+        }
+    }
+    for (name, weight) in [
+        ("vector_weight", query.vector_weight),
+        ("text_weight", query.text_weight),
+    ] {
+        if weight.is_some_and(|w| !w.is_finite() || w < 0.0) {
+            return Err(ApiError::BadRequest(format!(
+                "{} must be a non-negative number",
+                name
+            )));
+        }
+    }
+    if query.vector_weight == Some(0.0) && query.text_weight == Some(0.0) {
+        return Err(ApiError::BadRequest(
+            "vector_weight and text_weight must not both be 0".to_string(),
+        ));
+    }
+    Ok(EmbeddingsRepository::new(pg_pool).search(query).await?)
+}
+
+async fn search_chunks(
+    State(state): State<ApiState>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<Vec<SearchResult>>, ApiError> {
+    Ok(Json(search(&state.pg_pool, &query).await?))
+}
@@ -0,0 +1,140 @@
+use super::{ApiError, ApiState, Page, Pagination};
+use crate::domain::content::EmbeddingsRepository;
+use crate::domain::infra::postgres::sources_repo::SourcesRepositoryImpl;
+use crate::domain::sources::{HtmlParser, SourceRepository, SourceRepositoryError, SourceUpsert};
+use adco_shared::source::{Source, SourceChunk, SourceSummary};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use tracing::info;
+use uuid::Uuid;
+
+pub fn routes() -> Router<ApiState> {
+    Router::new()
+        .route("/api/sources", get(list_sources).post(add_source))
+        .route("/api/sources/{id}", get(get_source).delete(delete_source))
+        .route("/api/sources/{id}/refetch", post(refetch_source))
+        .route("/api/sources/{id}/chunks", get(get_source_chunks))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddSourceRequest {
+    pub url: String,
+    pub title: Option<String>,
+}
+
+async fn load_source(repo: &SourcesRepositoryImpl, id: Uuid) -> Result<Source, ApiError> {
+    repo.get_source_by_id(id)
+        .await?
+        .ok_or(SourceRepositoryError::NotFound { id }.into())
+}
+
+async fn scrape(url: &str, title: Option<&str>) -> Result<Source, ApiError> {
+    HtmlParser::new()
+        .scrap_source_from_url(url, title)
+        .await
+        .map_err(|e| ApiError::Upstream(format!("Failed to fetch {}: {}", url, e)))
+}
+
+/// Saves a source and replaces its chunks with freshly embedded ones. Embedding happens
+/// first; the source, the removal and the new chunks are then written in one transaction.
+async fn reindex(
+    state: &ApiState,
+    repo: &SourcesRepositoryImpl,
+    source: &Source,
+) -> Result<(), ApiError> {
+    let id = source.id.unwrap_or_default();
+    let chunks = EmbeddingsRepository::new(&state.pg_pool)
+        .embed_source(source)
+        .await?;
+    let deleted = repo.replace_source_chunks(source, &chunks).await?;
+    info!(
+        "Reindexed source {}: {} chunks removed, {} created",
+        id,
+        deleted,
+        chunks.len()
+    );
+    Ok(())
+}
+
+async fn list_sources(
+    State(state): State<ApiState>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<Page<SourceSummary>>, ApiError> {
+    let (limit, offset) = (pagination.limit(), pagination.offset());
+    let repo = SourcesRepositoryImpl::new(&state.pg_pool);
+    let items = repo.list_sources(limit, offset).await?;
+    let total = repo.count_sources().await?;
+    Ok(Json(Page {
+        items,
+        total,
+        limit,
+        offset,
+    }))
+}
+
+async fn add_source(
+    State(state): State<ApiState>,
+    Json(request): Json<AddSourceRequest>,
+) -> Result<(StatusCode, Json<Source>), ApiError> {
+    if reqwest::Url::parse(&request.url).is_err() {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid URL '{}'",
+            request.url
+        )));
+    }
+    let source = scrape(&request.url, request.title.as_deref()).await?;
+    let repo = SourcesRepositoryImpl::new(&state.pg_pool);
+    let upsert = repo.upsert_source(&source).await?;
+    let source = load_source(&repo, upsert.id()).await?;
+    if upsert.needs_indexing() {
+        reindex(&state, &repo, &source).await?;
+    }
+    let status = match upsert {
+        SourceUpsert::Created(_) => StatusCode::CREATED,
+        _ => StatusCode::OK,
+    };
+    Ok((status, Json(source)))
+}
+
+async fn get_source(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Source>, ApiError> {
+    let repo = SourcesRepositoryImpl::new(&state.pg_pool);
+    Ok(Json(load_source(&repo, id).await?))
+}
+
+async fn refetch_source(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Source>, ApiError> {
+    let repo = SourcesRepositoryImpl::new(&state.pg_pool);
+    let old = load_source(&repo, id).await?;
+    let mut source = scrape(&old.url, Some(&old.title)).await?;
+    source.id = Some(id);
+    reindex(&state, &repo, &source).await?;
+    Ok(Json(source))
+}
+
+async fn delete_source(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let repo = SourcesRepositoryImpl::new(&state.pg_pool);
+    repo.delete_source(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn get_source_chunks(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<SourceChunk>>, ApiError> {
+    let repo = SourcesRepositoryImpl::new(&state.pg_pool);
+    load_source(&repo, id).await?;
+    Ok(Json(repo.get_source_chunks(id).await?))
+}
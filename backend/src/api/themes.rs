@@ -0,0 +1,100 @@
+use super::{ApiError, ApiState};
+use crate::domain::infra::postgres::themes_repo::ThemeRepositoryPostgres;
+use crate::domain::themes::{ThemeRepository, ThemeRepositoryError};
+use adco_shared::theme::TopicTheme;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, patch},
+    Json, Router,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+pub fn routes() -> Router<ApiState> {
+    Router::new()
+        .route("/api/themes", get(list_themes).post(create_theme))
+        .route("/api/themes/{id}", patch(update_theme))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateThemeRequest {
+    pub name: String,
+    pub category: String,
+    pub weight: Option<f64>,
+    pub cooldown_days: Option<i32>,
+}
+
+/// Editorial settings of a theme; missing fields stay as they are.
+#[derive(Debug, Deserialize)]
+pub struct UpdateThemeRequest {
+    pub category: Option<String>,
+    pub weight: Option<f64>,
+    pub enabled: Option<bool>,
+    pub cooldown_days: Option<i32>,
+}
+
+fn check_settings(weight: Option<f64>, cooldown_days: Option<i32>) -> Result<(), ApiError> {
+    if weight.is_some_and(|w| !w.is_finite() || w < 0.0) {
+        return Err(ApiError::BadRequest(
+            "Weight must be a non-negative number".to_string(),
+        ));
+    }
+    if cooldown_days.is_some_and(|days| !(0..=TopicTheme::MAX_COOLDOWN_DAYS).contains(&days)) {
+        return Err(ApiError::BadRequest(format!(
+            "Cooldown must be from 0 to {} days",
+            TopicTheme::MAX_COOLDOWN_DAYS
+        )));
+    }
+    Ok(())
+}
+
+async fn list_themes(State(state): State<ApiState>) -> Result<Json<Vec<TopicTheme>>, ApiError> {
+    let repo = ThemeRepositoryPostgres::new(&state.pg_pool);
+    Ok(Json(repo.list_themes().await?))
+}
+
+async fn create_theme(
+    State(state): State<ApiState>,
+    Json(request): Json<CreateThemeRequest>,
+) -> Result<(StatusCode, Json<TopicTheme>), ApiError> {
+    if request.name.trim().is_empty() || request.category.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Name and category must not be empty".to_string(),
+        ));
+    }
+    check_settings(request.weight, request.cooldown_days)?;
+    let mut theme = TopicTheme::new(request.name.trim(), request.category.trim());
+    theme.weight = request.weight.unwrap_or(theme.weight);
+    theme.cooldown_days = request.cooldown_days.unwrap_or(theme.cooldown_days);
+    let repo = ThemeRepositoryPostgres::new(&state.pg_pool);
+    theme.id = Some(repo.create_theme(&theme).await?);
+    Ok((StatusCode::CREATED, Json(theme)))
+}
+
+async fn update_theme(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateThemeRequest>,
+) -> Result<Json<TopicTheme>, ApiError> {
+    check_settings(request.weight, request.cooldown_days)?;
+    let repo = ThemeRepositoryPostgres::new(&state.pg_pool);
+    let mut theme = repo
+        .get_theme_by_id(id)
+        .await?
+        .ok_or(ThemeRepositoryError::NotFound { id })?;
+    if let Some(category) = request.category {
+        theme.category = category;
+    }
+    if let Some(weight) = request.weight {
+        theme.weight = weight;
+    }
+    if let Some(enabled) = request.enabled {
+        theme.enabled = enabled;
+    }
+    if let Some(cooldown_days) = request.cooldown_days {
+        theme.cooldown_days = cooldown_days;
+    }
+    repo.update_theme(&theme).await?;
+    Ok(Json(theme))
+}
@@ -4,7 +4,9 @@ use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::{OnceCell};
 use tracing::info;
-use tracing_subscriber;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use crate::domain::pipeline::events::RunLogLayer;
 use dotenvy::dotenv;
 use tokio::spawn;
 
@@ -21,12 +23,14 @@ impl AppState {
     pub fn initialize() -> anyhow::Result<()> {
         dotenv().ok();
         // Initialize logging
-        tracing_subscriber::fmt()
-            .with_env_filter(
+        // Log lines of pipeline runs are also streamed to API clients
+        tracing_subscriber::registry()
+            .with(
                 tracing_subscriber::EnvFilter::from_default_env()
                     .add_directive("adco_backend=info".parse()?),
             )
-            .with_target(false)
+            .with(tracing_subscriber::fmt::layer().with_target(false))
+            .with(RunLogLayer)
             .init();
     
         info!("Starting ADCO backend");
@@ -63,9 +67,14 @@ impl AppState {
             .get_or_init(|| async move {
                 info!("Initializing pg pool");
                 let database_url = std::env::var("DATABASE_URL").unwrap();
+                // The scheduler keeps a connection checked out for its advisory locks
+                let max_connections = std::env::var("ADCO_DB_MAX_CONNECTIONS")
+                    .ok()
+                    .and_then(|v| v.parse::<u32>().ok())
+                    .unwrap_or(5);
                 let options: sqlx::pool::PoolOptions<sqlx::postgres::Postgres> =
                     sqlx::pool::PoolOptions::new()
-                        .max_connections(2)
+                        .max_connections(max_connections)
                         .min_connections(0);
                 info!("Connecting to database");
                 let pg_pool = options.connect(&database_url).await.unwrap();
@@ -1,9 +1,14 @@
-use rig::{
-    Embed,
-};
+use super::tokenizer::{token_counter, TokenCounter};
+use adco_shared::source::{Source, SourceType};
+use rig::Embed;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
 use tracing::info;
-use adco_shared::source::Source;
+
+/// Input limit of the OpenAI embedding models; longer chunks are rejected by the API.
+pub const MAX_EMBEDDING_INPUT_TOKENS: usize = 8191;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Embed)]
 pub struct Chunk {
@@ -12,51 +17,464 @@ pub struct Chunk {
     pub source_title: String,
     #[embed]
     pub text: String,
+    /// Position among the chunks of the source, from 0.
+    #[serde(default)]
+    pub chunk_index: usize,
+    /// Character (not byte) range of the chunk in the source's `raw_text`.
+    #[serde(default)]
+    pub char_start: usize,
+    #[serde(default)]
+    pub char_end: usize,
+    /// Heading the chunk is under, when the text has headings.
+    #[serde(default)]
+    pub section: Option<String>,
+    /// PDF page the chunk starts on, from 1.
+    #[serde(default)]
+    pub page: Option<usize>,
+}
+
+/// How a source's text is cut into chunks. Sizes are in tokens of the [`TokenCounter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkingStrategy {
+    /// Fixed windows cut between words, regardless of sentences and paragraphs.
+    Words,
+    /// Paragraphs (separated by blank lines) packed together; a paragraph that does not
+    /// fit is split at sentence ends.
+    Paragraphs,
+    /// Like `Paragraphs`, but a markdown heading always starts a new chunk, and chunks
+    /// know their heading. Scraped HTML pages are stored as markdown.
+    Sections,
+    /// Like `Sections` for PDF text: pages are separated by form feeds, numbered and
+    /// well-known headings ("2.1 Methods", "References") start new chunks.
+    Pages,
 }
 
 // content/chunker.rs
 pub struct TextChunker {
-    pub chunk_size: usize,    // 400 токенов
-    pub overlap_size: usize,  // 100 токенов
+    pub chunk_size: usize,   // 400 токенов
+    pub overlap_size: usize, // 100 токенов из конца предыдущего чанка
+    strategies: HashMap<SourceType, ChunkingStrategy>,
+    counter: Arc<dyn TokenCounter>,
+}
+
+impl Default for TextChunker {
+    fn default() -> Self {
+        Self::new(400, 100)
+    }
+}
+
+/// Piece of text that is never split when packing: a paragraph, a sentence or a word window.
+#[derive(Debug, Clone)]
+struct Unit {
+    range: Range<usize>,
+    tokens: usize,
+    section: Option<String>,
+    page: Option<usize>,
+    /// Starts a new chunk that does not repeat the end of the previous one: the first
+    /// unit of the text or of a section, or a word window (windows overlap already).
+    hard_break: bool,
 }
 
+const PDF_HEADINGS: &[&str] = &[
+    "abstract",
+    "introduction",
+    "background",
+    "related work",
+    "methods",
+    "materials and methods",
+    "results",
+    "discussion",
+    "conclusion",
+    "conclusions",
+    "acknowledgements",
+    "acknowledgments",
+    "references",
+    "appendix",
+];
+
 impl TextChunker {
+    /// Web pages and feed items are chunked by sections, PDFs by pages, anything else by paragraphs.
+    /// `chunk_size` is capped at [`MAX_EMBEDDING_INPUT_TOKENS`].
     pub fn new(chunk_size: usize, overlap_size: usize) -> Self {
+        let chunk_size = chunk_size.clamp(1, MAX_EMBEDDING_INPUT_TOKENS);
+        let strategies = HashMap::from([
+            (SourceType::WebPage, ChunkingStrategy::Sections),
+            (SourceType::RSS, ChunkingStrategy::Sections),
+            (SourceType::PDF, ChunkingStrategy::Pages),
+            (SourceType::API, ChunkingStrategy::Paragraphs),
+        ]);
         Self {
             chunk_size,
-            overlap_size,
+            overlap_size: overlap_size.min(chunk_size - 1),
+            strategies,
+            counter: token_counter(),
         }
     }
 
+    /// Sizes from ADCO_CHUNK_TOKENS and ADCO_CHUNK_OVERLAP_TOKENS, 400 and 100 by default.
+    pub fn from_env() -> Self {
+        let env = |name: &str, default: usize| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default)
+        };
+        Self::new(
+            env("ADCO_CHUNK_TOKENS", 400),
+            env("ADCO_CHUNK_OVERLAP_TOKENS", 100),
+        )
+    }
+
+    pub fn with_counter(mut self, counter: Arc<dyn TokenCounter>) -> Self {
+        self.counter = counter;
+        self
+    }
+
+    pub fn with_strategy(mut self, source_type: SourceType, strategy: ChunkingStrategy) -> Self {
+        self.strategies.insert(source_type, strategy);
+        self
+    }
+
+    pub fn strategy_for(&self, source_type: &SourceType) -> ChunkingStrategy {
+        self.strategies
+            .get(source_type)
+            .copied()
+            .unwrap_or(ChunkingStrategy::Paragraphs)
+    }
+
     pub fn chunk_text_from_source(&self, source: &Source) -> Vec<Chunk> {
-        let mut chunks: Vec<Chunk> = Vec::new();
-        let source_id = source.id.clone().unwrap_or_default().to_string();
-        let source_url = source.url.clone();
-        let source_title = source.title.clone();
-        let tokens = source.raw_text.split_whitespace();
-        let real_chunk_size = self.chunk_size - self.overlap_size;
-        let num_chunks = (tokens.clone().count() + real_chunk_size - 1) / real_chunk_size;
-        info!("Creating {} chunks from {} tokens", num_chunks, tokens.clone().count());
-        for i in 0..num_chunks {
-            let start_index = i * real_chunk_size;
-            let end_index = std::cmp::min(start_index + self.chunk_size, tokens.clone().count());
-            if tokens.clone().count() > end_index {
-                chunks.push(Chunk {
-                    source_id: source_id.clone(),
-                    text: tokens.clone().skip(start_index).take(end_index - start_index).collect::<Vec<&str>>().join(" "),
-                    source_url: source_url.clone(),
-                    source_title: source_title.clone(),
-                });
-            } else {
-                let remaining_tokens = tokens.clone().count() - start_index;
-                chunks.push(Chunk {
-                    source_id: source_id.clone(),
-                    text: tokens.clone().skip(start_index).take(remaining_tokens).collect::<Vec<&str>>().join(" "),
-                    source_url: source_url.clone(),
-                    source_title: source_title.clone(),
-                });
+        let strategy = self.strategy_for(&source.source_type);
+        let text = &source.raw_text;
+        let units = match strategy {
+            ChunkingStrategy::Words => self.split_words(text, 0..text.len(), &None, None, true),
+            _ => self.units(text, strategy),
+        };
+        // Chunk ranges only move forward, so the character offsets are counted in one pass
+        let mut starts = CharOffsets::new(text);
+        let mut ends = CharOffsets::new(text);
+        let chunks: Vec<Chunk> = self
+            .pack(units)
+            .into_iter()
+            .enumerate()
+            .map(|(index, (range, section, page))| Chunk {
+                source_id: source.id.unwrap_or_default().to_string(),
+                source_url: source.url.clone(),
+                source_title: source.title.clone(),
+                text: text[range.clone()]
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                chunk_index: index,
+                char_start: starts.at(range.start),
+                char_end: ends.at(range.end),
+                section,
+                page,
+            })
+            .collect();
+        info!(
+            "Created {} chunks with {:?} chunking",
+            chunks.len(),
+            strategy
+        );
+        chunks
+    }
+
+    /// Paragraphs of the text, with long ones split into sentences and then word windows.
+    fn units(&self, text: &str, strategy: ChunkingStrategy) -> Vec<Unit> {
+        let mut units = Vec::new();
+        let mut section: Option<String> = None;
+        let mut hard_break = true;
+        let whole = 0..text.len();
+        let pages = if strategy == ChunkingStrategy::Pages {
+            split_keeping_offsets(text, whole, '\u{c}')
+        } else {
+            vec![whole]
+        };
+
+        for (page_index, page) in pages.into_iter().enumerate() {
+            let page_number = (strategy == ChunkingStrategy::Pages).then_some(page_index + 1);
+            let mut paragraph: Option<Range<usize>> = None;
+            for line in split_keeping_offsets(text, page, '\n') {
+                let content = text[line.clone()].trim();
+                let heading = match strategy {
+                    ChunkingStrategy::Sections => markdown_heading(content),
+                    ChunkingStrategy::Pages => pdf_heading(content),
+                    _ => None,
+                };
+                if content.is_empty() || heading.is_some() {
+                    if let Some(range) = paragraph.take() {
+                        units.extend(self.paragraph_units(
+                            text,
+                            range,
+                            &section,
+                            page_number,
+                            hard_break,
+                        ));
+                        hard_break = false;
+                    }
+                }
+                if let Some(heading) = heading {
+                    section = Some(heading);
+                    units.extend(self.paragraph_units(text, line, &section, page_number, true));
+                    hard_break = false;
+                } else if !content.is_empty() {
+                    paragraph = Some(match paragraph {
+                        Some(range) => range.start..line.end,
+                        None => line,
+                    });
+                }
+            }
+            if let Some(range) = paragraph {
+                units.extend(self.paragraph_units(text, range, &section, page_number, hard_break));
+                hard_break = false;
+            }
+        }
+        units
+    }
+
+    fn paragraph_units(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        section: &Option<String>,
+        page: Option<usize>,
+        hard_break: bool,
+    ) -> Vec<Unit> {
+        let tokens = self.counter.count_tokens(&text[range.clone()]);
+        if tokens <= self.chunk_size {
+            return vec![Unit {
+                range,
+                tokens,
+                section: section.clone(),
+                page,
+                hard_break,
+            }];
+        }
+        let mut units = Vec::new();
+        for sentence in sentence_ranges(text, range) {
+            let first = hard_break && units.is_empty();
+            units.extend(self.split_words(text, sentence, section, page, first));
+        }
+        units
+    }
+
+    /// Windows of up to `chunk_size` tokens cut between words, each repeating up to
+    /// `overlap_size` tokens of the previous one; one unit if the range fits.
+    fn split_words(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        section: &Option<String>,
+        page: Option<usize>,
+        hard_break: bool,
+    ) -> Vec<Unit> {
+        let words = word_ranges(text, range);
+        if words.is_empty() {
+            return Vec::new();
+        }
+        let tokens: Vec<usize> = words
+            .iter()
+            .map(|word| self.counter.count_tokens(&text[word.clone()]))
+            .collect();
+        let mut units = Vec::new();
+        let mut start = 0;
+        loop {
+            // A single word longer than a chunk still makes a window of its own
+            let mut end = start + 1;
+            let mut used = tokens[start];
+            while end < words.len() && used + tokens[end] <= self.chunk_size {
+                used += tokens[end];
+                end += 1;
+            }
+            units.push(Unit {
+                range: words[start].start..words[end - 1].end,
+                tokens: used,
+                section: section.clone(),
+                page,
+                hard_break: hard_break || start > 0,
+            });
+            if end == words.len() {
+                break;
+            }
+            let mut next = end;
+            let mut overlap = 0;
+            while next - 1 > start && overlap + tokens[next - 1] <= self.overlap_size {
+                overlap += tokens[next - 1];
+                next -= 1;
+            }
+            start = next;
+        }
+        units
+    }
+
+    /// Greedily packs units into chunks of at most `chunk_size` tokens; a chunk repeats the
+    /// last units of the previous one, up to `overlap_size` tokens, unless a section starts.
+    /// The first unit of every chunk is new, so packing always moves forward.
+    fn pack(&self, units: Vec<Unit>) -> Vec<(Range<usize>, Option<String>, Option<usize>)> {
+        let mut chunks = Vec::new();
+        let mut current: Vec<Unit> = Vec::new();
+        let mut has_new = false;
+        for unit in units {
+            let tokens: usize = current.iter().map(|u| u.tokens).sum();
+            if has_new && (unit.hard_break || tokens + unit.tokens > self.chunk_size) {
+                chunks.push(span(&current));
+                let mut overlap: Vec<Unit> = Vec::new();
+                if !unit.hard_break {
+                    let mut overlap_tokens = 0;
+                    for previous in current.iter().rev() {
+                        overlap_tokens += previous.tokens;
+                        if overlap_tokens > self.overlap_size
+                            || overlap_tokens + unit.tokens > self.chunk_size
+                        {
+                            break;
+                        }
+                        overlap.insert(0, previous.clone());
+                    }
+                }
+                current = overlap;
             }
+            current.push(unit);
+            has_new = true;
+        }
+        if has_new {
+            chunks.push(span(&current));
         }
         chunks
     }
 }
+
+fn span(units: &[Unit]) -> (Range<usize>, Option<String>, Option<usize>) {
+    let first = &units[0];
+    let last = &units[units.len() - 1];
+    (
+        first.range.start..last.range.end,
+        first.section.clone(),
+        first.page,
+    )
+}
+
+/// Converts increasing byte offsets of a text to character offsets, counting each
+/// character once. An offset before the previous one is counted again from the start.
+struct CharOffsets<'a> {
+    text: &'a str,
+    byte: usize,
+    chars: usize,
+}
+
+impl<'a> CharOffsets<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            byte: 0,
+            chars: 0,
+        }
+    }
+
+    fn at(&mut self, byte: usize) -> usize {
+        if byte < self.byte {
+            self.byte = 0;
+            self.chars = 0;
+        }
+        self.chars += self.text[self.byte..byte].chars().count();
+        self.byte = byte;
+        self.chars
+    }
+}
+
+/// Byte ranges of the parts of `text[range]` between separators.
+fn split_keeping_offsets(text: &str, range: Range<usize>, separator: char) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = range.start;
+    for (at, _) in text[range.clone()].match_indices(separator) {
+        ranges.push(start..range.start + at);
+        start = range.start + at + separator.len_utf8();
+    }
+    ranges.push(start..range.end);
+    ranges
+}
+
+fn word_ranges(text: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (at, c) in text[range.clone()].char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                words.push(range.start + s..range.start + at);
+                start = None;
+            }
+            (false, None) => start = Some(at),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        words.push(range.start + s..range.end);
+    }
+    words
+}
+
+/// Sentences end with `.`, `!` or `?` (and closing quotes or brackets) followed by
+/// whitespace and an uppercase letter or a digit, which skips most abbreviations.
+fn sentence_ranges(text: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let part = &text[range.clone()];
+    let chars: Vec<(usize, char)> = part.char_indices().collect();
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        if matches!(chars[i].1, '.' | '!' | '?' | '…') {
+            let mut end = i + 1;
+            while end < chars.len() && matches!(chars[end].1, '"' | '\'' | ')' | ']' | '»' | '”')
+            {
+                end += 1;
+            }
+            let mut next = end;
+            while next < chars.len() && chars[next].1.is_whitespace() {
+                next += 1;
+            }
+            if next > end
+                && next < chars.len()
+                && (chars[next].1.is_uppercase() || chars[next].1.is_ascii_digit())
+            {
+                let end_byte = chars[end].0;
+                sentences.push(range.start + chars[start].0..range.start + end_byte);
+                start = next;
+                i = next;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    if start < chars.len() {
+        sentences.push(range.start + chars[start].0..range.end);
+    }
+    sentences
+}
+
+fn markdown_heading(line: &str) -> Option<String> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.starts_with(' ') {
+        return None;
+    }
+    Some(rest.trim().to_string()).filter(|heading| !heading.is_empty())
+}
+
+/// "3 Results", "2.1. Data collection" or a well-known section name on its own line.
+fn pdf_heading(line: &str) -> Option<String> {
+    let words = line.split_whitespace().count();
+    if line.is_empty() || words > 8 || line.ends_with(['.', ',', ';', ':']) {
+        return None;
+    }
+    let name = line
+        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.')
+        .trim();
+    let numbered = name.len() < line.len()
+        && line.starts_with(|c: char| c.is_ascii_digit())
+        && line[..line.len() - name.len()].ends_with([' ', '.'])
+        && name.starts_with(char::is_uppercase);
+    let known = PDF_HEADINGS.contains(&name.to_lowercase().as_str());
+    (numbered || known).then(|| line.to_string())
+}
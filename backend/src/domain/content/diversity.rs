@@ -0,0 +1,155 @@
+use crate::domain::content::chunker::Chunk;
+use crate::domain::content::novelty::cosine_similarity;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// How retrieved chunks are spread over sources before they reach the Researcher.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiversityConfig {
+    /// MMR trade-off: 1 ranks by relevance only, 0 by difference from the chunks
+    /// already selected only.
+    pub mmr_lambda: f64,
+    /// At most this many chunks of one source; 0 for no limit.
+    pub max_chunks_per_source: usize,
+}
+
+impl Default for DiversityConfig {
+    fn default() -> Self {
+        Self {
+            mmr_lambda: 0.7,
+            max_chunks_per_source: 3,
+        }
+    }
+}
+
+impl DiversityConfig {
+    /// Plain relevance order, as before diversification.
+    pub fn disabled() -> Self {
+        Self {
+            mmr_lambda: 1.0,
+            max_chunks_per_source: 0,
+        }
+    }
+
+    /// Reads `ADCO_RETRIEVAL_MMR_LAMBDA` and `ADCO_RETRIEVAL_MAX_CHUNKS_PER_SOURCE`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |name: &str| std::env::var(name).ok();
+        Self {
+            mmr_lambda: read("ADCO_RETRIEVAL_MMR_LAMBDA")
+                .and_then(|v| v.parse::<f64>().ok())
+                .map(|lambda| lambda.clamp(0.0, 1.0))
+                .unwrap_or(defaults.mmr_lambda),
+            max_chunks_per_source: read("ADCO_RETRIEVAL_MAX_CHUNKS_PER_SOURCE")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_chunks_per_source),
+        }
+    }
+}
+
+/// How varied a retrieval result is.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiversityStats {
+    /// Chunks the selection was made from.
+    pub candidates: usize,
+    pub selected: usize,
+    pub distinct_sources: usize,
+    /// Chunks of the source with the most selected chunks.
+    pub max_chunks_from_one_source: usize,
+    /// Candidates left out because their source already had the maximum of chunks.
+    pub skipped_by_source_limit: usize,
+    /// Mean cosine similarity between pairs of selected chunks, lower is more varied.
+    pub mean_pairwise_similarity: f64,
+}
+
+/// A chunk found for a query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetrievedChunk {
+    pub id: String,
+    /// Hybrid search score, see [`crate::domain::content::reciprocal_rank_fusion`].
+    pub score: f64,
+    /// Relevance to the topic given by the re-ranker, from 0 to 1.
+    #[serde(default)]
+    pub rerank_score: Option<f64>,
+    pub chunk: Chunk,
+}
+
+/// Chunks for a query in the order they are given to the Researcher.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetrievedChunks {
+    pub chunks: Vec<RetrievedChunk>,
+    pub diversity: DiversityStats,
+    /// Name of the re-ranker that scored the candidates.
+    #[serde(default)]
+    pub reranker: String,
+}
+
+/// Picks up to `k` candidates by maximal marginal relevance: each step takes the one with
+/// the best `lambda * relevance - (1 - lambda) * similarity to the closest picked one`,
+/// skipping sources that already have `max_chunks_per_source` chunks.
+/// Relevance is expected in 0..=1. Returns the picked indexes in order.
+pub fn select_diverse(
+    relevance: &[f64],
+    embeddings: &[Vec<f32>],
+    sources: &[&str],
+    k: usize,
+    config: DiversityConfig,
+) -> (Vec<usize>, DiversityStats) {
+    let lambda = config.mmr_lambda;
+    let capped = |counts: &HashMap<&str, usize>, source: &str| {
+        config.max_chunks_per_source > 0
+            && counts.get(source).copied().unwrap_or(0) >= config.max_chunks_per_source
+    };
+    let mut selected: Vec<usize> = Vec::new();
+    let mut per_source: HashMap<&str, usize> = HashMap::new();
+    while selected.len() < k {
+        let mut best: Option<(f64, usize)> = None;
+        for index in 0..relevance.len() {
+            if selected.contains(&index) || capped(&per_source, sources[index]) {
+                continue;
+            }
+            let redundancy = selected
+                .iter()
+                .map(|&picked| cosine_similarity(&embeddings[index], &embeddings[picked]))
+                .fold(0.0, f64::max);
+            let score = lambda * relevance[index] - (1.0 - lambda) * redundancy;
+            if best.is_none_or(|(best_score, _)| score > best_score) {
+                best = Some((score, index));
+            }
+        }
+        let Some((_, index)) = best else {
+            break;
+        };
+        selected.push(index);
+        *per_source.entry(sources[index]).or_default() += 1;
+    }
+
+    let skipped_by_source_limit = (0..relevance.len())
+        .filter(|index| !selected.contains(index) && capped(&per_source, sources[*index]))
+        .count();
+    let mut similarity_sum = 0.0;
+    let mut pairs = 0;
+    for (position, &a) in selected.iter().enumerate() {
+        for &b in &selected[position + 1..] {
+            similarity_sum += cosine_similarity(&embeddings[a], &embeddings[b]);
+            pairs += 1;
+        }
+    }
+    let stats = DiversityStats {
+        candidates: relevance.len(),
+        selected: selected.len(),
+        distinct_sources: selected
+            .iter()
+            .map(|&index| sources[index])
+            .collect::<HashSet<_>>()
+            .len(),
+        max_chunks_from_one_source: per_source.values().copied().max().unwrap_or(0),
+        skipped_by_source_limit,
+        mean_pairwise_similarity: if pairs > 0 {
+            similarity_sum / pairs as f64
+        } else {
+            0.0
+        },
+    };
+    (selected, stats)
+}
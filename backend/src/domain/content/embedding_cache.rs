@@ -0,0 +1,157 @@
+use super::embeddings_repository::{record_embedding, NamedEmbeddingModel};
+use crate::domain::infra::postgres::embedding_cache_repo::EmbeddingCacheRepositoryPostgres;
+use rig::embeddings::{Embedding, EmbeddingError, EmbeddingModel};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use thiserror::Error;
+use tracing::{info, warn};
+
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+
+pub trait EmbeddingCacheRepository {
+    /// Cached vectors of the keys that are known, marked as used now.
+    async fn get_embeddings(
+        &self,
+        keys: &[String],
+    ) -> Result<HashMap<String, Vec<f32>>, EmbeddingCacheError>;
+    async fn save_embeddings(
+        &self,
+        model: &str,
+        entries: &[(String, Vec<f32>)],
+    ) -> Result<(), EmbeddingCacheError>;
+}
+
+#[derive(Error, Debug)]
+pub enum EmbeddingCacheError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+/// Texts looked up in the cache since the process started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EmbeddingCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+pub fn embedding_cache_stats() -> EmbeddingCacheStats {
+    EmbeddingCacheStats {
+        hits: CACHE_HITS.load(Ordering::Relaxed),
+        misses: CACHE_MISSES.load(Ordering::Relaxed),
+    }
+}
+
+/// sha256 of the model, its dimensions and the text: the same model name with another
+/// number of dimensions gives different vectors.
+pub fn embedding_cache_key(model: &str, ndims: usize, text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}:{}\n", model, ndims).as_bytes());
+    hasher.update(text.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Embedding model that answers from `embedding_cache` and only sends the texts it has
+/// not seen to the wrapped model. Usage is recorded for those texts only.
+/// The cache failing is logged and the texts are embedded as if it were empty.
+#[derive(Debug, Clone)]
+pub struct CachedEmbeddingModel<M: NamedEmbeddingModel> {
+    model: M,
+    cache: EmbeddingCacheRepositoryPostgres,
+}
+
+impl<M: NamedEmbeddingModel> CachedEmbeddingModel<M> {
+    pub fn new(pg_pool: &PgPool, model: M) -> Self {
+        Self {
+            model,
+            cache: EmbeddingCacheRepositoryPostgres::new(pg_pool),
+        }
+    }
+
+    fn key(&self, text: &str) -> String {
+        embedding_cache_key(self.model.model_name(), self.model.ndims(), text)
+    }
+}
+
+impl<M: NamedEmbeddingModel> NamedEmbeddingModel for CachedEmbeddingModel<M> {
+    fn model_name(&self) -> &str {
+        self.model.model_name()
+    }
+}
+
+impl<M: NamedEmbeddingModel> EmbeddingModel for CachedEmbeddingModel<M> {
+    const MAX_DOCUMENTS: usize = M::MAX_DOCUMENTS;
+
+    fn ndims(&self) -> usize {
+        self.model.ndims()
+    }
+
+    async fn embed_texts(
+        &self,
+        texts: impl IntoIterator<Item = String> + Send,
+    ) -> Result<Vec<Embedding>, EmbeddingError> {
+        let texts: Vec<String> = texts.into_iter().collect();
+        let keys: Vec<String> = texts.iter().map(|text| self.key(text)).collect();
+        let mut vectors = self.cache.get_embeddings(&keys).await.unwrap_or_else(|e| {
+            warn!("Embedding cache lookup failed: {}", e);
+            HashMap::new()
+        });
+
+        let mut missing: Vec<(String, String)> = Vec::new();
+        for (key, text) in keys.iter().zip(&texts) {
+            if !vectors.contains_key(key) && !missing.iter().any(|(k, _)| k == key) {
+                missing.push((key.clone(), text.clone()));
+            }
+        }
+        let hits = texts.len() - missing.len();
+        CACHE_HITS.fetch_add(hits as u64, Ordering::Relaxed);
+        CACHE_MISSES.fetch_add(missing.len() as u64, Ordering::Relaxed);
+        info!("Embedding cache: {} hits, {} misses", hits, missing.len());
+
+        if !missing.is_empty() {
+            let start_time = Instant::now();
+            let characters = missing.iter().map(|(_, text)| text.chars().count()).sum();
+            let embedded = self
+                .model
+                .embed_texts(
+                    missing
+                        .iter()
+                        .map(|(_, text)| text.clone())
+                        .collect::<Vec<_>>(),
+                )
+                .await?;
+            record_embedding(self.model.model_name(), characters, start_time);
+            let entries: Vec<(String, Vec<f32>)> = missing
+                .into_iter()
+                .zip(embedded)
+                .map(|((key, _), embedding)| {
+                    (key, embedding.vec.iter().map(|v| *v as f32).collect())
+                })
+                .collect();
+            if let Err(e) = self
+                .cache
+                .save_embeddings(self.model.model_name(), &entries)
+                .await
+            {
+                warn!("Failed to save embeddings to the cache: {}", e);
+            }
+            vectors.extend(entries);
+        }
+
+        keys.iter()
+            .zip(texts)
+            .map(|(key, text)| {
+                let vector = vectors.get(key).ok_or_else(|| {
+                    EmbeddingError::ResponseError(format!("No embedding returned for '{}'", text))
+                })?;
+                Ok(Embedding {
+                    document: text,
+                    vec: vector.iter().map(|v| *v as f64).collect(),
+                })
+            })
+            .collect()
+    }
+}
@@ -1,25 +1,129 @@
-use crate::domain::content::chunker::Chunk;
+use crate::domain::content::chunker::{Chunk, TextChunker};
+use crate::domain::content::diversity::{
+    select_diverse, DiversityConfig, RetrievedChunk, RetrievedChunks,
+};
+use crate::domain::content::embedding_cache::CachedEmbeddingModel;
+use crate::domain::content::hybrid_search::{
+    full_text_query, reciprocal_rank_fusion, FullTextQuery, HybridWeights,
+};
+use crate::domain::content::rerank::{relative_scores, NoopReranker, Reranker};
+use crate::domain::usage::{cost_usd, record_call};
+use adco_shared::search::{SearchQuery, SearchResult};
+use adco_shared::source::{Source, SourceType};
+use adco_shared::usage::{LlmCall, LlmCallKind};
+use chrono::{DateTime, Utc};
 use rig::{
-    embeddings::{EmbeddingsBuilder},
+    embeddings::{EmbeddingModel, EmbeddingsBuilder},
     providers::openai::{self},
-    vector_store::{InsertDocuments, VectorSearchRequest, VectorStoreIndex},
+    vector_store::InsertDocuments,
 };
 use rig_postgres::PostgresVectorStore;
-use sqlx::PgPool;
-use tracing::error;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Embedding model that knows its name, used for usage and cost accounting.
+pub trait NamedEmbeddingModel: EmbeddingModel {
+    fn model_name(&self) -> &str;
+}
+
+impl NamedEmbeddingModel for openai::EmbeddingModel {
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+}
+
+/// rig does not return embeddings usage, so tokens are estimated as ~4 characters per token.
+pub(crate) fn record_embedding(model: &str, characters: usize, start_time: Instant) {
+    let input_tokens = characters.div_ceil(4) as u64;
+    let mut call = LlmCall::new("embeddings", LlmCallKind::Embedding, model);
+    call.input_tokens = input_tokens as i64;
+    call.latency_ms = start_time.elapsed().as_millis() as i64;
+    call.cost_usd = cost_usd(model, input_tokens, 0);
+    record_call(call);
+}
+
+/// A chunk with its embedding, computed before anything is written.
+#[derive(Debug, Clone)]
+pub struct EmbeddedChunk {
+    pub chunk: Chunk,
+    pub embedding: Vec<f64>,
+}
 
-pub struct EmbeddingsRepository {
+/// Restricts a search to chunks of matching sources. Chunks whose source row is missing
+/// only pass the empty filter.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceFilter {
+    pub source_type: Option<SourceType>,
+    pub source_id: Option<Uuid>,
+    pub fetched_after: Option<DateTime<Utc>>,
+    pub fetched_before: Option<DateTime<Utc>>,
+}
+
+impl From<&SearchQuery> for SourceFilter {
+    fn from(query: &SearchQuery) -> Self {
+        Self {
+            source_type: query.source_type.clone(),
+            source_id: query.source_id,
+            fetched_after: query.fetched_after,
+            fetched_before: query.fetched_before,
+        }
+    }
+}
+
+/// Joins the chunk's source and applies a [`SourceFilter`] bound as `$3`..`$6`.
+const SOURCE_FILTER: &str = "LEFT JOIN sources s ON s.id::TEXT = d.document->>'source_id'
+    WHERE ($3::TEXT IS NULL OR s.source_type = $3)
+      AND ($4::UUID IS NULL OR s.id = $4)
+      AND ($5::TIMESTAMPTZ IS NULL OR s.fetched_at >= $5)
+      AND ($6::TIMESTAMPTZ IS NULL OR s.fetched_at <= $6)";
+
+/// Every embedding goes through the cache, see [`CachedEmbeddingModel`].
+pub struct EmbeddingsRepository<M: NamedEmbeddingModel = openai::EmbeddingModel> {
     pg_pool: PgPool,
-    model: openai::EmbeddingModel,
+    model: CachedEmbeddingModel<M>,
+    chunker: TextChunker,
+    reranker: Arc<dyn Reranker>,
 }
 
 impl EmbeddingsRepository {
     pub fn new(pg_pool: &PgPool) -> Self {
+        Self::with_model(pg_pool, Self::default_model())
+    }
+
+    pub fn default_model() -> openai::EmbeddingModel {
         let api_key = std::env::var("ADCO_OPEN_AI_API_KEY").unwrap_or_default();
         let client = openai::Client::new(&api_key);
-        let model =
-            openai::EmbeddingModel::new(client, openai::embedding::TEXT_EMBEDDING_3_SMALL, 1536);
-        Self { pg_pool: pg_pool.clone(), model }
+        openai::EmbeddingModel::new(client, openai::embedding::TEXT_EMBEDDING_3_SMALL, 1536)
+    }
+}
+
+impl<M: NamedEmbeddingModel> EmbeddingsRepository<M> {
+    pub fn with_model(pg_pool: &PgPool, model: M) -> Self {
+        Self {
+            pg_pool: pg_pool.clone(),
+            model: CachedEmbeddingModel::new(pg_pool, model),
+            chunker: TextChunker::from_env(),
+            reranker: Arc::new(NoopReranker),
+        }
+    }
+
+    pub fn with_chunker(mut self, chunker: TextChunker) -> Self {
+        self.chunker = chunker;
+        self
+    }
+
+    /// Re-ranker used by [`Self::retrieve_chunks`], [`NoopReranker`] by default.
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>) -> Self {
+        self.reranker = reranker;
+        self
+    }
+
+    pub fn model_name(&self) -> &str {
+        self.model.model_name()
     }
 
     pub async fn save_chunks(&self, chunks: Vec<Chunk>) -> Result<(), anyhow::Error> {
@@ -43,21 +147,336 @@ impl EmbeddingsRepository {
         Ok(())
     }
 
+    /// Chunks a saved source and embeds the chunks, returns the number of chunks.
+    pub async fn index_source(&self, source: &Source) -> Result<usize, anyhow::Error> {
+        let chunks = self.chunker.chunk_text_from_source(source);
+        info!("Created {} chunks for source", chunks.len());
+        let count = chunks.len();
+        self.save_chunks(chunks).await?;
+        Ok(count)
+    }
+
+    /// Chunks a source and embeds the chunks without storing them, so they can replace
+    /// the stored ones in one transaction, see `SourceRepository::replace_source_chunks`.
+    pub async fn embed_source(&self, source: &Source) -> Result<Vec<EmbeddedChunk>, anyhow::Error> {
+        let chunks = self.chunker.chunk_text_from_source(source);
+        info!("Created {} chunks for source", chunks.len());
+        let mut embedded = Vec::with_capacity(chunks.len());
+        // Same batches as `save_chunks`, to stay under the token limit
+        for chunk_batch in chunks.chunks(2) {
+            let documents = EmbeddingsBuilder::new(self.model.clone())
+                .documents(chunk_batch.to_vec())?
+                .build()
+                .await?;
+            for (chunk, embeddings) in documents {
+                embedded.push(EmbeddedChunk {
+                    chunk,
+                    embedding: embeddings.first().vec,
+                });
+            }
+        }
+        Ok(embedded)
+    }
+
+    /// Embeds short texts (topics, queries) in one batch, vectors come back in order.
+    pub async fn embed_texts(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, anyhow::Error> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let embeddings = self.model.embed_texts(texts).await?;
+        Ok(embeddings
+            .into_iter()
+            .map(|embedding| embedding.vec.iter().map(|v| *v as f32).collect())
+            .collect())
+    }
+
+    /// Hybrid search: cosine and full-text rankings merged by reciprocal rank fusion with
+    /// the given weights, both limited to chunks passing `filter`. Returns (fused score,
+    /// chunk id, chunk), best first.
     pub async fn search_chunks(
         &self,
         query: &str,
         top_k: usize,
+        weights: HybridWeights,
+        filter: &SourceFilter,
     ) -> Result<Vec<(f64, String, Chunk)>, anyhow::Error> {
-        let vector_store =
-            PostgresVectorStore::with_defaults(self.model.clone(), self.pg_pool.clone());
-        let results = vector_store
-            .top_n::<Chunk>(
-                VectorSearchRequest::builder()
-                    .query(query)
-                    .samples(top_k as u64)
-                    .build()?,
+        let candidates = top_k * weights.candidates_per_result.max(1);
+        let mut chunks: HashMap<String, Chunk> = HashMap::new();
+
+        let mut vector_ranking = Vec::new();
+        if weights.vector > 0.0 {
+            for (id, chunk) in self.vector_search(query, candidates, filter).await? {
+                vector_ranking.push(id.clone());
+                chunks.insert(id, chunk);
+            }
+        }
+
+        let mut text_ranking = Vec::new();
+        if weights.text > 0.0 {
+            for (id, chunk) in self.full_text_search(query, candidates, filter).await? {
+                text_ranking.push(id.clone());
+                chunks.insert(id, chunk);
+            }
+        }
+
+        info!(
+            "Hybrid search: {} vector and {} full-text candidates",
+            vector_ranking.len(),
+            text_ranking.len()
+        );
+        Ok(reciprocal_rank_fusion(&[
+            (weights.vector, vector_ranking),
+            (weights.text, text_ranking),
+        ])
+        .into_iter()
+        .take(top_k)
+        .filter_map(|(id, score)| chunks.remove(&id).map(|chunk| (score, id, chunk)))
+        .collect())
+    }
+
+    /// Chunks closest to the query by cosine distance. Returns (chunk id, chunk), best first.
+    pub async fn vector_search(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &SourceFilter,
+    ) -> Result<Vec<(String, Chunk)>, anyhow::Error> {
+        let embedding = self.model.embed_text(query).await?;
+        let vector = pgvector::Vector::from(
+            embedding
+                .vec
+                .iter()
+                .map(|v| *v as f32)
+                .collect::<Vec<f32>>(),
+        );
+        let rows = bind_filter(
+            sqlx::query(&format!(
+                "SELECT d.id, d.document
+                FROM documents d
+                {}
+                ORDER BY d.embedding <=> $1, d.id
+                LIMIT $2",
+                SOURCE_FILTER
+            ))
+            .bind(vector)
+            .bind(limit as i64),
+            filter,
+        )
+        .fetch_all(&self.pg_pool)
+        .await?;
+        chunk_rows(rows)
+    }
+    /// Hybrid search for `top_k` chunks spread over sources. Candidates are over-fetched,
+    /// scored against `topic` by the re-ranker and picked by maximal marginal relevance
+    /// with at most `diversity.max_chunks_per_source` chunks of one source. Candidate
+    /// texts are embedded again, which the embedding cache answers.
+    pub async fn retrieve_chunks(
+        &self,
+        query: &str,
+        topic: &str,
+        top_k: usize,
+        weights: HybridWeights,
+        diversity: DiversityConfig,
+    ) -> Result<RetrievedChunks, anyhow::Error> {
+        let pool_size = top_k * weights.candidates_per_result.max(1);
+        let candidates = self
+            .search_chunks(
+                query,
+                pool_size,
+                HybridWeights {
+                    candidates_per_result: 1,
+                    ..weights
+                },
+                &SourceFilter::default(),
             )
             .await?;
-        Ok(results)
+        let reranker = self.reranker.name().to_string();
+        if candidates.is_empty() {
+            return Ok(RetrievedChunks {
+                reranker,
+                ..Default::default()
+            });
+        }
+        let mut candidates: Vec<RetrievedChunk> = candidates
+            .into_iter()
+            .map(|(score, id, chunk)| RetrievedChunk {
+                id,
+                score,
+                rerank_score: None,
+                chunk,
+            })
+            .collect();
+
+        let relevance = match self.reranker.rerank(topic, &candidates).await {
+            Ok(scores) if scores.len() == candidates.len() => scores,
+            Ok(scores) => {
+                warn!(
+                    "Re-ranker {} returned {} scores for {} chunks, keeping search order",
+                    reranker,
+                    scores.len(),
+                    candidates.len()
+                );
+                relative_scores(&candidates)
+            }
+            Err(e) => {
+                warn!("Re-ranker {} failed, keeping search order: {}", reranker, e);
+                relative_scores(&candidates)
+            }
+        };
+        for (candidate, relevance) in candidates.iter_mut().zip(&relevance) {
+            candidate.rerank_score = Some(*relevance);
+        }
+        info!("Re-ranked {} candidates with {}", candidates.len(), reranker);
+
+        let embeddings = self
+            .embed_texts(
+                candidates
+                    .iter()
+                    .map(|candidate| candidate.chunk.text.clone())
+                    .collect(),
+            )
+            .await?;
+        let sources: Vec<&str> = candidates
+            .iter()
+            .map(|RetrievedChunk { chunk, .. }| {
+                if chunk.source_id.is_empty() {
+                    chunk.source_url.as_str()
+                } else {
+                    chunk.source_id.as_str()
+                }
+            })
+            .collect();
+        let (selected, stats) =
+            select_diverse(&relevance, &embeddings, &sources, top_k, diversity);
+
+        let mut candidates: Vec<Option<RetrievedChunk>> =
+            candidates.into_iter().map(Some).collect();
+        let chunks = selected
+            .into_iter()
+            .filter_map(|index| candidates[index].take())
+            .collect();
+        Ok(RetrievedChunks {
+            chunks,
+            diversity: stats,
+            reranker,
+        })
+    }
+
+    /// Chunks matching the [`FullTextQuery`]: all of its identifiers, or any of its words
+    /// when there are none. Ranked by `ts_rank_cd` normalized by chunk length, BM25-style.
+    /// Returns (chunk id, chunk), best first.
+    pub async fn full_text_search(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &SourceFilter,
+    ) -> Result<Vec<(String, Chunk)>, anyhow::Error> {
+        let Some(FullTextQuery { words, identifiers }) = full_text_query(query) else {
+            return Ok(Vec::new());
+        };
+        // Without a query part its tsquery is NULL, which neither matches nor ranks
+        let matches = if identifiers.is_some() {
+            "d.embedded_tsv @@ i"
+        } else {
+            "d.embedded_tsv_stemmed @@ w"
+        };
+        let rows = bind_filter(
+            sqlx::query(&format!(
+                "SELECT d.id, d.document
+                FROM to_tsquery('russian', $1) w, to_tsquery('simple', $7) i, documents d
+                {}
+                  AND {}
+                ORDER BY COALESCE(ts_rank_cd(d.embedded_tsv_stemmed, w, 1), 0)
+                    + COALESCE(ts_rank_cd(d.embedded_tsv, i, 1), 0) DESC, d.id
+                LIMIT $2",
+                SOURCE_FILTER, matches
+            ))
+            .bind(words)
+            .bind(limit as i64),
+            filter,
+        )
+        .bind(identifiers)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        chunk_rows(rows)
+    }
+
+    /// Hybrid search with filters on the chunk's source, weighted as the query asks or
+    /// as configured by `ADCO_RETRIEVAL_*_WEIGHT`.
+    pub async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, anyhow::Error> {
+        let defaults = HybridWeights::from_env();
+        let weights = HybridWeights {
+            vector: query.vector_weight.unwrap_or(defaults.vector),
+            text: query.text_weight.unwrap_or(defaults.text),
+            ..defaults
+        };
+        let found = self
+            .search_chunks(&query.query, query.top_k(), weights, &query.into())
+            .await?;
+        let ids: Vec<Uuid> = found
+            .iter()
+            .filter_map(|(_, id, _)| id.parse().ok())
+            .collect();
+        let rows = sqlx::query(
+            "SELECT d.id, s.id AS source_id, s.source_type, s.fetched_at
+            FROM documents d
+            LEFT JOIN sources s ON s.id::TEXT = d.document->>'source_id'
+            WHERE d.id = ANY($1)",
+        )
+        .bind(&ids)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        let mut sources = HashMap::new();
+        for row in rows {
+            let id: Uuid = row.try_get("id")?;
+            let source_id: Option<Uuid> = row.try_get("source_id")?;
+            let source_type: Option<String> = row.try_get("source_type")?;
+            let fetched_at: Option<DateTime<Utc>> = row.try_get("fetched_at")?;
+            sources.insert(
+                id,
+                (source_id, source_type.map(SourceType::from), fetched_at),
+            );
+        }
+
+        found
+            .into_iter()
+            .map(|(score, id, chunk)| {
+                let chunk_id: Uuid = id.parse()?;
+                let (source_id, source_type, fetched_at) =
+                    sources.remove(&chunk_id).unwrap_or_default();
+                Ok(SearchResult {
+                    chunk_id,
+                    source_id,
+                    source_title: chunk.source_title,
+                    source_url: chunk.source_url,
+                    source_type,
+                    fetched_at,
+                    text: chunk.text,
+                    score,
+                })
+            })
+            .collect()
     }
 }
+
+fn bind_filter<'q>(
+    query: sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>,
+    filter: &SourceFilter,
+) -> sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments> {
+    query
+        .bind(filter.source_type.as_ref().map(|t| t.to_string()))
+        .bind(filter.source_id)
+        .bind(filter.fetched_after)
+        .bind(filter.fetched_before)
+}
+
+/// Reads (chunk id, chunk) from rows with `id` and `document`.
+fn chunk_rows(rows: Vec<sqlx::postgres::PgRow>) -> Result<Vec<(String, Chunk)>, anyhow::Error> {
+    rows.into_iter()
+        .map(|row| {
+            let id: Uuid = row.try_get("id")?;
+            let chunk: Chunk = serde_json::from_value(row.try_get("document")?)?;
+            Ok((id.to_string(), chunk))
+        })
+        .collect()
+}
@@ -0,0 +1,63 @@
+use super::embeddings_repository::NamedEmbeddingModel;
+use rig::embeddings::{Embedding, EmbeddingError, EmbeddingModel};
+
+/// Offline embedding model: hashes words into buckets, so texts sharing words get close vectors.
+/// Deterministic across runs, used for tests and runs without an embeddings API.
+#[derive(Debug, Clone)]
+pub struct FakeEmbeddingModel {
+    ndims: usize,
+}
+
+impl FakeEmbeddingModel {
+    pub fn new(ndims: usize) -> Self {
+        Self { ndims }
+    }
+
+    pub fn embed(&self, text: &str) -> Vec<f64> {
+        let mut vector = vec![0.0; self.ndims];
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+        {
+            let bucket = fnv1a(&word.to_lowercase()) as usize % self.ndims;
+            vector[bucket] += 1.0;
+        }
+        let norm = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            // Zero vectors have no cosine distance, point empty texts somewhere fixed
+            vector[0] = 1.0;
+            return vector;
+        }
+        vector.iter().map(|v| v / norm).collect()
+    }
+}
+
+fn fnv1a(word: &str) -> u64 {
+    word.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+impl NamedEmbeddingModel for FakeEmbeddingModel {
+    fn model_name(&self) -> &str {
+        "fake-embedding"
+    }
+}
+
+impl EmbeddingModel for FakeEmbeddingModel {
+    const MAX_DOCUMENTS: usize = 1024;
+
+    fn ndims(&self) -> usize {
+        self.ndims
+    }
+
+    async fn embed_texts(
+        &self,
+        texts: impl IntoIterator<Item = String> + Send,
+    ) -> Result<Vec<Embedding>, EmbeddingError> {
+        Ok(texts
+            .into_iter()
+            .map(|text| Embedding { vec: self.embed(&text), document: text })
+            .collect())
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Constant of reciprocal rank fusion, dampens the difference between the top ranks.
+pub const RRF_K: f64 = 60.0;
+
+/// How much each ranking counts when vector and full-text results are fused.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HybridWeights {
+    pub vector: f64,
+    pub text: f64,
+    /// Candidates taken from each ranking per requested result.
+    pub candidates_per_result: usize,
+}
+
+impl Default for HybridWeights {
+    fn default() -> Self {
+        Self {
+            vector: 1.0,
+            text: 1.0,
+            candidates_per_result: 4,
+        }
+    }
+}
+
+impl HybridWeights {
+    /// Cosine similarity only, as before hybrid retrieval.
+    pub fn vector_only() -> Self {
+        Self {
+            text: 0.0,
+            ..Self::default()
+        }
+    }
+
+    /// Reads `ADCO_RETRIEVAL_VECTOR_WEIGHT` and `ADCO_RETRIEVAL_TEXT_WEIGHT`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|weight| *weight >= 0.0)
+        };
+        Self {
+            vector: read("ADCO_RETRIEVAL_VECTOR_WEIGHT").unwrap_or(defaults.vector),
+            text: read("ADCO_RETRIEVAL_TEXT_WEIGHT").unwrap_or(defaults.text),
+            ..defaults
+        }
+    }
+}
+
+/// Sums `weight / (RRF_K + rank)` over the rankings an item appears in, ranks start at 1.
+/// Best first; ties keep the order in which items were first seen.
+pub fn reciprocal_rank_fusion<T: Clone + Eq + Hash>(rankings: &[(f64, Vec<T>)]) -> Vec<(T, f64)> {
+    let mut scores: HashMap<T, (f64, usize)> = HashMap::new();
+    for (weight, ranking) in rankings {
+        if *weight <= 0.0 {
+            continue;
+        }
+        for (index, item) in ranking.iter().enumerate() {
+            let seen = scores.len();
+            let entry = scores.entry(item.clone()).or_insert((0.0, seen));
+            entry.0 += weight / (RRF_K + (index + 1) as f64);
+        }
+    }
+    let mut fused: Vec<(T, f64, usize)> = scores
+        .into_iter()
+        .map(|(item, (score, seen))| (item, score, seen))
+        .collect();
+    fused.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.2.cmp(&b.2)));
+    fused
+        .into_iter()
+        .map(|(item, score, _)| (item, score))
+        .collect()
+}
+
+/// Terms of a full-text query, split by how they are looked up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FullTextQuery {
+    /// `to_tsquery('russian', ...)` input with the ordinary words, any of them matches.
+    /// The configuration stems Russian and English words and drops their stop words.
+    pub words: Option<String>,
+    /// `to_tsquery('simple', ...)` input with the identifier-like terms, looked up as
+    /// written. A chunk has to contain all of them.
+    pub identifiers: Option<String>,
+}
+
+/// Splits a query into words and identifier-like terms: those with digits, `.`, `-` or
+/// `_`, such as "gpt-4o" and "2401.12345". None without any terms.
+pub fn full_text_query(query: &str) -> Option<FullTextQuery> {
+    let mut words: Vec<String> = Vec::new();
+    let mut identifiers: Vec<String> = Vec::new();
+    for term in query.split(|c: char| !(c.is_alphanumeric() || matches!(c, '.' | '-' | '_'))) {
+        let term = term
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        if term.is_empty() {
+            continue;
+        }
+        let terms = if term.contains(|c: char| c.is_ascii_digit() || matches!(c, '.' | '-' | '_')) {
+            &mut identifiers
+        } else {
+            &mut words
+        };
+        if !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    let join = |terms: Vec<String>, separator: &str| {
+        (!terms.is_empty()).then(|| {
+            terms
+                .iter()
+                .map(|term| format!("'{}'", term))
+                .collect::<Vec<_>>()
+                .join(separator)
+        })
+    };
+    let query = FullTextQuery {
+        words: join(words, " | "),
+        identifiers: join(identifiers, " & "),
+    };
+    (query.words.is_some() || query.identifiers.is_some()).then_some(query)
+}
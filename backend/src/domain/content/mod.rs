@@ -1,5 +1,19 @@
 pub mod chunker;
+pub mod diversity;
+pub mod embedding_cache;
 pub mod embeddings_repository;
+pub mod fake_embedding;
+pub mod hybrid_search;
+pub mod novelty;
+pub mod rerank;
+pub mod tokenizer;
 
 pub use chunker::*;
-pub use embeddings_repository::*;
\ No newline at end of file
+pub use diversity::*;
+pub use embedding_cache::*;
+pub use embeddings_repository::*;
+pub use fake_embedding::*;
+pub use hybrid_search::*;
+pub use novelty::*;
+pub use rerank::*;
+pub use tokenizer::*;
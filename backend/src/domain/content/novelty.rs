@@ -0,0 +1,137 @@
+use crate::domain::content::embeddings_repository::{EmbeddingsRepository, NamedEmbeddingModel};
+use crate::domain::infra::postgres::posts_repo::PostsRepositoryPostgres;
+use crate::domain::posts::PostRepository;
+use sqlx::PgPool;
+use std::future::Future;
+use std::pin::Pin;
+use tracing::info;
+use uuid::Uuid;
+
+/// A past post topic and how close it is to the compared text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarTopic {
+    pub post_id: Uuid,
+    pub topic: String,
+    /// Cosine similarity, 1 for the same direction.
+    pub similarity: f64,
+}
+
+pub type TopicHistoryFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<SimilarTopic>, anyhow::Error>> + Send + 'a>>;
+
+/// Topics the channel already has, searchable by meaning.
+pub trait TopicHistory: Send + Sync {
+    /// Up to `limit` past topics closest to `text`, most similar first.
+    fn nearest_topics<'a>(&'a self, text: &'a str, limit: usize) -> TopicHistoryFuture<'a>;
+}
+
+/// When a generated topic counts as a repeat and how hard to try for a new one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TopicNoveltyConfig {
+    /// Topics at least this similar to a past one are regenerated.
+    pub threshold: f64,
+    /// How many nearest past topics are put into the prompt to avoid.
+    pub avoid_count: usize,
+    /// Generations per topic before giving up.
+    pub max_attempts: usize,
+}
+
+impl Default for TopicNoveltyConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.85,
+            avoid_count: 5,
+            max_attempts: 3,
+        }
+    }
+}
+
+impl TopicNoveltyConfig {
+    /// Never treats a topic as a repeat, past topics are still listed in the prompt.
+    pub fn disabled() -> Self {
+        Self {
+            threshold: f64::INFINITY,
+            ..Self::default()
+        }
+    }
+
+    /// Reads `ADCO_TOPIC_SIMILARITY_THRESHOLD`, `ADCO_TOPIC_AVOID_COUNT` and
+    /// `ADCO_TOPIC_MAX_ATTEMPTS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |name: &str| std::env::var(name).ok();
+        Self {
+            threshold: read("ADCO_TOPIC_SIMILARITY_THRESHOLD")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.threshold),
+            avoid_count: read("ADCO_TOPIC_AVOID_COUNT")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.avoid_count),
+            max_attempts: read("ADCO_TOPIC_MAX_ATTEMPTS")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_attempts)
+                .max(1),
+        }
+    }
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (*x as f64, *y as f64);
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Topics of the rows in `posts`. Their embeddings are computed on first use and stored
+/// in the row, so each topic is embedded once per embedding model; the nearest ones are
+/// found by pgvector.
+pub struct PostTopicHistory<M: NamedEmbeddingModel> {
+    pg_pool: PgPool,
+    embeddings: EmbeddingsRepository<M>,
+}
+
+impl<M: NamedEmbeddingModel> PostTopicHistory<M> {
+    pub fn new(pg_pool: &PgPool, model: M) -> Self {
+        Self {
+            pg_pool: pg_pool.clone(),
+            embeddings: EmbeddingsRepository::with_model(pg_pool, model),
+        }
+    }
+
+    async fn nearest(&self, text: &str, limit: usize) -> Result<Vec<SimilarTopic>, anyhow::Error> {
+        let post_repo = PostsRepositoryPostgres::new(&self.pg_pool);
+        let model = self.embeddings.model_name().to_string();
+        let missing = post_repo.get_unembedded_post_topics(&model).await?;
+        if !missing.is_empty() {
+            info!("Embedding {} past post topics", missing.len());
+            let texts = missing.iter().map(|t| t.topic.clone()).collect();
+            let vectors = self.embeddings.embed_texts(texts).await?;
+            for (topic, vector) in missing.iter().zip(vectors) {
+                post_repo
+                    .save_topic_embedding(topic.post_id, &model, &vector)
+                    .await?;
+            }
+        }
+
+        let query = self
+            .embeddings
+            .embed_texts(vec![text.to_string()])
+            .await?
+            .pop()
+            .unwrap_or_default();
+        Ok(post_repo.nearest_post_topics(&query, &model, limit).await?)
+    }
+}
+
+impl<M: NamedEmbeddingModel + 'static> TopicHistory for PostTopicHistory<M> {
+    fn nearest_topics<'a>(&'a self, text: &'a str, limit: usize) -> TopicHistoryFuture<'a> {
+        Box::pin(self.nearest(text, limit))
+    }
+}
@@ -0,0 +1,40 @@
+use crate::domain::content::diversity::RetrievedChunk;
+use std::future::Future;
+use std::pin::Pin;
+
+pub type RerankFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<f64>, anyhow::Error>> + Send + 'a>>;
+
+/// Re-scores chunks found by search against the topic of the post.
+pub trait Reranker: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Relevance of each candidate to `topic`, from 0 to 1, in the order of `candidates`.
+    fn rerank<'a>(&'a self, topic: &'a str, candidates: &'a [RetrievedChunk]) -> RerankFuture<'a>;
+}
+
+/// Keeps the search order, see [`relative_scores`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopReranker;
+
+impl Reranker for NoopReranker {
+    fn name(&self) -> &str {
+        "noop"
+    }
+
+    fn rerank<'a>(&'a self, _topic: &'a str, candidates: &'a [RetrievedChunk]) -> RerankFuture<'a> {
+        Box::pin(async move { Ok(relative_scores(candidates)) })
+    }
+}
+
+/// Search scores divided by the best one, so the top candidate gets 1.
+pub fn relative_scores(candidates: &[RetrievedChunk]) -> Vec<f64> {
+    let best = candidates
+        .iter()
+        .map(|candidate| candidate.score)
+        .fold(f64::MIN_POSITIVE, f64::max);
+    candidates
+        .iter()
+        .map(|candidate| candidate.score / best)
+        .collect()
+}
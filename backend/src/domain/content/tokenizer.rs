@@ -0,0 +1,176 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::{info, warn};
+
+/// cl100k pre-tokenization, without the `\s+(?!\S)` lookahead the regex crate lacks;
+/// [`BpeTokenizer::pieces`] gives the last whitespace character back instead.
+const CL100K_PATTERN: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+";
+
+static CL100K_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(CL100K_PATTERN).unwrap());
+
+/// Tokenizer of `ADCO_TOKENIZER_VOCAB`, loaded once; estimates when it is not set.
+static TOKEN_COUNTER: Lazy<Arc<dyn TokenCounter>> = Lazy::new(|| {
+    let Ok(path) = std::env::var("ADCO_TOKENIZER_VOCAB") else {
+        info!("ADCO_TOKENIZER_VOCAB is not set, token counts are estimated");
+        return Arc::new(EstimatedTokenCounter);
+    };
+    match BpeTokenizer::load(&path) {
+        Ok(tokenizer) => Arc::new(tokenizer),
+        Err(e) => {
+            warn!(
+                "Failed to load tokenizer vocabulary, token counts are estimated: {}",
+                e
+            );
+            Arc::new(EstimatedTokenCounter)
+        }
+    }
+});
+
+pub trait TokenCounter: Send + Sync {
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+/// The counter configured for this process, see [`TOKEN_COUNTER`].
+pub fn token_counter() -> Arc<dyn TokenCounter> {
+    TOKEN_COUNTER.clone()
+}
+
+#[derive(Error, Debug)]
+pub enum TokenizerError {
+    #[error("Failed to read vocabulary: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid vocabulary line {line}: {reason}")]
+    InvalidLine { line: usize, reason: String },
+}
+
+/// About 4 characters per token, as OpenAI suggests for English text.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EstimatedTokenCounter;
+
+impl TokenCounter for EstimatedTokenCounter {
+    fn count_tokens(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(4)
+    }
+}
+
+/// Byte-level BPE over a tiktoken vocabulary (`<base64 token> <rank>` per line), such as
+/// `cl100k_base.tiktoken` of the OpenAI embedding models. Only counts tokens.
+#[derive(Debug, Clone)]
+pub struct BpeTokenizer {
+    ranks: HashMap<Vec<u8>, u32>,
+}
+
+impl BpeTokenizer {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TokenizerError> {
+        let tokenizer = Self::parse(&std::fs::read_to_string(&path)?)?;
+        info!(
+            "Loaded {} tokens from {}",
+            tokenizer.ranks.len(),
+            path.as_ref().display()
+        );
+        Ok(tokenizer)
+    }
+
+    pub fn parse(vocabulary: &str) -> Result<Self, TokenizerError> {
+        let mut ranks = HashMap::new();
+        for (index, line) in vocabulary.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = |reason: String| TokenizerError::InvalidLine {
+                line: index + 1,
+                reason,
+            };
+            let (token, rank) = line
+                .split_once(' ')
+                .ok_or_else(|| invalid("expected '<token> <rank>'".to_string()))?;
+            let token = STANDARD.decode(token).map_err(|e| invalid(e.to_string()))?;
+            let rank = rank
+                .trim()
+                .parse::<u32>()
+                .map_err(|e| invalid(e.to_string()))?;
+            ranks.insert(token, rank);
+        }
+        Ok(Self { ranks })
+    }
+
+    /// Pre-tokenized pieces of the text; BPE never merges across them.
+    fn pieces<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let mut pieces = Vec::new();
+        let mut at = 0;
+        while let Some(found) = CL100K_REGEX.find_at(text, at) {
+            let mut end = found.end();
+            let piece = found.as_str();
+            // `\s+(?!\S)`: a run of spaces before a word leaves its last one to the word
+            if end < text.len()
+                && piece.chars().all(char::is_whitespace)
+                && !piece.ends_with(['\r', '\n'])
+            {
+                if let Some((last, _)) = piece.char_indices().last().filter(|(i, _)| *i > 0) {
+                    end = found.start() + last;
+                }
+            }
+            pieces.push(&text[found.start()..end]);
+            at = end;
+        }
+        pieces
+    }
+
+    /// Merges the adjacent pair with the lowest rank until no pair is in the vocabulary.
+    fn count_piece(&self, piece: &[u8]) -> usize {
+        if piece.len() < 2 || self.ranks.contains_key(piece) {
+            return piece.len().min(1);
+        }
+        let mut parts: Vec<usize> = (0..=piece.len()).collect();
+        while parts.len() > 2 {
+            let best = (0..parts.len() - 2)
+                .filter_map(|i| {
+                    self.ranks
+                        .get(&piece[parts[i]..parts[i + 2]])
+                        .map(|rank| (*rank, i))
+                })
+                .min();
+            match best {
+                Some((_, i)) => {
+                    parts.remove(i + 1);
+                }
+                None => break,
+            }
+        }
+        parts.len() - 1
+    }
+}
+
+impl TokenCounter for BpeTokenizer {
+    fn count_tokens(&self, text: &str) -> usize {
+        self.pieces(text)
+            .into_iter()
+            .map(|piece| self.count_piece(piece.as_bytes()))
+            .sum()
+    }
+}
+
+/// Keeps the texts, in order, whose tokens fit into `max_tokens` together; a text that
+/// does not fit is skipped and smaller ones after it may still be taken.
+/// Returns the indexes of the kept texts.
+pub fn fit_to_budget(
+    counter: &dyn TokenCounter,
+    texts: &[String],
+    max_tokens: usize,
+) -> Vec<usize> {
+    let mut used = 0;
+    let mut kept = Vec::new();
+    for (index, text) in texts.iter().enumerate() {
+        let tokens = counter.count_tokens(text);
+        if used + tokens <= max_tokens {
+            used += tokens;
+            kept.push(index);
+        }
+    }
+    kept
+}
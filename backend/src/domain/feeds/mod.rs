@@ -0,0 +1,7 @@
+pub mod parser;
+pub mod poller;
+pub mod repository;
+
+pub use parser::*;
+pub use poller::*;
+pub use repository::*;
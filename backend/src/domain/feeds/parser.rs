@@ -0,0 +1,191 @@
+use chrono::{DateTime, Utc};
+use quick_xml::de::from_str;
+use scraper::{Html, Node};
+use serde::Deserialize;
+
+/// Feed contents, the same for RSS 2.0 and Atom.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedFeed {
+    pub title: Option<String>,
+    pub items: Vec<FeedItem>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedItem {
+    /// RSS `guid` or Atom `id`, the link when the feed has neither.
+    pub guid: String,
+    pub url: String,
+    pub title: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    /// Plain text of the description or content, used when the page can't be scraped.
+    pub summary: Option<String>,
+}
+
+/// Parses an RSS 2.0 or Atom document. Items without a link are skipped.
+pub fn parse_feed(xml: &str) -> Result<ParsedFeed, anyhow::Error> {
+    let root = root_element(xml).ok_or_else(|| anyhow::anyhow!("Feed has no root element"))?;
+    match root.as_str() {
+        "rss" => {
+            let rss: Rss = from_str(xml)?;
+            Ok(ParsedFeed {
+                title: non_empty(rss.channel.title),
+                items: rss
+                    .channel
+                    .items
+                    .into_iter()
+                    .filter_map(RssItem::into_item)
+                    .collect(),
+            })
+        }
+        "feed" => {
+            let atom: Atom = from_str(xml)?;
+            Ok(ParsedFeed {
+                title: atom.title.and_then(|t| non_empty(Some(t.text))),
+                items: atom
+                    .entries
+                    .into_iter()
+                    .filter_map(AtomEntry::into_item)
+                    .collect(),
+            })
+        }
+        other => Err(anyhow::anyhow!("Unsupported feed format <{}>", other)),
+    }
+}
+
+/// Local name of the first element, skipping the declaration, comments and doctype.
+fn root_element(xml: &str) -> Option<String> {
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        if rest.starts_with('?') || rest.starts_with('!') {
+            continue;
+        }
+        let name: String = rest
+            .chars()
+            .take_while(|c| !c.is_whitespace() && *c != '>' && *c != '/')
+            .collect();
+        return Some(name.rsplit(':').next().unwrap_or_default().to_string());
+    }
+    None
+}
+
+fn non_empty(text: Option<String>) -> Option<String> {
+    text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+const BLOCK_ELEMENTS: &[&str] = &["p", "br", "div", "li", "h1", "h2", "h3", "h4", "h5", "h6"];
+
+/// Descriptions are usually HTML, keep only the text.
+fn html_to_text(html: Option<String>) -> Option<String> {
+    let html = non_empty(html)?;
+    let fragment = Html::parse_fragment(&html);
+    let mut text = String::new();
+    for node in fragment.root_element().descendants() {
+        match node.value() {
+            Node::Text(t) => text.push_str(t),
+            // Block elements separate words that have no whitespace between them
+            Node::Element(e) if BLOCK_ELEMENTS.contains(&e.name()) => text.push(' '),
+            _ => {}
+        }
+    }
+    non_empty(Some(text.split_whitespace().collect::<Vec<_>>().join(" ")))
+}
+
+#[derive(Debug, Deserialize)]
+struct Rss {
+    channel: RssChannel,
+}
+
+#[derive(Debug, Deserialize)]
+struct RssChannel {
+    title: Option<String>,
+    #[serde(rename = "item", default)]
+    items: Vec<RssItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RssItem {
+    title: Option<String>,
+    link: Option<String>,
+    guid: Option<Text>,
+    #[serde(rename = "pubDate")]
+    pub_date: Option<String>,
+    description: Option<String>,
+    #[serde(rename = "encoded")]
+    content: Option<String>,
+}
+
+impl RssItem {
+    fn into_item(self) -> Option<FeedItem> {
+        let url = non_empty(self.link)?;
+        Some(FeedItem {
+            guid: self
+                .guid
+                .and_then(|g| non_empty(Some(g.text)))
+                .unwrap_or_else(|| url.clone()),
+            title: html_to_text(self.title),
+            published_at: self
+                .pub_date
+                .and_then(|d| DateTime::parse_from_rfc2822(d.trim()).ok())
+                .map(|d| d.with_timezone(&Utc)),
+            summary: html_to_text(self.content.or(self.description)),
+            url,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Atom {
+    title: Option<Text>,
+    #[serde(rename = "entry", default)]
+    entries: Vec<AtomEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AtomEntry {
+    id: Option<String>,
+    title: Option<Text>,
+    #[serde(rename = "link", default)]
+    links: Vec<AtomLink>,
+    published: Option<String>,
+    updated: Option<String>,
+    summary: Option<Text>,
+    content: Option<Text>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AtomLink {
+    #[serde(rename = "@href")]
+    href: String,
+    #[serde(rename = "@rel")]
+    rel: Option<String>,
+}
+
+impl AtomEntry {
+    fn into_item(self) -> Option<FeedItem> {
+        // rel defaults to "alternate", the link to the entry's page
+        let url = self
+            .links
+            .into_iter()
+            .find(|link| link.rel.as_deref().is_none_or(|rel| rel == "alternate"))
+            .and_then(|link| non_empty(Some(link.href)))?;
+        Some(FeedItem {
+            guid: non_empty(self.id).unwrap_or_else(|| url.clone()),
+            title: html_to_text(self.title.map(|t| t.text)),
+            published_at: self
+                .published
+                .or(self.updated)
+                .and_then(|d| DateTime::parse_from_rfc3339(d.trim()).ok())
+                .map(|d| d.with_timezone(&Utc)),
+            summary: html_to_text(self.content.or(self.summary).map(|t| t.text)),
+            url,
+        })
+    }
+}
+
+/// Element with attributes (`type="html"`, `isPermaLink`) of which only the text is used.
+#[derive(Debug, Deserialize)]
+struct Text {
+    #[serde(rename = "$text", default)]
+    text: String,
+}
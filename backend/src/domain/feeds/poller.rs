@@ -0,0 +1,250 @@
+use super::parser::{parse_feed, FeedItem};
+use super::repository::FeedRepository;
+use crate::domain::content::{EmbeddingsRepository, NamedEmbeddingModel};
+use crate::domain::infra::http_client::ResilientHttpClient;
+use crate::domain::infra::postgres::{
+    feeds_repo::FeedRepositoryPostgres, sources_repo::SourcesRepositoryImpl,
+};
+use crate::domain::sources::{HtmlParser, SourceRepository};
+use adco_shared::feed::Feed;
+use adco_shared::source::{Source, SourceType};
+use chrono::{DateTime, Utc};
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use rig::providers::openai;
+use serde::Serialize;
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// New items scraped per poll; the rest are picked up by the next polls.
+const MAX_ITEMS_PER_POLL: usize = 20;
+
+/// Result of a conditional feed request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedFetch {
+    /// 304, nothing changed since the validators were saved.
+    NotModified,
+    Fetched {
+        body: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+/// Downloads feeds, sending the saved `ETag`/`Last-Modified` back.
+#[derive(Debug, Clone, Default)]
+pub struct FeedFetcher {
+    client: ResilientHttpClient,
+}
+
+impl FeedFetcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn fetch(&self, feed: &Feed) -> Result<FeedFetch, anyhow::Error> {
+        let mut headers = HeaderMap::new();
+        if let Some(etag) = feed
+            .etag
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+        if let Some(modified) = feed
+            .last_modified
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(IF_MODIFIED_SINCE, modified);
+        }
+        let response = self.client.get_with_headers(&feed.url, headers).await?;
+        match response.status {
+            304 => Ok(FeedFetch::NotModified),
+            200 => {
+                let header = |name| {
+                    response
+                        .headers
+                        .get(name)
+                        .and_then(|v: &HeaderValue| v.to_str().ok())
+                        .map(str::to_string)
+                };
+                let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+                Ok(FeedFetch::Fetched {
+                    body: response.text(),
+                    etag,
+                    last_modified,
+                })
+            }
+            status => Err(anyhow::anyhow!("Status code: {}", status)),
+        }
+    }
+}
+
+/// What one poll of a feed did.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FeedPollReport {
+    pub feed_id: Uuid,
+    pub not_modified: bool,
+    /// Items not seen before, including the ones left for the next poll.
+    pub new_items: usize,
+    /// Items stored as sources with chunks.
+    pub stored: usize,
+    /// Items that could not be stored: not scraped with no text in the feed, or failed
+    /// to save or index. They are recorded without a source and not retried.
+    pub failed: usize,
+}
+
+/// Polls feeds and turns their new items into RSS sources with embedded chunks.
+pub struct FeedPoller<M: NamedEmbeddingModel = openai::EmbeddingModel> {
+    pg_pool: PgPool,
+    fetcher: FeedFetcher,
+    embeddings: EmbeddingsRepository<M>,
+}
+
+impl FeedPoller {
+    pub fn new(pg_pool: &PgPool) -> Self {
+        Self::with_model(pg_pool, EmbeddingsRepository::default_model())
+    }
+}
+
+impl<M: NamedEmbeddingModel> FeedPoller<M> {
+    pub fn with_model(pg_pool: &PgPool, model: M) -> Self {
+        Self {
+            pg_pool: pg_pool.clone(),
+            fetcher: FeedFetcher::new(),
+            embeddings: EmbeddingsRepository::with_model(pg_pool, model),
+        }
+    }
+
+    /// Polls every feed whose interval has passed; a failing feed doesn't stop the others.
+    pub async fn poll_due(&self, now: DateTime<Utc>) -> Result<Vec<FeedPollReport>, anyhow::Error> {
+        let feeds = FeedRepositoryPostgres::new(&self.pg_pool)
+            .list_feeds()
+            .await?;
+        let mut reports = Vec::new();
+        for mut feed in feeds.into_iter().filter(|feed| feed.is_due(now)) {
+            match self.poll_feed(&mut feed).await {
+                Ok(report) => reports.push(report),
+                Err(e) => error!("Polling feed {} failed: {:?}", feed.url, e),
+            }
+        }
+        Ok(reports)
+    }
+
+    /// Polls one feed now and saves the poll time, validators and error on it.
+    pub async fn poll_feed(&self, feed: &mut Feed) -> Result<FeedPollReport, anyhow::Error> {
+        let feed_id = feed
+            .id
+            .ok_or_else(|| anyhow::anyhow!("Feed is not saved"))?;
+        let result = self.fetch_and_store(feed_id, feed).await;
+        feed.last_polled_at = Some(Utc::now());
+        feed.last_error = result.as_ref().err().map(|e| e.to_string());
+        FeedRepositoryPostgres::new(&self.pg_pool)
+            .update_feed(feed)
+            .await?;
+        result
+    }
+
+    async fn fetch_and_store(
+        &self,
+        feed_id: Uuid,
+        feed: &mut Feed,
+    ) -> Result<FeedPollReport, anyhow::Error> {
+        let mut report = FeedPollReport {
+            feed_id,
+            ..FeedPollReport::default()
+        };
+        let (body, etag, last_modified) = match self.fetcher.fetch(feed).await? {
+            FeedFetch::NotModified => {
+                info!("Feed {} not modified", feed.url);
+                report.not_modified = true;
+                return Ok(report);
+            }
+            FeedFetch::Fetched {
+                body,
+                etag,
+                last_modified,
+            } => (body, etag, last_modified),
+        };
+        let parsed = parse_feed(&body)?;
+        if feed.title.is_none() {
+            feed.title = parsed.title;
+        }
+
+        let feed_repo = FeedRepositoryPostgres::new(&self.pg_pool);
+        let guids: Vec<String> = parsed.items.iter().map(|item| item.guid.clone()).collect();
+        let new_guids = feed_repo.get_new_guids(feed_id, &guids).await?;
+        let new_items: Vec<&FeedItem> = parsed
+            .items
+            .iter()
+            .filter(|item| new_guids.contains(&item.guid))
+            .collect();
+        report.new_items = new_items.len();
+        info!("Feed {}: {} new items", feed.url, new_items.len());
+        let left_over = new_items.len() > MAX_ITEMS_PER_POLL;
+
+        for item in new_items.into_iter().take(MAX_ITEMS_PER_POLL) {
+            // A failed item is recorded without a source, so it doesn't stop the poll
+            let source_id = match self.store_item(item).await {
+                Ok(source_id) => source_id,
+                Err(e) => {
+                    warn!("Failed to store feed item {}: {:?}", item.url, e);
+                    None
+                }
+            };
+            feed_repo.record_item(feed_id, item, source_id).await?;
+            match source_id {
+                Some(_) => report.stored += 1,
+                None => report.failed += 1,
+            }
+        }
+        // Saved only once every item is recorded; with items left over they are not
+        // saved at all, so the next poll gets the rest
+        if !left_over {
+            feed.etag = etag;
+            feed.last_modified = last_modified;
+        }
+        Ok(report)
+    }
+
+    /// Scrapes the item's page, falling back to the text in the feed.
+    async fn store_item(&self, item: &FeedItem) -> Result<Option<Uuid>, anyhow::Error> {
+        let scraped = HtmlParser::new()
+            .scrap_source_from_url(&item.url, item.title.as_deref())
+            .await;
+        let mut source = match (scraped, &item.summary) {
+            (Ok(source), _) if !source.raw_text.trim().is_empty() => source,
+            (result, Some(summary)) => {
+                if let Err(e) = result {
+                    warn!("Failed to scrape {}, using the feed text: {}", item.url, e);
+                }
+                Source::new(
+                    item.url.clone(),
+                    item.title.clone().unwrap_or_else(|| item.url.clone()),
+                    SourceType::RSS,
+                    summary.clone(),
+                )
+            }
+            (result, None) => {
+                if let Err(e) = result {
+                    warn!("Failed to scrape {}: {}", item.url, e);
+                }
+                return Ok(None);
+            }
+        };
+        source.source_type = SourceType::RSS;
+        let sources_repo = SourcesRepositoryImpl::new(&self.pg_pool);
+        let upsert = sources_repo.upsert_source(&source).await?;
+        source.id = Some(upsert.id());
+        if !upsert.needs_indexing() {
+            info!("Feed item {} is already stored as source {}", item.url, upsert.id());
+            return Ok(Some(upsert.id()));
+        }
+        let chunks = self.embeddings.index_source(&source).await?;
+        info!("Stored feed item {} with {} chunks", item.url, chunks);
+        Ok(Some(upsert.id()))
+    }
+}
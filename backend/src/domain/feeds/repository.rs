@@ -0,0 +1,34 @@
+use super::parser::FeedItem;
+use adco_shared::feed::Feed;
+use thiserror::Error;
+use uuid::Uuid;
+
+pub trait FeedRepository {
+    async fn list_feeds(&self) -> Result<Vec<Feed>, FeedRepositoryError>;
+    async fn get_feed_by_id(&self, id: Uuid) -> Result<Option<Feed>, FeedRepositoryError>;
+    async fn create_feed(&self, feed: &Feed) -> Result<Uuid, FeedRepositoryError>;
+    async fn update_feed(&self, feed: &Feed) -> Result<(), FeedRepositoryError>;
+    /// Deletes the feed and its seen items, the sources made from them stay.
+    async fn delete_feed(&self, id: Uuid) -> Result<(), FeedRepositoryError>;
+    /// The `guids` that have not been seen in the feed yet, in the given order.
+    async fn get_new_guids(
+        &self,
+        feed_id: Uuid,
+        guids: &[String],
+    ) -> Result<Vec<String>, FeedRepositoryError>;
+    /// Marks the item as seen; `source_id` is `None` when nothing could be stored.
+    async fn record_item(
+        &self,
+        feed_id: Uuid,
+        item: &FeedItem,
+        source_id: Option<Uuid>,
+    ) -> Result<(), FeedRepositoryError>;
+}
+
+#[derive(Error, Debug)]
+pub enum FeedRepositoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error("Feed not found: {id}")]
+    NotFound { id: Uuid },
+}
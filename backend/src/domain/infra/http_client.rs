@@ -0,0 +1,130 @@
+use super::resilience::{
+    acquire_host_slot, host_of, parse_retry_after, retry_decision_for_status, with_retry,
+    RetryDecision, RetryPolicy, TimeoutError,
+};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use thiserror::Error;
+
+/// reqwest client with retries, backoff, `Retry-After` support and per-host concurrency limits.
+#[derive(Debug, Clone)]
+pub struct ResilientHttpClient {
+    client: reqwest::Client,
+    policy: RetryPolicy,
+}
+
+/// A response read to the end. The body is read within the attempt, so slow bodies
+/// are covered by the timeouts and the host slot and are retried like slow requests.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Body as UTF-8, invalid sequences replaced.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.body)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum HttpCallError {
+    #[error("Request error: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Status code: {status}")]
+    Status {
+        status: u16,
+        retry_after: Option<Duration>,
+    },
+    #[error(transparent)]
+    Timeout(#[from] TimeoutError),
+}
+
+impl ResilientHttpClient {
+    pub fn new() -> Self {
+        Self::with_policy(RetryPolicy::from_env())
+    }
+
+    pub fn with_policy(policy: RetryPolicy) -> Self {
+        // The attempt timeout of the policy also bounds requests made without `with_retry`
+        let client = reqwest::Client::builder()
+            .timeout(policy.attempt_timeout)
+            .connect_timeout(policy.attempt_timeout.min(Duration::from_secs(30)))
+            .build()
+            .expect("HTTP client settings are valid");
+        Self { client, policy }
+    }
+
+    /// GET with retries. Transient statuses (429, 5xx) are retried, other
+    /// responses are returned as is for the caller to check.
+    pub async fn get(&self, url: &str) -> Result<HttpResponse, HttpCallError> {
+        self.get_with_headers(url, HeaderMap::new()).await
+    }
+
+    /// [`get`](Self::get) with extra request headers, e.g. for conditional requests.
+    pub async fn get_with_headers(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<HttpResponse, HttpCallError> {
+        let host = host_of(url);
+        with_retry(&self.policy, &format!("GET {}", url), classify, || async {
+            let _slot = acquire_host_slot(&host).await;
+            let response = self
+                .client
+                .get(url)
+                .headers(headers.clone())
+                .send()
+                .await?;
+            let status = response.status().as_u16();
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after);
+            if let RetryDecision::Retry { .. } = retry_decision_for_status(status, retry_after) {
+                return Err(HttpCallError::Status {
+                    status,
+                    retry_after,
+                });
+            }
+            let headers = response.headers().clone();
+            let body = response.bytes().await?.to_vec();
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+        .await
+    }
+}
+
+impl Default for ResilientHttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn classify(error: &HttpCallError) -> RetryDecision {
+    match error {
+        HttpCallError::Request(e)
+            if e.is_timeout() || e.is_connect() || e.is_request() || e.is_body() =>
+        {
+            RetryDecision::Retry { after: None }
+        }
+        HttpCallError::Status {
+            status,
+            retry_after,
+        } => retry_decision_for_status(*status, *retry_after),
+        HttpCallError::Timeout(_) => RetryDecision::Retry { after: None },
+        _ => RetryDecision::Fail,
+    }
+}
@@ -1 +1,3 @@
+pub mod http_client;
 pub mod postgres;
+pub mod resilience;
@@ -0,0 +1,50 @@
+use sqlx::pool::PoolConnection;
+use sqlx::{PgPool, Postgres};
+use tracing::warn;
+
+/// Session-level Postgres advisory lock, held on a dedicated pool connection.
+///
+/// Locks are per-session, so the connection stays checked out while the lock is held.
+/// Dropping the lock without `release` closes the connection, which frees the lock too.
+pub struct AdvisoryLock {
+    key: i64,
+    connection: Option<PoolConnection<Postgres>>,
+}
+
+impl AdvisoryLock {
+    /// Returns `None` when another session already holds the lock.
+    pub async fn try_acquire(pool: &PgPool, key: i64) -> Result<Option<Self>, sqlx::Error> {
+        let mut connection = pool.acquire().await?;
+        let result = sqlx::query!(r#"SELECT pg_try_advisory_lock($1) AS "locked!""#, key)
+            .fetch_one(&mut *connection)
+            .await?;
+        if !result.locked {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            key,
+            connection: Some(connection),
+        }))
+    }
+
+    pub async fn release(mut self) -> Result<(), sqlx::Error> {
+        if let Some(mut connection) = self.connection.take() {
+            sqlx::query!("SELECT pg_advisory_unlock($1)", self.key)
+                .fetch_one(&mut *connection)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for AdvisoryLock {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            warn!(
+                "Advisory lock {} dropped without release, closing its connection",
+                self.key
+            );
+            drop(connection.detach());
+        }
+    }
+}
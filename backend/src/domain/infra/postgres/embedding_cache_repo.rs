@@ -0,0 +1,53 @@
+use crate::domain::content::embedding_cache::{EmbeddingCacheError, EmbeddingCacheRepository};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct EmbeddingCacheRepositoryPostgres {
+    pool: PgPool,
+}
+
+impl EmbeddingCacheRepositoryPostgres {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl EmbeddingCacheRepository for EmbeddingCacheRepositoryPostgres {
+    async fn get_embeddings(
+        &self,
+        keys: &[String],
+    ) -> Result<HashMap<String, Vec<f32>>, EmbeddingCacheError> {
+        if keys.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let result = sqlx::query!(
+            "UPDATE embedding_cache SET last_used_at = now() WHERE key = ANY($1) RETURNING key, embedding",
+            keys,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(result.into_iter().map(|r| (r.key, r.embedding)).collect())
+    }
+
+    async fn save_embeddings(
+        &self,
+        model: &str,
+        entries: &[(String, Vec<f32>)],
+    ) -> Result<(), EmbeddingCacheError> {
+        let mut tx = self.pool.begin().await?;
+        for (key, embedding) in entries {
+            sqlx::query!(
+                "INSERT INTO embedding_cache (key, model, embedding) VALUES ($1, $2, $3)
+                ON CONFLICT (key) DO UPDATE SET embedding = EXCLUDED.embedding, last_used_at = now()",
+                key,
+                model,
+                embedding,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+use crate::domain::feeds::parser::FeedItem;
+use crate::domain::feeds::repository::{FeedRepository, FeedRepositoryError};
+use adco_shared::feed::Feed;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct FeedRepositoryPostgres {
+    pool: PgPool,
+}
+
+impl FeedRepositoryPostgres {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Feeds ordered by creation, optionally only the one with `id`.
+    async fn fetch_feeds(&self, id: Option<Uuid>) -> Result<Vec<Feed>, FeedRepositoryError> {
+        let result = sqlx::query!(
+            "SELECT * FROM feeds WHERE ($1::uuid IS NULL OR id = $1) ORDER BY created_at, url",
+            id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(result
+            .into_iter()
+            .map(|r| Feed {
+                id: Some(r.id),
+                url: r.url,
+                title: r.title,
+                poll_interval_minutes: r.poll_interval_minutes,
+                enabled: r.enabled,
+                etag: r.etag,
+                last_modified: r.last_modified,
+                last_polled_at: r.last_polled_at,
+                last_error: r.last_error,
+                created_at: r.created_at,
+            })
+            .collect())
+    }
+}
+
+impl FeedRepository for FeedRepositoryPostgres {
+    async fn list_feeds(&self) -> Result<Vec<Feed>, FeedRepositoryError> {
+        self.fetch_feeds(None).await
+    }
+
+    async fn get_feed_by_id(&self, id: Uuid) -> Result<Option<Feed>, FeedRepositoryError> {
+        Ok(self.fetch_feeds(Some(id)).await?.pop())
+    }
+
+    async fn create_feed(&self, feed: &Feed) -> Result<Uuid, FeedRepositoryError> {
+        let result = sqlx::query!(
+            "INSERT INTO feeds (url, title, poll_interval_minutes, enabled, etag, last_modified, last_polled_at, last_error, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE($9, now())) RETURNING id",
+            feed.url,
+            feed.title,
+            feed.poll_interval_minutes,
+            feed.enabled,
+            feed.etag,
+            feed.last_modified,
+            feed.last_polled_at,
+            feed.last_error,
+            feed.created_at,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(result.id)
+    }
+
+    async fn update_feed(&self, feed: &Feed) -> Result<(), FeedRepositoryError> {
+        let result = sqlx::query!(
+            "UPDATE feeds SET url = $1, title = $2, poll_interval_minutes = $3, enabled = $4, etag = $5, last_modified = $6, last_polled_at = $7, last_error = $8 WHERE id = $9",
+            feed.url,
+            feed.title,
+            feed.poll_interval_minutes,
+            feed.enabled,
+            feed.etag,
+            feed.last_modified,
+            feed.last_polled_at,
+            feed.last_error,
+            feed.id,
+        )
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(FeedRepositoryError::NotFound {
+                id: feed.id.unwrap_or_default(),
+            });
+        }
+        Ok(())
+    }
+
+    async fn delete_feed(&self, id: Uuid) -> Result<(), FeedRepositoryError> {
+        let result = sqlx::query!("DELETE FROM feeds WHERE id = $1", id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(FeedRepositoryError::NotFound { id });
+        }
+        Ok(())
+    }
+
+    async fn get_new_guids(
+        &self,
+        feed_id: Uuid,
+        guids: &[String],
+    ) -> Result<Vec<String>, FeedRepositoryError> {
+        let seen = sqlx::query!(
+            "SELECT guid FROM feed_items WHERE feed_id = $1 AND guid = ANY($2)",
+            feed_id,
+            guids,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(guids
+            .iter()
+            .filter(|guid| !seen.iter().any(|r| &r.guid == *guid))
+            .cloned()
+            .collect())
+    }
+
+    async fn record_item(
+        &self,
+        feed_id: Uuid,
+        item: &FeedItem,
+        source_id: Option<Uuid>,
+    ) -> Result<(), FeedRepositoryError> {
+        sqlx::query!(
+            "INSERT INTO feed_items (feed_id, guid, url, title, published_at, source_id) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT (feed_id, guid) DO UPDATE SET source_id = COALESCE(EXCLUDED.source_id, feed_items.source_id)",
+            feed_id,
+            item.guid,
+            item.url,
+            item.title,
+            item.published_at,
+            source_id,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
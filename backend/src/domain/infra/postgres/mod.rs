@@ -1,2 +1,8 @@
+pub mod advisory_lock;
+pub mod embedding_cache_repo;
+pub mod feeds_repo;
+pub mod pipeline_repo;
 pub mod posts_repo;
 pub mod sources_repo;
+pub mod themes_repo;
+pub mod usage_repo;
@@ -0,0 +1,141 @@
+use crate::domain::pipeline::repository::{PipelineRepository, PipelineRepositoryError};
+use adco_shared::pipeline::{
+    PipelineRun, PipelineRunStatus, PipelineStage, PipelineStageKind, PipelineStageStatus,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct PipelineRepositoryPostgres {
+    pool: PgPool,
+}
+
+
+impl PipelineRepositoryPostgres {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl PipelineRepository for PipelineRepositoryPostgres {
+
+
+    async fn create_run(&self, run: &PipelineRun) -> Result<Uuid, PipelineRepositoryError> {
+        let result = sqlx::query!(
+            "INSERT INTO pipeline_runs (status, current_stage, post_id, error, created_at, updated_at, finished_at, meta) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id",
+            run.status.to_string(),
+            run.current_stage.map(|s| s.to_string()),
+            run.post_id,
+            run.error,
+            run.created_at,
+            run.updated_at,
+            run.finished_at,
+            run.meta,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(result.id)
+    }
+
+    async fn get_run_by_id(&self, id: Uuid) -> Result<Option<PipelineRun>, PipelineRepositoryError> {
+        let result = sqlx::query!("SELECT * FROM pipeline_runs WHERE id = $1", id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(result.map(|r| PipelineRun {
+            id: Some(r.id),
+            status: PipelineRunStatus::from(r.status),
+            current_stage: r.current_stage.map(PipelineStageKind::from),
+            post_id: r.post_id,
+            error: r.error,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+            finished_at: r.finished_at,
+            meta: r.meta,
+        }))
+    }
+
+    async fn update_run(&self, run: &PipelineRun) -> Result<(), PipelineRepositoryError> {
+        let result = sqlx::query!(
+            "UPDATE pipeline_runs SET status = $1, current_stage = $2, post_id = $3, error = $4, updated_at = now(), finished_at = $5, meta = $6 WHERE id = $7",
+            run.status.to_string(),
+            run.current_stage.map(|s| s.to_string()),
+            run.post_id,
+            run.error,
+            run.finished_at,
+            run.meta,
+            run.id,
+        )
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(PipelineRepositoryError::NotFound { id: run.id.unwrap_or_default() });
+        }
+        Ok(())
+    }
+
+    async fn get_stages(&self, run_id: Uuid) -> Result<Vec<PipelineStage>, PipelineRepositoryError> {
+        let result = sqlx::query!(
+            "SELECT * FROM pipeline_stages WHERE run_id = $1 ORDER BY started_at",
+            run_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(result.into_iter().map(|r| PipelineStage {
+            id: Some(r.id),
+            run_id: r.run_id,
+            stage: PipelineStageKind::from(r.stage),
+            status: PipelineStageStatus::from(r.status),
+            attempts: r.attempts,
+            input: r.input,
+            output: r.output,
+            error: r.error,
+            started_at: r.started_at,
+            finished_at: r.finished_at,
+            duration_ms: r.duration_ms,
+        }).collect())
+    }
+
+    async fn get_stage(&self, run_id: Uuid, stage: PipelineStageKind) -> Result<Option<PipelineStage>, PipelineRepositoryError> {
+        let result = sqlx::query!(
+            "SELECT * FROM pipeline_stages WHERE run_id = $1 AND stage = $2",
+            run_id,
+            stage.to_string(),
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(result.map(|r| PipelineStage {
+            id: Some(r.id),
+            run_id: r.run_id,
+            stage: PipelineStageKind::from(r.stage),
+            status: PipelineStageStatus::from(r.status),
+            attempts: r.attempts,
+            input: r.input,
+            output: r.output,
+            error: r.error,
+            started_at: r.started_at,
+            finished_at: r.finished_at,
+            duration_ms: r.duration_ms,
+        }))
+    }
+
+    async fn save_stage(&self, stage: &PipelineStage) -> Result<Uuid, PipelineRepositoryError> {
+        let result = sqlx::query!(
+            "INSERT INTO pipeline_stages (run_id, stage, status, attempts, input, output, error, started_at, finished_at, duration_ms) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (run_id, stage) DO UPDATE SET status = EXCLUDED.status, attempts = EXCLUDED.attempts, input = EXCLUDED.input, output = EXCLUDED.output, error = EXCLUDED.error, started_at = EXCLUDED.started_at, finished_at = EXCLUDED.finished_at, duration_ms = EXCLUDED.duration_ms
+            RETURNING id",
+            stage.run_id,
+            stage.stage.to_string(),
+            stage.status.to_string(),
+            stage.attempts,
+            stage.input,
+            stage.output,
+            stage.error,
+            stage.started_at,
+            stage.finished_at,
+            stage.duration_ms,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(result.id)
+    }
+}
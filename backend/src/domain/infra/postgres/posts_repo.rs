@@ -1,6 +1,8 @@
-use crate::domain::posts::repository::{PostRepository, PostRepositoryError};
+use crate::domain::content::SimilarTopic;
+use crate::domain::posts::repository::{PostRepository, PostRepositoryError, PostTopic};
 use adco_shared::post::{Post, PostStatus};
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
@@ -30,7 +32,11 @@ impl PostsRepositoryPostgres {
             channel_id: r.channel_id.unwrap_or_default(),
             scheduled_at: r.scheduled_at,
             published_at: r.published_at,
+            created_at: r.created_at,
             meta: r.meta,
+            telegram_message_ids: r.telegram_message_ids,
+            publish_attempts: r.publish_attempts,
+            publish_error: r.publish_error,
         }).collect())
     }
 }
@@ -40,7 +46,7 @@ impl PostRepository for PostsRepositoryPostgres {
 
     async fn create_post(&self, post: &Post) -> Result<Uuid, PostRepositoryError> {
         let result = sqlx::query!(
-            "INSERT INTO posts (topic, draft, post_text, status, channel_id, scheduled_at, published_at, meta) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id",
+            "INSERT INTO posts (topic, draft, post_text, status, channel_id, scheduled_at, published_at, meta, telegram_message_ids, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, COALESCE($10, now())) RETURNING id",
             post.topic,
             post.draft,
             post.post_text,
@@ -49,6 +55,8 @@ impl PostRepository for PostsRepositoryPostgres {
             post.scheduled_at,
             post.published_at,
             post.meta,
+            &post.telegram_message_ids,
+            post.created_at,
         )
         .fetch_one(&self.pool)
         .await?;
@@ -57,7 +65,7 @@ impl PostRepository for PostsRepositoryPostgres {
 
     async fn update_post(&self, post: &Post) -> Result<(), PostRepositoryError> {
         let _result = sqlx::query!(
-            "UPDATE posts SET topic = $1, draft = $2, post_text = $3, status = $4, channel_id = $5, scheduled_at = $6, published_at = $7, meta = $8 WHERE id = $9",
+            "UPDATE posts SET topic_embedding = CASE WHEN topic IS DISTINCT FROM $1 THEN NULL ELSE topic_embedding END, publish_attempts = CASE WHEN status IS DISTINCT FROM $4 THEN 0 ELSE publish_attempts END, topic = $1, draft = $2, post_text = $3, status = $4, channel_id = $5, scheduled_at = $6, published_at = $7, meta = $8, telegram_message_ids = $9 WHERE id = $10",
             post.topic,
             post.draft,
             post.post_text,
@@ -66,10 +74,37 @@ impl PostRepository for PostsRepositoryPostgres {
             post.scheduled_at,
             post.published_at,
             post.meta,
+            &post.telegram_message_ids,
             post.id,
         )
         .execute(&self.pool)
         .await?;
+        if _result.rows_affected() == 0 {
+            return Err(PostRepositoryError::NotFound { id: post.id.unwrap_or_default() });
+        }
+        Ok(())
+    }
+
+    async fn update_post_if_status(&self, post: &Post, expected: PostStatus) -> Result<(), PostRepositoryError> {
+        let result = sqlx::query!(
+            "UPDATE posts SET topic_embedding = CASE WHEN topic IS DISTINCT FROM $1 THEN NULL ELSE topic_embedding END, publish_attempts = CASE WHEN status IS DISTINCT FROM $4 THEN 0 ELSE publish_attempts END, topic = $1, draft = $2, post_text = $3, status = $4, channel_id = $5, scheduled_at = $6, published_at = $7, meta = $8, telegram_message_ids = $9 WHERE id = $10 AND status = $11",
+            post.topic,
+            post.draft,
+            post.post_text,
+            post.status.to_string(),
+            post.channel_id,
+            post.scheduled_at,
+            post.published_at,
+            post.meta,
+            &post.telegram_message_ids,
+            post.id,
+            expected.to_string(),
+        )
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(PostRepositoryError::StatusChanged { id: post.id.unwrap_or_default(), expected });
+        }
         Ok(())
     }
 
@@ -78,6 +113,19 @@ impl PostRepository for PostsRepositoryPostgres {
         let _result = sqlx::query!("DELETE FROM posts WHERE id = $1", id)
         .execute(&self.pool)
         .await?;
+        if _result.rows_affected() == 0 {
+            return Err(PostRepositoryError::NotFound { id });
+        }
+        Ok(())
+    }
+
+    async fn delete_post_if_status(&self, id: Uuid, expected: PostStatus) -> Result<(), PostRepositoryError> {
+        let result = sqlx::query!("DELETE FROM posts WHERE id = $1 AND status = $2", id, expected.to_string())
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(PostRepositoryError::StatusChanged { id, expected });
+        }
         Ok(())
     }
 
@@ -95,7 +143,184 @@ impl PostRepository for PostsRepositoryPostgres {
             channel_id: r.channel_id.unwrap_or_default(),
             scheduled_at: r.scheduled_at,
             published_at: r.published_at,
+            created_at: r.created_at,
             meta: r.meta,
+            telegram_message_ids: r.telegram_message_ids,
+            publish_attempts: r.publish_attempts,
+            publish_error: r.publish_error,
         }))
     }
-}
\ No newline at end of file
+
+    async fn list_posts(&self, status: Option<PostStatus>, limit: i64, offset: i64) -> Result<Vec<Post>, PostRepositoryError> {
+        let result = sqlx::query!(
+            "SELECT * FROM posts WHERE ($1::TEXT IS NULL OR status = $1) ORDER BY created_at DESC LIMIT $2 OFFSET $3",
+            status.map(|s| s.to_string()),
+            limit,
+            offset,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(result.into_iter().map(|r| Post {
+            id: Some(r.id),
+            topic: r.topic.unwrap_or_default(),
+            draft: r.draft.unwrap_or_default(),
+            post_text: r.post_text.unwrap_or_default(),
+            status: PostStatus::from(r.status.unwrap_or_default()),
+            channel_id: r.channel_id.unwrap_or_default(),
+            scheduled_at: r.scheduled_at,
+            published_at: r.published_at,
+            created_at: r.created_at,
+            meta: r.meta,
+            telegram_message_ids: r.telegram_message_ids,
+            publish_attempts: r.publish_attempts,
+            publish_error: r.publish_error,
+        }).collect())
+    }
+
+    async fn count_posts(&self, status: Option<PostStatus>) -> Result<i64, PostRepositoryError> {
+        let result = sqlx::query!(
+            r#"SELECT COUNT(*) AS "count!" FROM posts WHERE ($1::TEXT IS NULL OR status = $1)"#,
+            status.map(|s| s.to_string()),
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(result.count)
+    }
+
+    async fn get_due_scheduled_posts(&self, now: DateTime<Utc>) -> Result<Vec<Post>, PostRepositoryError> {
+        let result = sqlx::query!(
+            "SELECT * FROM posts WHERE status = $1 AND scheduled_at <= $2 ORDER BY scheduled_at",
+            PostStatus::Scheduled.to_string(),
+            now,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(result.into_iter().map(|r| Post {
+            id: Some(r.id),
+            topic: r.topic.unwrap_or_default(),
+            draft: r.draft.unwrap_or_default(),
+            post_text: r.post_text.unwrap_or_default(),
+            status: PostStatus::from(r.status.unwrap_or_default()),
+            channel_id: r.channel_id.unwrap_or_default(),
+            scheduled_at: r.scheduled_at,
+            published_at: r.published_at,
+            created_at: r.created_at,
+            meta: r.meta,
+            telegram_message_ids: r.telegram_message_ids,
+            publish_attempts: r.publish_attempts,
+            publish_error: r.publish_error,
+        }).collect())
+    }
+
+    async fn transition_status(&self, id: Uuid, from: PostStatus, to: PostStatus) -> Result<(), PostRepositoryError> {
+        let result = sqlx::query!(
+            "UPDATE posts SET status = $1 WHERE id = $2 AND status = $3",
+            to.to_string(),
+            id,
+            from.to_string(),
+        )
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(PostRepositoryError::StatusChanged { id, expected: from });
+        }
+        Ok(())
+    }
+
+    async fn mark_published(&self, id: Uuid, message_ids: &[i64], published_at: DateTime<Utc>) -> Result<(), PostRepositoryError> {
+        let result = sqlx::query!(
+            "UPDATE posts SET status = $1, telegram_message_ids = $2, published_at = $3, publish_error = NULL WHERE id = $4 AND status = $5",
+            PostStatus::Published.to_string(),
+            message_ids,
+            published_at,
+            id,
+            PostStatus::Publishing.to_string(),
+        )
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(PostRepositoryError::StatusChanged { id, expected: PostStatus::Publishing });
+        }
+        Ok(())
+    }
+
+    async fn record_publish_failure(&self, id: Uuid, error: &str, retry_status: PostStatus, max_attempts: i32) -> Result<Post, PostRepositoryError> {
+        let r = sqlx::query!(
+            "UPDATE posts SET publish_attempts = publish_attempts + 1, publish_error = $1,
+                status = CASE WHEN publish_attempts + 1 >= $2 THEN $3 ELSE $4 END,
+                scheduled_at = CASE WHEN publish_attempts + 1 >= $2 THEN NULL ELSE scheduled_at END
+            WHERE id = $5 AND status = $6
+            RETURNING *",
+            error,
+            max_attempts,
+            PostStatus::Draft.to_string(),
+            retry_status.to_string(),
+            id,
+            PostStatus::Publishing.to_string(),
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(PostRepositoryError::StatusChanged { id, expected: PostStatus::Publishing })?;
+        Ok(Post {
+            id: Some(r.id),
+            topic: r.topic.unwrap_or_default(),
+            draft: r.draft.unwrap_or_default(),
+            post_text: r.post_text.unwrap_or_default(),
+            status: PostStatus::from(r.status.unwrap_or_default()),
+            channel_id: r.channel_id.unwrap_or_default(),
+            scheduled_at: r.scheduled_at,
+            published_at: r.published_at,
+            created_at: r.created_at,
+            meta: r.meta,
+            telegram_message_ids: r.telegram_message_ids,
+            publish_attempts: r.publish_attempts,
+            publish_error: r.publish_error,
+        })
+    }
+
+    async fn get_unembedded_post_topics(&self, model: &str) -> Result<Vec<PostTopic>, PostRepositoryError> {
+        let result = sqlx::query!(
+            "SELECT id, topic FROM posts WHERE status IS DISTINCT FROM $1 AND topic IS NOT NULL AND (topic_embedding IS NULL OR topic_embedding_model IS DISTINCT FROM $2) ORDER BY created_at",
+            PostStatus::Rejected.to_string(),
+            model,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(result.into_iter().map(|r| PostTopic {
+            post_id: r.id,
+            topic: r.topic.unwrap_or_default(),
+        }).collect())
+    }
+
+    // The vector column only exists with pgvector, so these queries are not checked against the schema
+    async fn save_topic_embedding(&self, id: Uuid, model: &str, embedding: &[f32]) -> Result<(), PostRepositoryError> {
+        sqlx::query("UPDATE posts SET topic_embedding = $1, topic_embedding_model = $2 WHERE id = $3")
+        .bind(pgvector::Vector::from(embedding.to_vec()))
+        .bind(model)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn nearest_post_topics(&self, embedding: &[f32], model: &str, limit: usize) -> Result<Vec<SimilarTopic>, PostRepositoryError> {
+        let rows = sqlx::query(
+            "SELECT id, topic, 1 - (topic_embedding <=> $1) AS similarity FROM posts WHERE status IS DISTINCT FROM $2 AND topic IS NOT NULL AND topic_embedding_model = $3 ORDER BY topic_embedding <=> $1 LIMIT $4",
+        )
+        .bind(pgvector::Vector::from(embedding.to_vec()))
+        .bind(PostStatus::Rejected.to_string())
+        .bind(model)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter()
+            .map(|row| {
+                Ok(SimilarTopic {
+                    post_id: row.try_get("id")?,
+                    topic: row.try_get("topic")?,
+                    similarity: row.try_get("similarity")?,
+                })
+            })
+            .collect()
+    }
+}
@@ -1,6 +1,11 @@
-use crate::domain::sources::repository::{SourceRepository, SourceRepositoryError};
-use adco_shared::source::{Source, SourceType};
+use crate::domain::content::EmbeddedChunk;
+use crate::domain::sources::canonical::{canonical_url, content_hash};
+use crate::domain::sources::repository::{
+    SourceCanonicalization, SourceRepository, SourceRepositoryError, SourceUpsert,
+};
+use adco_shared::source::{Source, SourceChunk, SourceSummary, SourceType};
 use sqlx::PgPool;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
@@ -15,17 +20,24 @@ impl SourcesRepositoryImpl {
     }
 }
 
+/// Sources without a URL (pasted text) are only deduplicated by their hash.
+fn canonical_key(source: &Source) -> Option<String> {
+    Some(canonical_url(&source.url)).filter(|url| !url.is_empty())
+}
+
 impl SourceRepository for SourcesRepositoryImpl {
 
 
     async fn create_source(&self, source: &Source) -> Result<Uuid, SourceRepositoryError> {
         let result = sqlx::query!(
-            "INSERT INTO sources (url, title, source_type, fetched_at, raw_text) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+            "INSERT INTO sources (url, title, source_type, fetched_at, raw_text, canonical_url, content_hash) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id",
             source.url,
             source.title,
             source.source_type.to_string(),
             source.fetched_at,
             source.raw_text,
+            canonical_key(source),
+            content_hash(&source.raw_text),
         )
         .fetch_one(&self.pool)
         .await?;
@@ -33,25 +45,185 @@ impl SourceRepository for SourcesRepositoryImpl {
     }
 
     async fn update_source(&self, source: &Source) -> Result<(), SourceRepositoryError> {
-        let _result = sqlx::query!(
-            "UPDATE sources SET url = $1, title = $2, source_type = $3, fetched_at = $4, raw_text = $5 WHERE id = $6",
+        let result = sqlx::query!(
+            "UPDATE sources SET url = $1, title = $2, source_type = $3, fetched_at = $4, raw_text = $5, canonical_url = $6, content_hash = $7 WHERE id = $8",
             source.url,
             source.title,
             source.source_type.to_string(),
             source.fetched_at,
             source.raw_text,
+            canonical_key(source),
+            content_hash(&source.raw_text),
             source.id,
         )
         .execute(&self.pool)
         .await?;
+        if result.rows_affected() == 0 {
+            return Err(SourceRepositoryError::NotFound { id: source.id.unwrap_or_default() });
+        }
         Ok(())
     }
 
-    
+    async fn upsert_source(&self, source: &Source) -> Result<SourceUpsert, SourceRepositoryError> {
+        let canonical = canonical_key(source);
+        let hash = content_hash(&source.raw_text);
+        let mut tx = self.pool.begin().await?;
+        // A concurrent upsert may insert the same URL between the lookup and the insert,
+        // then the insert does nothing and the lookup is repeated to find that row
+        let known = loop {
+            // A URL match wins over a text match, empty texts never match each other
+            let known = sqlx::query!(
+                r#"SELECT id, content_hash,
+                    EXISTS (SELECT 1 FROM documents d WHERE d.document->>'source_id' = sources.id::TEXT) AS "indexed!"
+                FROM sources
+                WHERE canonical_url = $1 OR ($2 AND content_hash = $3)
+                ORDER BY canonical_url = $1 DESC NULLS LAST
+                LIMIT 1
+                FOR UPDATE"#,
+                canonical,
+                !source.raw_text.is_empty(),
+                hash,
+            )
+            .fetch_optional(&mut *tx)
+            .await?;
+            if let Some(known) = known {
+                break known;
+            }
+            let inserted = sqlx::query!(
+                "INSERT INTO sources (url, title, source_type, fetched_at, raw_text, canonical_url, content_hash) VALUES ($1, $2, $3, $4, $5, $6, $7) ON CONFLICT (canonical_url) DO NOTHING RETURNING id",
+                source.url,
+                source.title,
+                source.source_type.to_string(),
+                source.fetched_at,
+                source.raw_text,
+                canonical,
+                hash,
+            )
+            .fetch_optional(&mut *tx)
+            .await?;
+            if let Some(inserted) = inserted {
+                tx.commit().await?;
+                return Ok(SourceUpsert::Created(inserted.id));
+            }
+        };
+
+        // Without chunks the earlier indexing failed, the source is indexed again
+        let upsert = if known.content_hash.as_deref() == Some(hash.as_str()) && known.indexed {
+            sqlx::query!(
+                "UPDATE sources SET fetched_at = GREATEST(fetched_at, $1) WHERE id = $2",
+                source.fetched_at,
+                known.id,
+            )
+            .execute(&mut *tx)
+            .await?;
+            SourceUpsert::Unchanged(known.id)
+        } else {
+            sqlx::query!(
+                "UPDATE sources SET url = $1, title = $2, source_type = $3, fetched_at = $4, raw_text = $5, content_hash = $6 WHERE id = $7",
+                source.url,
+                source.title,
+                source.source_type.to_string(),
+                source.fetched_at,
+                source.raw_text,
+                hash,
+                known.id,
+            )
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query!("DELETE FROM documents WHERE document->>'source_id' = $1", known.id.to_string())
+            .execute(&mut *tx)
+            .await?;
+            SourceUpsert::Updated(known.id)
+        };
+        tx.commit().await?;
+        Ok(upsert)
+    }
+
+    async fn canonicalize_sources(&self) -> Result<SourceCanonicalization, SourceRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        let rows = sqlx::query!(
+            r#"SELECT s.id, s.url, s.canonical_url,
+                EXISTS (SELECT 1 FROM documents d WHERE d.document->>'source_id' = s.id::TEXT) AS "indexed!"
+            FROM sources s
+            ORDER BY s.fetched_at NULLS LAST, s.id
+            FOR UPDATE OF s"#
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // Oldest first within every canonical URL
+        let mut groups: HashMap<String, Vec<_>> = HashMap::new();
+        let mut order = Vec::new();
+        for row in rows {
+            let key = canonical_url(row.url.as_deref().unwrap_or_default());
+            if key.is_empty() {
+                continue;
+            }
+            if !groups.contains_key(&key) {
+                order.push(key.clone());
+            }
+            groups.entry(key).or_default().push(row);
+        }
+
+        // Stored keys are cleared first, so no row holds a key another one is moved to
+        sqlx::query!("UPDATE sources SET canonical_url = NULL WHERE canonical_url IS NOT NULL")
+            .execute(&mut *tx)
+            .await?;
+        let mut report = SourceCanonicalization::default();
+        for key in order {
+            let group = groups.remove(&key).unwrap_or_default();
+            let keeper = group.iter().position(|row| row.indexed).unwrap_or(0);
+            for (index, row) in group.iter().enumerate() {
+                if index == keeper {
+                    continue;
+                }
+                let deleted = sqlx::query!(
+                    "DELETE FROM documents WHERE document->>'source_id' = $1",
+                    row.id.to_string()
+                )
+                .execute(&mut *tx)
+                .await?;
+                report.deleted_chunks += deleted.rows_affected();
+                sqlx::query!(
+                    "UPDATE feed_items SET source_id = $1 WHERE source_id = $2",
+                    group[keeper].id,
+                    row.id
+                )
+                .execute(&mut *tx)
+                .await?;
+                sqlx::query!("DELETE FROM sources WHERE id = $1", row.id)
+                    .execute(&mut *tx)
+                    .await?;
+                report.merged += 1;
+            }
+            let keeper = &group[keeper];
+            sqlx::query!(
+                "UPDATE sources SET canonical_url = $1 WHERE id = $2",
+                key,
+                keeper.id
+            )
+            .execute(&mut *tx)
+            .await?;
+            if keeper.canonical_url.as_deref() != Some(key.as_str()) {
+                report.updated += 1;
+            }
+        }
+        tx.commit().await?;
+        Ok(report)
+    }
+
     async fn delete_source(&self, id: Uuid) -> Result<(), SourceRepositoryError> {
-        let _result = sqlx::query!("DELETE FROM sources WHERE id = $1", id)
-        .execute(&self.pool)
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!("DELETE FROM documents WHERE document->>'source_id' = $1", id.to_string())
+        .execute(&mut *tx)
         .await?;
+        let result = sqlx::query!("DELETE FROM sources WHERE id = $1", id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(SourceRepositoryError::NotFound { id });
+        }
+        tx.commit().await?;
         Ok(())
     }
 
@@ -69,4 +241,99 @@ impl SourceRepository for SourcesRepositoryImpl {
             raw_text: r.raw_text.unwrap_or_default(),
         }))
     }
-}
\ No newline at end of file
+
+    async fn list_sources(&self, limit: i64, offset: i64) -> Result<Vec<SourceSummary>, SourceRepositoryError> {
+        let result = sqlx::query!(
+            r#"SELECT s.id, s.url, s.title, s.source_type, s.fetched_at,
+                COALESCE(length(s.raw_text), 0) AS "text_length!",
+                (SELECT COUNT(*) FROM documents d WHERE d.document->>'source_id' = s.id::TEXT) AS "chunk_count!"
+            FROM sources s
+            ORDER BY s.fetched_at DESC NULLS LAST
+            LIMIT $1 OFFSET $2"#,
+            limit,
+            offset,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(result.into_iter().map(|r| SourceSummary {
+            id: r.id,
+            url: r.url.unwrap_or_default(),
+            title: r.title.unwrap_or_default(),
+            source_type: SourceType::from(r.source_type.unwrap_or_default()),
+            fetched_at: r.fetched_at,
+            text_length: r.text_length as i64,
+            chunk_count: r.chunk_count,
+        }).collect())
+    }
+
+    async fn count_sources(&self) -> Result<i64, SourceRepositoryError> {
+        let result = sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM sources"#)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(result.count)
+    }
+
+    async fn get_source_chunks(&self, source_id: Uuid) -> Result<Vec<SourceChunk>, SourceRepositoryError> {
+        let result = sqlx::query!(
+            "SELECT id, embedded_text FROM documents WHERE document->>'source_id' = $1",
+            source_id.to_string(),
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(result.into_iter().map(|r| SourceChunk {
+            id: r.id,
+            source_id,
+            text: r.embedded_text,
+        }).collect())
+    }
+
+    async fn delete_source_chunks(&self, source_id: Uuid) -> Result<u64, SourceRepositoryError> {
+        let result = sqlx::query!(
+            "DELETE FROM documents WHERE document->>'source_id' = $1",
+            source_id.to_string(),
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn replace_source_chunks(&self, source: &Source, chunks: &[EmbeddedChunk]) -> Result<u64, SourceRepositoryError> {
+        let id = source.id.unwrap_or_default();
+        let mut tx = self.pool.begin().await?;
+        let updated = sqlx::query!(
+            "UPDATE sources SET url = $1, title = $2, source_type = $3, fetched_at = $4, raw_text = $5, canonical_url = $6, content_hash = $7 WHERE id = $8",
+            source.url,
+            source.title,
+            source.source_type.to_string(),
+            source.fetched_at,
+            source.raw_text,
+            canonical_key(source),
+            content_hash(&source.raw_text),
+            id,
+        )
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(SourceRepositoryError::NotFound { id });
+        }
+        let deleted = sqlx::query!(
+            "DELETE FROM documents WHERE document->>'source_id' = $1",
+            id.to_string(),
+        )
+        .execute(&mut *tx)
+        .await?;
+        // The column is pgvector in production, so the query is not checked against the schema
+        for embedded in chunks {
+            let document = serde_json::to_value(&embedded.chunk)
+                .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+            sqlx::query("INSERT INTO documents (document, embedded_text, embedding) VALUES ($1, $2, $3)")
+                .bind(document)
+                .bind(&embedded.chunk.text)
+                .bind(&embedded.embedding)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(deleted.rows_affected())
+    }
+}
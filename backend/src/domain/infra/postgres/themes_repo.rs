@@ -0,0 +1,110 @@
+use crate::domain::themes::repository::{ThemeRepository, ThemeRepositoryError};
+use adco_shared::theme::TopicTheme;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct ThemeRepositoryPostgres {
+    pool: PgPool,
+}
+
+impl ThemeRepositoryPostgres {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Themes ordered by category and name, optionally only one `id` or only enabled ones.
+    async fn fetch_themes(
+        &self,
+        id: Option<Uuid>,
+        enabled_only: bool,
+    ) -> Result<Vec<TopicTheme>, ThemeRepositoryError> {
+        let result = sqlx::query!(
+            "SELECT * FROM topic_themes WHERE ($1::uuid IS NULL OR id = $1) AND (enabled OR NOT $2) ORDER BY category, name",
+            id,
+            enabled_only
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(result
+            .into_iter()
+            .map(|r| TopicTheme {
+                id: Some(r.id),
+                name: r.name,
+                category: r.category,
+                weight: r.weight,
+                enabled: r.enabled,
+                cooldown_days: r.cooldown_days,
+                last_used_at: r.last_used_at,
+                created_at: r.created_at,
+            })
+            .collect())
+    }
+}
+
+impl ThemeRepository for ThemeRepositoryPostgres {
+    async fn list_themes(&self) -> Result<Vec<TopicTheme>, ThemeRepositoryError> {
+        self.fetch_themes(None, false).await
+    }
+
+    async fn get_enabled_themes(&self) -> Result<Vec<TopicTheme>, ThemeRepositoryError> {
+        self.fetch_themes(None, true).await
+    }
+
+    async fn get_theme_by_id(&self, id: Uuid) -> Result<Option<TopicTheme>, ThemeRepositoryError> {
+        Ok(self.fetch_themes(Some(id), false).await?.pop())
+    }
+
+    async fn create_theme(&self, theme: &TopicTheme) -> Result<Uuid, ThemeRepositoryError> {
+        let result = sqlx::query!(
+            "INSERT INTO topic_themes (name, category, weight, enabled, cooldown_days, last_used_at, created_at) VALUES ($1, $2, $3, $4, $5, $6, COALESCE($7, now())) RETURNING id",
+            theme.name,
+            theme.category,
+            theme.weight,
+            theme.enabled,
+            theme.cooldown_days,
+            theme.last_used_at,
+            theme.created_at,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(result.id)
+    }
+
+    async fn update_theme(&self, theme: &TopicTheme) -> Result<(), ThemeRepositoryError> {
+        let result = sqlx::query!(
+            "UPDATE topic_themes SET name = $1, category = $2, weight = $3, enabled = $4, cooldown_days = $5, last_used_at = $6 WHERE id = $7",
+            theme.name,
+            theme.category,
+            theme.weight,
+            theme.enabled,
+            theme.cooldown_days,
+            theme.last_used_at,
+            theme.id,
+        )
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(ThemeRepositoryError::NotFound {
+                id: theme.id.unwrap_or_default(),
+            });
+        }
+        Ok(())
+    }
+
+    async fn mark_theme_used(
+        &self,
+        name: &str,
+        at: DateTime<Utc>,
+    ) -> Result<(), ThemeRepositoryError> {
+        sqlx::query!(
+            "UPDATE topic_themes SET last_used_at = $1 WHERE name = $2",
+            at,
+            name
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
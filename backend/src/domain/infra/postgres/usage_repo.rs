@@ -0,0 +1,108 @@
+use crate::domain::usage::repository::{UsageRepository, UsageRepositoryError};
+use adco_shared::pipeline::PipelineStageKind;
+use adco_shared::usage::{CostSummary, DailyCost, LlmCall, LlmCallKind};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct UsageRepositoryPostgres {
+    pool: PgPool,
+}
+
+impl UsageRepositoryPostgres {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl UsageRepository for UsageRepositoryPostgres {
+    async fn save_calls(&self, calls: &[LlmCall]) -> Result<(), UsageRepositoryError> {
+        for call in calls {
+            sqlx::query!(
+                "INSERT INTO llm_calls (run_id, stage, agent, kind, model, input_tokens, output_tokens, latency_ms, cost_usd, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+                call.run_id,
+                call.stage.map(|s| s.to_string()),
+                call.agent,
+                call.kind.to_string(),
+                call.model,
+                call.input_tokens,
+                call.output_tokens,
+                call.latency_ms,
+                call.cost_usd,
+                call.created_at,
+            )
+            .execute(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn get_run_calls(&self, run_id: Uuid) -> Result<Vec<LlmCall>, UsageRepositoryError> {
+        let result = sqlx::query!(
+            "SELECT * FROM llm_calls WHERE run_id = $1 ORDER BY created_at",
+            run_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(result
+            .into_iter()
+            .map(|r| LlmCall {
+                id: Some(r.id),
+                run_id: r.run_id,
+                stage: r.stage.map(PipelineStageKind::from),
+                agent: r.agent,
+                kind: LlmCallKind::from(r.kind),
+                model: r.model,
+                input_tokens: r.input_tokens,
+                output_tokens: r.output_tokens,
+                latency_ms: r.latency_ms,
+                cost_usd: r.cost_usd,
+                created_at: r.created_at,
+            })
+            .collect())
+    }
+
+    async fn get_post_cost(&self, post_id: Uuid) -> Result<CostSummary, UsageRepositoryError> {
+        let result = sqlx::query!(
+            r#"SELECT COUNT(*) AS "calls!", COALESCE(SUM(c.input_tokens), 0)::BIGINT AS "input_tokens!", COALESCE(SUM(c.output_tokens), 0)::BIGINT AS "output_tokens!", COALESCE(SUM(c.cost_usd), 0) AS "cost_usd!"
+               FROM llm_calls c JOIN pipeline_runs r ON r.id = c.run_id WHERE r.post_id = $1"#,
+            post_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(CostSummary {
+            calls: result.calls,
+            input_tokens: result.input_tokens,
+            output_tokens: result.output_tokens,
+            cost_usd: result.cost_usd,
+        })
+    }
+
+    async fn get_daily_costs(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<DailyCost>, UsageRepositoryError> {
+        let result = sqlx::query!(
+            r#"SELECT (created_at AT TIME ZONE 'UTC')::DATE AS "day!", COUNT(*) AS "calls!", COALESCE(SUM(input_tokens), 0)::BIGINT AS "input_tokens!", COALESCE(SUM(output_tokens), 0)::BIGINT AS "output_tokens!", COALESCE(SUM(cost_usd), 0) AS "cost_usd!"
+               FROM llm_calls WHERE created_at >= $1 AND created_at < $2 GROUP BY 1 ORDER BY 1"#,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(result
+            .into_iter()
+            .map(|r| DailyCost {
+                day: r.day,
+                summary: CostSummary {
+                    calls: r.calls,
+                    input_tokens: r.input_tokens,
+                    output_tokens: r.output_tokens,
+                    cost_usd: r.cost_usd,
+                },
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,220 @@
+use once_cell::sync::Lazy;
+use rand::Rng;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::warn;
+
+/// Retry, backoff and timeout settings shared by outbound HTTP and LLM calls.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Timeout of a single attempt.
+    pub attempt_timeout: Duration,
+    /// Deadline for all attempts together, including backoff sleeps.
+    pub total_timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            attempt_timeout: Duration::from_secs(60),
+            total_timeout: Duration::from_secs(300),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Reads `ADCO_RETRY_MAX_RETRIES`, `ADCO_RETRY_BASE_DELAY_MS`, `ADCO_RETRY_MAX_DELAY_MS`,
+    /// `ADCO_RETRY_ATTEMPT_TIMEOUT_SECS` and `ADCO_RETRY_TOTAL_TIMEOUT_SECS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            max_retries: read("ADCO_RETRY_MAX_RETRIES")
+                .map(|v| v as u32)
+                .unwrap_or(defaults.max_retries),
+            base_delay: read("ADCO_RETRY_BASE_DELAY_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.base_delay),
+            max_delay: read("ADCO_RETRY_MAX_DELAY_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.max_delay),
+            attempt_timeout: read("ADCO_RETRY_ATTEMPT_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.attempt_timeout),
+            total_timeout: read("ADCO_RETRY_TOTAL_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.total_timeout),
+        }
+    }
+
+    /// [`from_env`](Self::from_env) with the timeouts of LLM calls, which take minutes for
+    /// long outputs: `ADCO_LLM_ATTEMPT_TIMEOUT_SECS` (default 300) and
+    /// `ADCO_LLM_TOTAL_TIMEOUT_SECS` (default 900).
+    pub fn llm_from_env() -> Self {
+        let read = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            attempt_timeout: Duration::from_secs(
+                read("ADCO_LLM_ATTEMPT_TIMEOUT_SECS").unwrap_or(300),
+            ),
+            total_timeout: Duration::from_secs(read("ADCO_LLM_TOTAL_TIMEOUT_SECS").unwrap_or(900)),
+            ..Self::from_env()
+        }
+    }
+
+    /// Exponential backoff with full jitter: random delay in `[0, min(max, base * 2^attempt)]`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let millis = exponential.as_millis() as u64;
+        Duration::from_millis(rand::rng().random_range(0..=millis))
+    }
+}
+
+/// What to do with a failed attempt.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RetryDecision {
+    /// Retry; `after` is the server-requested delay (`Retry-After`), if any.
+    Retry {
+        after: Option<Duration>,
+    },
+    Fail,
+}
+
+#[derive(Error, Debug)]
+#[error("{operation} timed out after {timeout:?}")]
+pub struct TimeoutError {
+    pub operation: String,
+    pub timeout: Duration,
+}
+
+/// Runs `operation` with per-attempt and total timeouts, retrying the errors that
+/// `classify` marks as transient. A server-requested delay is waited out in full; if it
+/// would run past the total timeout, the error is returned right away instead.
+pub async fn with_retry<T, E, F, Fut, C>(
+    policy: &RetryPolicy,
+    operation_name: &str,
+    classify: C,
+    mut operation: F,
+) -> Result<T, E>
+where
+    E: From<TimeoutError> + std::fmt::Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    C: Fn(&E) -> RetryDecision,
+{
+    let deadline = tokio::time::Instant::now() + policy.total_timeout;
+    let attempts = async {
+        let mut attempt = 0;
+        loop {
+            let result = match tokio::time::timeout(policy.attempt_timeout, operation()).await {
+                Ok(result) => result,
+                Err(_) => Err(E::from(TimeoutError {
+                    operation: operation_name.to_string(),
+                    timeout: policy.attempt_timeout,
+                })),
+            };
+            let error = match result {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+            let delay = match classify(&error) {
+                RetryDecision::Retry { after: Some(after) } if attempt < policy.max_retries => {
+                    let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+                    if after >= remaining {
+                        warn!(
+                            "{} failed, the server asks to retry in {:?}, past the deadline: {}",
+                            operation_name, after, error
+                        );
+                        return Err(error);
+                    }
+                    after
+                }
+                RetryDecision::Retry { after: None } if attempt < policy.max_retries => {
+                    policy.backoff(attempt)
+                }
+                _ => return Err(error),
+            };
+            attempt += 1;
+            warn!(
+                "{} failed (attempt {}/{}), retrying in {:?}: {}",
+                operation_name,
+                attempt,
+                policy.max_retries + 1,
+                delay,
+                error
+            );
+            tokio::time::sleep(delay).await;
+        }
+    };
+    match tokio::time::timeout(policy.total_timeout, attempts).await {
+        Ok(result) => result,
+        Err(_) => Err(E::from(TimeoutError {
+            operation: operation_name.to_string(),
+            timeout: policy.total_timeout,
+        })),
+    }
+}
+
+/// Retry decision for an HTTP status: 429 and 5xx are transient.
+pub fn retry_decision_for_status(status: u16, retry_after: Option<Duration>) -> RetryDecision {
+    match status {
+        408 | 429 | 500..=599 => RetryDecision::Retry { after: retry_after },
+        _ => RetryDecision::Fail,
+    }
+}
+
+/// Parses a `Retry-After` header value: delay in seconds or an HTTP date.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(delay.to_std().unwrap_or_default())
+}
+
+static HOST_LIMITS: Lazy<Mutex<HashMap<String, Arc<Semaphore>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Waits for a free slot for `host`. At most `ADCO_MAX_CONCURRENCY_PER_HOST` (default 2)
+/// requests to the same host run at once across the whole process.
+pub async fn acquire_host_slot(host: &str) -> OwnedSemaphorePermit {
+    let semaphore = {
+        let mut limits = HOST_LIMITS.lock().unwrap();
+        limits
+            .entry(host.to_string())
+            .or_insert_with(|| {
+                let permits = std::env::var("ADCO_MAX_CONCURRENCY_PER_HOST")
+                    .ok()
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .unwrap_or(2);
+                Arc::new(Semaphore::new(permits.max(1)))
+            })
+            .clone()
+    };
+    semaphore
+        .acquire_owned()
+        .await
+        .expect("host semaphore is never closed")
+}
+
+/// Host part of an URL, used as the concurrency key.
+pub fn host_of(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.to_string()))
+        .unwrap_or_default()
+}
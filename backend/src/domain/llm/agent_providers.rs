@@ -0,0 +1,81 @@
+use super::agents::{QAController, Researcher, Stylizer, TopicGenerator};
+use super::config::LlmConfig;
+use super::fixture_provider::{FixtureLlmProvider, RecordingLlmProvider};
+use super::metered_provider::MeteredLlmProvider;
+use super::provider::{LlmError, LlmProvider};
+use super::resilient_provider::ResilientLlmProvider;
+use super::rig_provider::RigLlmProvider;
+use crate::domain::infra::resilience::RetryPolicy;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::info;
+
+/// Builds the provider for an agent from env overrides and the agent defaults.
+///
+/// `ADCO_LLM_FIXTURES_DIR` replaces every agent with recorded fixtures,
+/// `ADCO_LLM_RECORD_DIR` records real responses into fixtures.
+pub fn provider_from_env(agent: &str, defaults: LlmConfig) -> Result<Arc<dyn LlmProvider>, LlmError> {
+    if let Ok(dir) = std::env::var("ADCO_LLM_FIXTURES_DIR") {
+        info!("Agent {} uses fixtures from {}", agent, dir);
+        return Ok(Arc::new(FixtureLlmProvider::load(Path::new(&dir), agent)?));
+    }
+    let config = LlmConfig::from_env(agent, defaults)?;
+    info!(
+        "Agent {} uses {} model {}",
+        agent,
+        config.provider,
+        config.model
+    );
+    let host = config.host();
+    let provider: Arc<dyn LlmProvider> = Arc::new(MeteredLlmProvider::new(Arc::new(
+        ResilientLlmProvider::new(
+            Arc::new(RigLlmProvider::new(config)),
+            &host,
+            RetryPolicy::llm_from_env(),
+        ),
+    )));
+    match std::env::var("ADCO_LLM_RECORD_DIR") {
+        Ok(dir) => Ok(Arc::new(RecordingLlmProvider::new(provider, PathBuf::from(dir)))),
+        Err(_) => Ok(provider),
+    }
+}
+
+/// Providers of all pipeline agents.
+#[derive(Clone)]
+pub struct AgentProviders {
+    pub topic_generator: Arc<dyn LlmProvider>,
+    pub researcher: Arc<dyn LlmProvider>,
+    pub stylizer: Arc<dyn LlmProvider>,
+    pub qa_controller: Arc<dyn LlmProvider>,
+}
+
+impl AgentProviders {
+    pub fn from_env() -> Result<Self, LlmError> {
+        Ok(Self {
+            topic_generator: provider_from_env(TopicGenerator::NAME, TopicGenerator::default_config())?,
+            researcher: provider_from_env(Researcher::NAME, Researcher::default_config())?,
+            stylizer: provider_from_env(Stylizer::NAME, Stylizer::default_config())?,
+            qa_controller: provider_from_env(QAController::NAME, QAController::default_config())?,
+        })
+    }
+
+    pub fn from_fixtures(dir: &Path) -> Result<Self, LlmError> {
+        Ok(Self {
+            topic_generator: Arc::new(FixtureLlmProvider::load(dir, TopicGenerator::NAME)?),
+            researcher: Arc::new(FixtureLlmProvider::load(dir, Researcher::NAME)?),
+            stylizer: Arc::new(FixtureLlmProvider::load(dir, Stylizer::NAME)?),
+            qa_controller: Arc::new(FixtureLlmProvider::load(dir, QAController::NAME)?),
+        })
+    }
+
+    /// Like [`Self::from_fixtures`], but without the `default` responses, see
+    /// [`FixtureLlmProvider::load_recorded`].
+    pub fn from_recorded_fixtures(dir: &Path) -> Result<Self, LlmError> {
+        Ok(Self {
+            topic_generator: Arc::new(FixtureLlmProvider::load_recorded(dir, TopicGenerator::NAME)?),
+            researcher: Arc::new(FixtureLlmProvider::load_recorded(dir, Researcher::NAME)?),
+            stylizer: Arc::new(FixtureLlmProvider::load_recorded(dir, Stylizer::NAME)?),
+            qa_controller: Arc::new(FixtureLlmProvider::load_recorded(dir, QAController::NAME)?),
+        })
+    }
+}
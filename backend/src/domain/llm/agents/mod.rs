@@ -2,8 +2,10 @@ pub mod researcher;
 pub mod stylizer;
 pub mod topic_generator;
 pub mod qa_controller;
+pub mod reranker;
 
 pub use topic_generator::*;
 pub use researcher::*;
 pub use stylizer::*;
-pub use qa_controller::*;
\ No newline at end of file
+pub use qa_controller::*;
+pub use reranker::*;
\ No newline at end of file
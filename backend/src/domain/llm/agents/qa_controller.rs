@@ -1,30 +1,35 @@
-use dotenvy::dotenv;
-use rig::{
-    client::CompletionClient,
-    completion::Prompt,
-    providers::openai::{self},
-};
+use crate::domain::llm::{provider_from_env, LlmConfig, LlmProvider, LlmProviderKind, LlmRequest};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QAControllerResult {
     pub content: String,
 }
 
-pub struct QAController {}
+pub struct QAController {
+    llm: Arc<dyn LlmProvider>,
+}
 
 impl QAController {
-    pub fn new() -> Self {
-        Self {}
+    pub const NAME: &'static str = "qa_controller";
+
+    pub fn new(llm: Arc<dyn LlmProvider>) -> Self {
+        Self { llm }
+    }
+
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        Ok(Self::new(provider_from_env(Self::NAME, Self::default_config())?))
+    }
+
+    pub fn default_config() -> LlmConfig {
+        LlmConfig::new(LlmProviderKind::OpenAi, "gpt-5").max_tokens(800)
     }
 
     pub async fn qa(
         &self,
         post_content: String,
     ) -> Result<QAControllerResult, anyhow::Error> {
-        dotenv().ok();
-        let api_key = std::env::var("ADCO_OPEN_AI_API_KEY")?;
-
         let system_prompt = "
 You are a final quality control editor for translated Russian content. Fix awkward phrasing and make text natural for Russian speakers.
 
@@ -51,21 +56,15 @@ You are a final quality control editor for translated Russian content. Fix awkwa
 
   Return ONLY corrected Russian text, no explanations. 
 ";
-        // Create Anthropic client
-        let agent = openai::Client::new(&api_key)
-            .completion_model("gpt-5")
-            .completions_api()
-            .into_agent_builder()
-            .preamble(system_prompt)
-            .max_tokens(800)
-            .build();
-
         let prompt = format!("Fix the following Russian text if needed:\n{post_content}.\n\nReturn ONLY corrected Russian text, no explanations.");
 
         // Prompt the agent and print the response
-        let response = agent.prompt(prompt).await?;
+        let response = self
+            .llm
+            .complete(&LlmRequest::new(Self::NAME, system_prompt, prompt))
+            .await?;
 
-        let result = QAControllerResult { content: response };
+        let result = QAControllerResult { content: response.content };
 
         Ok(result)
     }
@@ -0,0 +1,137 @@
+use crate::domain::content::{RerankFuture, Reranker, RetrievedChunk};
+use crate::domain::llm::{
+    complete_structured, provider_from_env, LlmConfig, LlmProvider, LlmProviderKind, LlmRequest,
+    StructuredOutput,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const MAX_REPAIR_ATTEMPTS: usize = 1;
+/// Longer chunks are cut for the prompt, the start is enough to judge relevance.
+const MAX_PASSAGE_CHARS: usize = 1200;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PassageScore {
+    /// Number of the passage as given in the prompt, from 1
+    pub passage: usize,
+    /// 0 when unrelated to the topic, 10 when it directly answers it
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RelevanceScores {
+    pub scores: Vec<PassageScore>,
+}
+
+impl StructuredOutput for RelevanceScores {
+    fn validate(&self) -> Result<(), String> {
+        match self
+            .scores
+            .iter()
+            .find(|score| !(0.0..=10.0).contains(&score.score))
+        {
+            Some(score) => Err(format!(
+                "score of passage {} is {}, expected 0 to 10",
+                score.passage, score.score
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Re-ranker that asks a model to score the relevance of passages to the topic,
+/// `batch_size` passages per call.
+pub struct LlmReranker {
+    llm: Arc<dyn LlmProvider>,
+    batch_size: usize,
+}
+
+impl LlmReranker {
+    pub const NAME: &'static str = "reranker";
+
+    pub fn new(llm: Arc<dyn LlmProvider>) -> Self {
+        Self {
+            llm,
+            batch_size: 10,
+        }
+    }
+
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        Ok(Self::new(provider_from_env(
+            Self::NAME,
+            Self::default_config(),
+        )?))
+    }
+
+    pub fn default_config() -> LlmConfig {
+        LlmConfig::new(LlmProviderKind::OpenAi, "gpt-5-mini").max_tokens(400)
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Scores from 0 to 1 in the order of `batch`; passages the model skipped get 0.
+    async fn score_batch(
+        &self,
+        topic: &str,
+        batch: &[RetrievedChunk],
+    ) -> Result<Vec<f64>, anyhow::Error> {
+        let system_prompt = "
+            You judge how useful passages from scientific sources are for writing a popular science post on a topic.
+
+            Score every passage from 0 to 10:
+            10 - directly explains or answers the topic,
+            5 - related background,
+            0 - unrelated or only shares words with the topic.
+
+            Judge each passage on its own, do not favour passages for their position.";
+        let passages = batch
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| {
+                let text: String = candidate
+                    .chunk
+                    .text
+                    .chars()
+                    .take(MAX_PASSAGE_CHARS)
+                    .collect();
+                format!("[{}] {}\n{}", index + 1, candidate.chunk.source_title, text)
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n");
+        let prompt = format!("Topic: {}\n\nPassages:\n\n{}", topic, passages);
+        let result = complete_structured::<RelevanceScores>(
+            self.llm.as_ref(),
+            &LlmRequest::new(Self::NAME, system_prompt, prompt),
+            MAX_REPAIR_ATTEMPTS,
+        )
+        .await?;
+
+        let mut scores = vec![0.0; batch.len()];
+        for score in result.scores {
+            if let Some(slot) = score.passage.checked_sub(1).and_then(|i| scores.get_mut(i)) {
+                *slot = score.score / 10.0;
+            }
+        }
+        Ok(scores)
+    }
+}
+
+impl Reranker for LlmReranker {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn rerank<'a>(&'a self, topic: &'a str, candidates: &'a [RetrievedChunk]) -> RerankFuture<'a> {
+        Box::pin(async move {
+            let mut scores = Vec::with_capacity(candidates.len());
+            for batch in candidates.chunks(self.batch_size) {
+                scores.extend(self.score_batch(topic, batch).await?);
+            }
+            Ok(scores)
+        })
+    }
+}
@@ -1,10 +1,6 @@
-use dotenvy::dotenv;
-use rig::{
-    client::CompletionClient,
-    completion::Prompt,
-    providers::anthropic::{self},
-};
+use crate::domain::llm::{provider_from_env, LlmConfig, LlmProvider, LlmProviderKind, LlmRequest};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ResearcherResult {
@@ -13,11 +9,25 @@ pub struct ResearcherResult {
 
 const RESEARCH_MAX_WORDS_LENGTH: usize = 600;
 
-pub struct Researcher {}
+pub struct Researcher {
+    llm: Arc<dyn LlmProvider>,
+}
 
 impl Researcher {
-    pub fn new() -> Self {
-        Self {}
+    pub const NAME: &'static str = "researcher";
+
+    pub fn new(llm: Arc<dyn LlmProvider>) -> Self {
+        Self { llm }
+    }
+
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        Ok(Self::new(provider_from_env(Self::NAME, Self::default_config())?))
+    }
+
+    pub fn default_config() -> LlmConfig {
+        LlmConfig::new(LlmProviderKind::Anthropic, "claude-sonnet-4-20250514")
+            .max_tokens(800)
+            .temperature(0.5)
     }
 
     pub async fn research(
@@ -25,10 +35,6 @@ impl Researcher {
         topic: String,
         context: String,
     ) -> Result<ResearcherResult, anyhow::Error> {
-        dotenv().ok();
-        let client = anthropic::Client::new(
-            &std::env::var("ADCO_ANTHROPIC_API_KEY").expect("ANTHROPIC_API_KEY not set"),
-        );
         let system_prompt = "
 Your job: gather and explain advanced AI/tech/science topics in a **clear scientific-popular style**.  
 
@@ -39,20 +45,15 @@ Rules:
 - Avoid slang, jokes, or philosophy. Neutral, precise, digestible.    
 - Provide just plain text without any other text or markdown    
 ";
-        // Create agent with a single context prompt
-        let agent = client
-            .agent("claude-sonnet-4-20250514")
-            .preamble(system_prompt)
-            .max_tokens(800)
-            .temperature(0.5)
-            .build();
-
         let prompt = format!("Research the topic:\n\n{topic}.\nContext from internet search:\n\n{context}.\nResearch must be less than {RESEARCH_MAX_WORDS_LENGTH} words.");
 
         // Prompt the agent and print the response
-        let response = agent.prompt(prompt).await?;
+        let response = self
+            .llm
+            .complete(&LlmRequest::new(Self::NAME, system_prompt, prompt))
+            .await?;
 
-        let result = ResearcherResult { content: response };
+        let result = ResearcherResult { content: response.content };
 
         Ok(result)
     }
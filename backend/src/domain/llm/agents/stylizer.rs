@@ -1,10 +1,6 @@
-use dotenvy::dotenv;
-use rig::{
-    client::CompletionClient,
-    completion::Prompt,
-    providers::openai::{self},
-};
+use crate::domain::llm::{provider_from_env, LlmConfig, LlmProvider, LlmProviderKind, LlmRequest};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StylizerResult {
@@ -13,11 +9,23 @@ pub struct StylizerResult {
 
 const POST_MAX_CHARACTERS_LENGTH: usize = 1500;
 
-pub struct Stylizer {}
+pub struct Stylizer {
+    llm: Arc<dyn LlmProvider>,
+}
 
 impl Stylizer {
-    pub fn new() -> Self {
-        Self {}
+    pub const NAME: &'static str = "stylizer";
+
+    pub fn new(llm: Arc<dyn LlmProvider>) -> Self {
+        Self { llm }
+    }
+
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        Ok(Self::new(provider_from_env(Self::NAME, Self::default_config())?))
+    }
+
+    pub fn default_config() -> LlmConfig {
+        LlmConfig::new(LlmProviderKind::OpenAi, "gpt-5-mini").max_tokens(800)
     }
 
     pub async fn stylize(
@@ -25,9 +33,6 @@ impl Stylizer {
         topic: String,
         sources: String,
     ) -> Result<StylizerResult, anyhow::Error> {
-        dotenv().ok();
-        let api_key = std::env::var("ADCO_OPEN_AI_API_KEY")?;
-
         let system_prompt = "
 Your job: transform scientific content into accessible popular science posts for a general audience.
 
@@ -86,21 +91,15 @@ Quality gate:
 - Does this sound like a human conversation, not a textbook?
 - Did I avoid announcing structural elements like 'Conclusion' or 'Summary'?
 ";
-        // Create Anthropic client
-        let agent = openai::Client::new(&api_key)
-            .completion_model("gpt-5-mini")
-            .completions_api()
-            .into_agent_builder()
-            .preamble(system_prompt)
-            .max_tokens(800)
-            .build();
-
         let prompt = format!("Write the post about the given research result:\n{topic}.\n\nSources:\n\n{sources}.\n\nProvide Telegram post in HTML format. Post must be less than {POST_MAX_CHARACTERS_LENGTH} characters (including spaces and HTML formatting).");
 
         // Prompt the agent and print the response
-        let response = agent.prompt(prompt).await?;
+        let response = self
+            .llm
+            .complete(&LlmRequest::new(Self::NAME, system_prompt, prompt))
+            .await?;
 
-        let result = StylizerResult { content: response };
+        let result = StylizerResult { content: response.content };
 
         Ok(result)
     }
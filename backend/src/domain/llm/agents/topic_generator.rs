@@ -1,35 +1,99 @@
-use dotenvy::dotenv;
+use adco_shared::theme::TopicTheme;
 use rand::rng;
-use rand::seq::IndexedRandom;
-use rig::{client::CompletionClient, completion::Prompt, providers::openai};
 use serde::{Deserialize, Serialize};
 use std::io::{self, Write};
-use tracing::info;
+use std::sync::Arc;
+use tracing::{info, warn};
 use crate::appstate::APP_STATE;
+use crate::domain::llm::{
+    complete_structured, provider_from_env, LlmConfig, LlmProvider, LlmProviderKind, LlmRequest,
+    StructuredOutput,
+};
+use crate::domain::content::{SimilarTopic, TopicHistory, TopicNoveltyConfig};
+use crate::domain::themes::pick_theme;
+use schemars::JsonSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TopicGeneratorResult {
+    /// An intriguing, timeless question or concept about the given theme
     pub topic: String,
+    /// Abstract keywords for finding relevant scientific sources, no dates
     pub full_search_query: String,
+    /// Just one keyword or sentence, no dates
     pub short_search_query: String,
+    /// Theme the topic was generated for, filled in by the generator
+    #[serde(default)]
+    #[schemars(skip)]
+    pub theme: Option<String>,
 }
 
-pub struct TopicGenerator {}
+impl StructuredOutput for TopicGeneratorResult {
+    fn validate(&self) -> Result<(), String> {
+        if self.topic.trim().is_empty() {
+            return Err("topic is empty".to_string());
+        }
+        if self.full_search_query.trim().is_empty() || self.short_search_query.trim().is_empty() {
+            return Err("search queries must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// How many times a malformed topic JSON is sent back to the model for repair.
+const MAX_REPAIR_ATTEMPTS: usize = 2;
+
+pub struct TopicGenerator {
+    llm: Arc<dyn LlmProvider>,
+    themes: Vec<TopicTheme>,
+    history: Option<Arc<dyn TopicHistory>>,
+    novelty: TopicNoveltyConfig,
+}
 
 impl TopicGenerator {
-    pub fn new() -> Self {
-        Self {}
+    pub const NAME: &'static str = "topic_generator";
+
+    pub fn new(llm: Arc<dyn LlmProvider>) -> Self {
+        Self {
+            llm,
+            themes: Vec::new(),
+            history: None,
+            novelty: TopicNoveltyConfig::default(),
+        }
     }
 
-    pub async fn generate_topic(&self) -> Result<TopicGeneratorResult, anyhow::Error> {
-        dotenv().ok();
-        let api_key = std::env::var("ADCO_OPEN_AI_API_KEY")?;
-        // Create Anthropic client
-        let agent = openai::Client::new(&api_key)
-            .completion_model("gpt-5-mini")
-            .completions_api()
-            .into_agent_builder()
-            .preamble("
+    /// Themes to pick from when no theme is given, see [`pick_theme`].
+    pub fn with_themes(mut self, themes: Vec<TopicTheme>) -> Self {
+        self.themes = themes;
+        self
+    }
+
+    /// Past topics to stay away from: the nearest ones are listed in the prompt and a
+    /// topic too similar to any of them is generated again.
+    pub fn with_history(
+        mut self,
+        history: Arc<dyn TopicHistory>,
+        novelty: TopicNoveltyConfig,
+    ) -> Self {
+        self.history = Some(history);
+        self.novelty = novelty;
+        self
+    }
+
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        Ok(Self::new(provider_from_env(Self::NAME, Self::default_config())?))
+    }
+
+    pub fn default_config() -> LlmConfig {
+        LlmConfig::new(LlmProviderKind::OpenAi, "gpt-5-mini").max_tokens(300)
+    }
+
+    /// Generates a topic within `theme`, or within a theme picked from the configured
+    /// ones when none is given.
+    pub async fn generate_topic(
+        &self,
+        theme: Option<&str>,
+    ) -> Result<TopicGeneratorResult, anyhow::Error> {
+        let system_prompt = "
             IMPORTANT RULES:
             - Create CONCEPTUAL topics, not news-based or time-specific ones
             - Focus on fundamental principles, mechanisms, and fascinating questions
@@ -54,23 +118,31 @@ impl TopicGenerator {
             full_search_query (abstract keywords for finding relevant scientific sources, no dates),
             short_search_query (just one keyword or sentence, no dates).
 
-            Do not include any extra text, explanations, or markdown.")
-            .max_tokens(300)
-            .build();
+            Do not include any extra text, explanations, or markdown.";
         let mut avoiding_topics: Vec<String> = Vec::new();
         info!("Interactive mode: {}", APP_STATE.is_interactive_mode);
         let mut result: TopicGeneratorResult;
         loop {
-            let mut rng = rng();
-            let random_theme = TOPIC_THEMES.choose(&mut rng).unwrap();
-            info!("========== Random theme: {}", random_theme);
-            if APP_STATE.is_interactive_mode {
+            // ThreadRng is not Send, keep it out of the awaits below
+            let current_theme = match theme {
+                Some(theme) => theme.to_string(),
+                None => pick_theme(&self.themes, chrono::Utc::now(), &mut rng())
+                    .ok_or_else(|| anyhow::anyhow!("No enabled topic themes to pick from"))?
+                    .name
+                    .clone(),
+            };
+            info!("========== Theme: {}", current_theme);
+            if APP_STATE.is_interactive_mode && theme.is_none() {
                 info!("Continue or generate new random theme? (y/N): ");
                 if !self.confirm_continue().await {
                     info!("Generating new random theme...");
                     continue;
                 }
             }
+            for past in self.nearest_topics(&current_theme).await? {
+                push_unique(&mut avoiding_topics, past.topic);
+            }
+            let mut attempts = 0;
              // Prompt the agent and print the response
             loop {
                 let avoiding_topics_str = {
@@ -81,21 +153,50 @@ impl TopicGenerator {
                     }
                 };
                 let prompt: String = format!(
-                    "Generate an abstract, timeless topic for a popular science blog post about {random_theme}.
+                    "Generate an abstract, timeless topic for a popular science blog post about {current_theme}.
                     Avoid topics that are already in the list of avoiding topics: {avoiding_topics_str}.
                     If the topic is already in the list of avoiding topics, generate a new one.
                     ",
                     );
 
-                let response = agent.prompt(prompt.to_string()).await?;
-
-                result = serde_json::from_str::<TopicGeneratorResult>(&response)?;
+                result = complete_structured::<TopicGeneratorResult>(
+                    self.llm.as_ref(),
+                    &LlmRequest::new(Self::NAME, system_prompt, prompt),
+                    MAX_REPAIR_ATTEMPTS,
+                )
+                .await?;
+                result.theme = Some(current_theme.clone());
                 info!("========== Result: {}", result.topic);
                 info!("========== Full search query: {}", result.full_search_query);
                 info!(
                     "========== Short search query: {}",
                     result.short_search_query
                 );
+                let nearest = self.nearest_topics(&result.topic).await?;
+                if let Some(repeat) = nearest
+                    .first()
+                    .filter(|past| past.similarity >= self.novelty.threshold)
+                {
+                    attempts += 1;
+                    warn!(
+                        "Topic is too similar ({:.2}) to the existing post topic '{}'",
+                        repeat.similarity, repeat.topic
+                    );
+                    if attempts >= self.novelty.max_attempts {
+                        anyhow::bail!(
+                            "No new topic after {} attempts, '{}' repeats '{}' ({:.2})",
+                            attempts,
+                            result.topic,
+                            repeat.topic,
+                            repeat.similarity
+                        );
+                    }
+                    push_unique(&mut avoiding_topics, result.topic.clone());
+                    for past in nearest {
+                        push_unique(&mut avoiding_topics, past.topic);
+                    }
+                    continue;
+                }
                 if APP_STATE.is_interactive_mode {
                     info!("Continue or generate new topic? (y/N): ");
                     if !self.confirm_continue().await {
@@ -111,6 +212,52 @@ impl TopicGenerator {
         Ok(result)
     }
 
+    /// Keeps the given topic as is and only asks the model for the search queries.
+    pub async fn search_queries_for_topic(
+        &self,
+        topic: &str,
+    ) -> Result<TopicGeneratorResult, anyhow::Error> {
+        let system_prompt = "
+            You write search queries for finding scientific sources about a popular science topic.
+
+            Provide result in pure JSON format with the following fields:
+            topic (the given topic, unchanged),
+            full_search_query (abstract keywords for finding relevant scientific sources, no dates),
+            short_search_query (just one keyword or sentence, no dates).
+
+            Do not include any extra text, explanations, or markdown.";
+        let prompt = format!("Write search queries for the topic: {}", topic);
+        let mut result = complete_structured::<TopicGeneratorResult>(
+            self.llm.as_ref(),
+            &LlmRequest::new(Self::NAME, system_prompt, prompt),
+            MAX_REPAIR_ATTEMPTS,
+        )
+        .await?;
+        result.topic = topic.to_string();
+        // The topic was asked for explicitly, a repeat is only reported
+        if let Some(repeat) = self
+            .nearest_topics(topic)
+            .await?
+            .into_iter()
+            .find(|past| past.similarity >= self.novelty.threshold)
+        {
+            warn!(
+                "Requested topic is similar ({:.2}) to the existing post topic '{}'",
+                repeat.similarity, repeat.topic
+            );
+        }
+        info!("========== Full search query: {}", result.full_search_query);
+        info!("========== Short search query: {}", result.short_search_query);
+        Ok(result)
+    }
+
+    async fn nearest_topics(&self, text: &str) -> Result<Vec<SimilarTopic>, anyhow::Error> {
+        match &self.history {
+            Some(history) => history.nearest_topics(text, self.novelty.avoid_count).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
     async fn confirm_continue(&self) -> bool {
         io::stdout().flush().unwrap();
 
@@ -121,73 +268,8 @@ impl TopicGenerator {
     }
 }
 
-const TOPIC_THEMES: &[&str] = &[
-    // Мозг и нейротех
-    "brain-computer interfaces",
-    "neural decoding",
-    "neuroplasticity",
-    "synaptic mechanisms",
-    "memory consolidation",
-    "predictive brain",
-    "consciousness",
-    "sleep and dreams",
-    // Когнитивные науки
-    "cognitive biases",
-    "decision making",
-    "attention and focus",
-    "language processing",
-    "spatial navigation",
-    "temporal perception",
-    "social cognition",
-    // Нейронауки
-    "neurotransmitters",
-    "brain networks",
-    "neural development",
-    "brain rhythms",
-    "sensory processing",
-    "motor control",
-    "neural computation",
-    // AI и машинное обучение
-    "artificial neural networks",
-    "deep learning",
-    "reinforcement learning",
-    "neural architecture",
-    "brain-inspired AI",
-    "spiking neural networks",
-    // Физика и математика
-    "quantum mechanics",
-    "particle physics",
-    "relativity",
-    "thermodynamics",
-    "waves and vibrations",
-    "chaos theory",
-    "symmetry in nature",
-    "mathematical patterns",
-    // Биология и эволюция
-    "evolutionary biology",
-    "genetic algorithms",
-    "protein folding",
-    "cellular automata",
-    "ecosystem dynamics",
-    "biomechanics",
-    "molecular biology",
-    // Космос и астрофизика
-    "black holes",
-    "dark matter",
-    "exoplanets",
-    "stellar evolution",
-    "cosmic rays",
-    "gravitational waves",
-    "space-time",
-    "astrobiology",
-    "cosmic inflation",
-    // Инженерия и технологии
-    "robotics",
-    "smart materials",
-    "nanotechnology",
-    "renewable energy",
-    "architecture and design",
-    "aerodynamics",
-    "sensors and automation",
-    "biomimetics",
-];
+fn push_unique(topics: &mut Vec<String>, topic: String) {
+    if !topics.contains(&topic) {
+        topics.push(topic);
+    }
+}
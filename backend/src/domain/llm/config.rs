@@ -0,0 +1,130 @@
+use super::provider::LlmError;
+use crate::domain::infra::resilience::host_of;
+use dotenvy::dotenv;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum LlmProviderKind {
+    OpenAi,
+    Anthropic,
+    /// Any server that speaks the OpenAI chat completions API (llama.cpp, vLLM, Ollama, ...).
+    OpenAiCompatible,
+}
+
+impl TryFrom<String> for LlmProviderKind {
+    type Error = LlmError;
+
+    fn try_from(provider: String) -> Result<Self, Self::Error> {
+        match provider.to_lowercase().as_str() {
+            "openai" => Ok(LlmProviderKind::OpenAi),
+            "anthropic" => Ok(LlmProviderKind::Anthropic),
+            "openai_compatible" => Ok(LlmProviderKind::OpenAiCompatible),
+            other => Err(LlmError::InvalidConfig(format!("unknown provider '{}'", other))),
+        }
+    }
+}
+
+impl std::fmt::Display for LlmProviderKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LlmProviderKind::OpenAi => write!(f, "openai"),
+            LlmProviderKind::Anthropic => write!(f, "anthropic"),
+            LlmProviderKind::OpenAiCompatible => write!(f, "openai_compatible"),
+        }
+    }
+}
+
+/// Provider and model settings of a single agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmConfig {
+    pub provider: LlmProviderKind,
+    pub model: String,
+    pub temperature: Option<f64>,
+    pub max_tokens: Option<u64>,
+    pub base_url: Option<String>,
+    #[serde(skip_serializing)]
+    pub api_key: Option<String>,
+}
+
+impl LlmConfig {
+    pub fn new(provider: LlmProviderKind, model: &str) -> Self {
+        Self {
+            provider,
+            model: model.to_string(),
+            temperature: None,
+            max_tokens: None,
+            base_url: None,
+            api_key: None,
+        }
+    }
+
+    pub fn temperature(mut self, temperature: f64) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn max_tokens(mut self, max_tokens: u64) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Host the provider talks to, used for per-host concurrency limits.
+    pub fn host(&self) -> String {
+        match (&self.base_url, self.provider) {
+            (Some(base_url), _) => host_of(base_url),
+            (None, LlmProviderKind::Anthropic) => "api.anthropic.com".to_string(),
+            (None, _) => "api.openai.com".to_string(),
+        }
+    }
+
+    /// Applies env overrides on top of the agent defaults.
+    ///
+    /// Lookup order for every setting: `ADCO_LLM_<AGENT>_<SETTING>`, then `ADCO_LLM_<SETTING>`,
+    /// then the default. Settings: PROVIDER, MODEL, TEMPERATURE, MAX_TOKENS, BASE_URL, API_KEY.
+    pub fn from_env(agent: &str, defaults: LlmConfig) -> Result<Self, LlmError> {
+        dotenv().ok();
+        let agent = agent.to_uppercase();
+        let lookup = |setting: &str| {
+            std::env::var(format!("ADCO_LLM_{}_{}", agent, setting))
+                .or_else(|_| std::env::var(format!("ADCO_LLM_{}", setting)))
+                .ok()
+                .filter(|value| !value.is_empty())
+        };
+
+        let mut config = defaults;
+        if let Some(provider) = lookup("PROVIDER") {
+            config.provider = LlmProviderKind::try_from(provider)?;
+        }
+        if let Some(model) = lookup("MODEL") {
+            config.model = model;
+        }
+        if let Some(temperature) = lookup("TEMPERATURE") {
+            config.temperature = Some(temperature.parse().map_err(|_| {
+                LlmError::InvalidConfig(format!("invalid temperature '{}'", temperature))
+            })?);
+        }
+        if let Some(max_tokens) = lookup("MAX_TOKENS") {
+            config.max_tokens = Some(max_tokens.parse().map_err(|_| {
+                LlmError::InvalidConfig(format!("invalid max_tokens '{}'", max_tokens))
+            })?);
+        }
+        if let Some(base_url) = lookup("BASE_URL") {
+            config.base_url = Some(base_url);
+        }
+        config.api_key = lookup("API_KEY").or_else(|| {
+            let key_var = match config.provider {
+                LlmProviderKind::OpenAi => "ADCO_OPEN_AI_API_KEY",
+                LlmProviderKind::Anthropic => "ADCO_ANTHROPIC_API_KEY",
+                LlmProviderKind::OpenAiCompatible => return None,
+            };
+            std::env::var(key_var).ok()
+        });
+        if config.provider == LlmProviderKind::OpenAiCompatible && config.base_url.is_none() {
+            return Err(LlmError::InvalidConfig(format!(
+                "ADCO_LLM_{}_BASE_URL is required for openai_compatible provider",
+                agent
+            )));
+        }
+        Ok(config)
+    }
+}
@@ -0,0 +1,149 @@
+use super::provider::{LlmError, LlmFuture, LlmProvider, LlmRequest, LlmResponse, LlmUsage};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::info;
+
+/// Recorded responses of one agent, stored as `<fixtures_dir>/<agent>.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentFixtures {
+    /// Returned when no response is recorded for the prompt hash.
+    #[serde(default)]
+    pub default: Option<String>,
+    /// Responses keyed by `prompt_hash`.
+    #[serde(default)]
+    pub responses: BTreeMap<String, RecordedResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub prompt: String,
+    pub response: String,
+}
+
+impl AgentFixtures {
+    pub fn path(dir: &Path, agent: &str) -> PathBuf {
+        dir.join(format!("{}.json", agent))
+    }
+
+    pub fn load(dir: &Path, agent: &str) -> Result<Self, LlmError> {
+        let path = Self::path(dir, agent);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| LlmError::InvalidConfig(format!("{}: {}", path.display(), e)))?;
+        serde_json::from_str(&content)
+            .map_err(|e| LlmError::InvalidConfig(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn save(&self, dir: &Path, agent: &str) -> Result<(), LlmError> {
+        let path = Self::path(dir, agent);
+        std::fs::create_dir_all(dir)
+            .and_then(|_| std::fs::write(&path, serde_json::to_string_pretty(self).unwrap_or_default()))
+            .map_err(|e| LlmError::InvalidConfig(format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// Stable key of a request: sha256 of preamble and prompt.
+pub fn prompt_hash(request: &LlmRequest) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request.preamble.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(request.prompt.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Deterministic provider that replays recorded responses, for offline runs and tests.
+pub struct FixtureLlmProvider {
+    agent: String,
+    fixtures: AgentFixtures,
+}
+
+impl FixtureLlmProvider {
+    pub fn new(agent: &str, fixtures: AgentFixtures) -> Self {
+        Self { agent: agent.to_string(), fixtures }
+    }
+
+    pub fn load(dir: &Path, agent: &str) -> Result<Self, LlmError> {
+        Ok(Self::new(agent, AgentFixtures::load(dir, agent)?))
+    }
+
+    /// Only the responses recorded for a prompt hash, without the `default` fallback,
+    /// so a prompt changed since the recording fails until it is recorded again.
+    pub fn load_recorded(dir: &Path, agent: &str) -> Result<Self, LlmError> {
+        let mut fixtures = AgentFixtures::load(dir, agent)?;
+        fixtures.default = None;
+        Ok(Self::new(agent, fixtures))
+    }
+
+    fn respond(&self, request: &LlmRequest) -> Result<LlmResponse, LlmError> {
+        let hash = prompt_hash(request);
+        let content = match self.fixtures.responses.get(&hash) {
+            Some(recorded) => recorded.response.clone(),
+            None => self.fixtures.default.clone().ok_or_else(|| {
+                LlmError::InvalidConfig(format!(
+                    "no fixture for agent {} and prompt hash {}, record it with ADCO_LLM_RECORD_DIR",
+                    self.agent, hash
+                ))
+            })?,
+        };
+        Ok(LlmResponse {
+            content,
+            model: self.model().to_string(),
+            usage: LlmUsage::default(),
+        })
+    }
+}
+
+impl LlmProvider for FixtureLlmProvider {
+    fn model(&self) -> &str {
+        "fixture"
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(async move { self.respond(request) })
+    }
+}
+
+/// Wraps a real provider and appends every response to the agent's fixture file.
+pub struct RecordingLlmProvider {
+    inner: Arc<dyn LlmProvider>,
+    dir: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl RecordingLlmProvider {
+    pub fn new(inner: Arc<dyn LlmProvider>, dir: PathBuf) -> Self {
+        Self { inner, dir, lock: Mutex::new(()) }
+    }
+
+    async fn record(&self, request: &LlmRequest) -> Result<LlmResponse, LlmError> {
+        let response = self.inner.complete(request).await?;
+        let _guard = self.lock.lock().await;
+        let mut fixtures = AgentFixtures::load(&self.dir, &request.agent)?;
+        fixtures.responses.insert(
+            prompt_hash(request),
+            RecordedResponse {
+                prompt: request.prompt.clone(),
+                response: response.content.clone(),
+            },
+        );
+        fixtures.save(&self.dir, &request.agent)?;
+        info!("Recorded {} response to {}", request.agent, self.dir.display());
+        Ok(response)
+    }
+}
+
+impl LlmProvider for RecordingLlmProvider {
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(self.record(request))
+    }
+}
@@ -0,0 +1,42 @@
+use super::provider::{LlmError, LlmFuture, LlmProvider, LlmRequest, LlmResponse};
+use crate::domain::usage::{cost_usd, record_call};
+use adco_shared::usage::{LlmCall, LlmCallKind};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Records tokens, latency and cost of every successful completion.
+pub struct MeteredLlmProvider {
+    inner: Arc<dyn LlmProvider>,
+}
+
+impl MeteredLlmProvider {
+    pub fn new(inner: Arc<dyn LlmProvider>) -> Self {
+        Self { inner }
+    }
+
+    async fn complete_metered(&self, request: &LlmRequest) -> Result<LlmResponse, LlmError> {
+        let start_time = Instant::now();
+        let response = self.inner.complete(request).await?;
+        let mut call = LlmCall::new(&request.agent, LlmCallKind::Completion, &response.model);
+        call.input_tokens = response.usage.input_tokens as i64;
+        call.output_tokens = response.usage.output_tokens as i64;
+        call.latency_ms = start_time.elapsed().as_millis() as i64;
+        call.cost_usd = cost_usd(
+            &response.model,
+            response.usage.input_tokens,
+            response.usage.output_tokens,
+        );
+        record_call(call);
+        Ok(response)
+    }
+}
+
+impl LlmProvider for MeteredLlmProvider {
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(self.complete_metered(request))
+    }
+}
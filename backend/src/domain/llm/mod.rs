@@ -1,2 +1,18 @@
+pub mod agent_providers;
 pub mod agents;
+pub mod config;
+pub mod fixture_provider;
+pub mod metered_provider;
+pub mod provider;
+pub mod resilient_provider;
+pub mod rig_provider;
+pub mod structured;
 
+pub use agent_providers::*;
+pub use config::*;
+pub use fixture_provider::*;
+pub use metered_provider::*;
+pub use provider::*;
+pub use resilient_provider::*;
+pub use rig_provider::*;
+pub use structured::*;
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use thiserror::Error;
+
+/// Single completion call made by an agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmRequest {
+    /// Agent that makes the call, e.g. "researcher".
+    pub agent: String,
+    pub preamble: String,
+    pub prompt: String,
+}
+
+impl LlmRequest {
+    pub fn new(agent: &str, preamble: &str, prompt: String) -> Self {
+        Self {
+            agent: agent.to_string(),
+            preamble: preamble.to_string(),
+            prompt,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LlmUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmResponse {
+    pub content: String,
+    pub model: String,
+    pub usage: LlmUsage,
+}
+
+pub type LlmFuture<'a> = Pin<Box<dyn Future<Output = Result<LlmResponse, LlmError>> + Send + 'a>>;
+
+/// Completion backend used by the agents. Implementations own their model settings,
+/// so the same agent code runs against OpenAI, Anthropic or a local OpenAI-compatible server.
+pub trait LlmProvider: Send + Sync {
+    fn model(&self) -> &str;
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a>;
+}
+
+#[derive(Error, Debug)]
+pub enum LlmError {
+    #[error("Missing environment variable: {0}")]
+    MissingApiKey(String),
+    #[error("Invalid LLM config: {0}")]
+    InvalidConfig(String),
+    #[error("Client error: {0}")]
+    Client(String),
+    #[error("Prompt error: {0}")]
+    Prompt(Box<rig::completion::PromptError>),
+    #[error(transparent)]
+    Timeout(#[from] crate::domain::infra::resilience::TimeoutError),
+}
+
+impl From<rig::completion::PromptError> for LlmError {
+    fn from(error: rig::completion::PromptError) -> Self {
+        LlmError::Prompt(Box::new(error))
+    }
+}
@@ -0,0 +1,110 @@
+use super::provider::{LlmError, LlmFuture, LlmProvider, LlmRequest, LlmResponse};
+use crate::domain::infra::resilience::{acquire_host_slot, with_retry, RetryDecision, RetryPolicy};
+use rig::completion::{CompletionError, PromptError};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Adds retries with backoff, rate-limit handling, timeouts and the per-host
+/// concurrency limit to any provider.
+pub struct ResilientLlmProvider {
+    inner: Arc<dyn LlmProvider>,
+    host: String,
+    policy: RetryPolicy,
+}
+
+impl ResilientLlmProvider {
+    pub fn new(inner: Arc<dyn LlmProvider>, host: &str, policy: RetryPolicy) -> Self {
+        Self {
+            inner,
+            host: host.to_string(),
+            policy,
+        }
+    }
+
+    async fn complete_with_retry(&self, request: &LlmRequest) -> Result<LlmResponse, LlmError> {
+        let operation = format!("{} completion ({})", request.agent, self.inner.model());
+        with_retry(&self.policy, &operation, classify, || async {
+            let _slot = acquire_host_slot(&self.host).await;
+            self.inner.complete(request).await
+        })
+        .await
+    }
+}
+
+impl LlmProvider for ResilientLlmProvider {
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(self.complete_with_retry(request))
+    }
+}
+
+fn classify(error: &LlmError) -> RetryDecision {
+    match error {
+        LlmError::Timeout(_) => RetryDecision::Retry { after: None },
+        LlmError::Prompt(prompt_error) => match prompt_error.as_ref() {
+            PromptError::CompletionError(CompletionError::HttpError(e)) => {
+                let transient_status = e
+                    .status()
+                    .map(|status| status.as_u16() == 429 || status.is_server_error())
+                    .unwrap_or(false);
+                if e.is_timeout() || e.is_connect() || e.is_request() || transient_status {
+                    RetryDecision::Retry { after: None }
+                } else {
+                    RetryDecision::Fail
+                }
+            }
+            // rig turns non-2xx responses into the provider's error text
+            PromptError::CompletionError(
+                CompletionError::ProviderError(message) | CompletionError::ResponseError(message),
+            ) => classify_provider_message(message),
+            _ => RetryDecision::Fail,
+        },
+        _ => RetryDecision::Fail,
+    }
+}
+
+const TRANSIENT_MARKERS: &[&str] = &[
+    "rate limit",
+    "rate_limit",
+    "too many requests",
+    "overloaded",
+    "server_error",
+    "internal server error",
+    "bad gateway",
+    "service unavailable",
+    "timeout",
+    "timed out",
+];
+
+fn classify_provider_message(message: &str) -> RetryDecision {
+    let message = message.to_lowercase();
+    if TRANSIENT_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+    {
+        RetryDecision::Retry {
+            after: parse_try_again_in(&message),
+        }
+    } else {
+        RetryDecision::Fail
+    }
+}
+
+/// OpenAI puts the wait time into the message: "Please try again in 1.5s" / "in 350ms".
+fn parse_try_again_in(message: &str) -> Option<Duration> {
+    let rest = &message[message.find("try again in ")? + "try again in ".len()..];
+    let number_end = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(rest.len());
+    let value = rest[..number_end].parse::<f64>().ok()?;
+    if rest[number_end..].starts_with("ms") {
+        Some(Duration::from_secs_f64(value / 1000.0))
+    } else if rest[number_end..].starts_with('s') {
+        Some(Duration::from_secs_f64(value))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,99 @@
+use super::config::{LlmConfig, LlmProviderKind};
+use super::provider::{LlmError, LlmFuture, LlmProvider, LlmRequest, LlmResponse, LlmUsage};
+use rig::{
+    agent::AgentBuilder,
+    client::CompletionClient,
+    completion::{CompletionModel, Prompt},
+    providers::{anthropic, openai},
+};
+
+/// `LlmProvider` backed by rig clients, configured by `LlmConfig`.
+pub struct RigLlmProvider {
+    config: LlmConfig,
+}
+
+impl RigLlmProvider {
+    pub fn new(config: LlmConfig) -> Self {
+        Self { config }
+    }
+
+    fn api_key(&self) -> Result<String, LlmError> {
+        match (&self.config.api_key, self.config.provider) {
+            (Some(api_key), _) => Ok(api_key.clone()),
+            // Local servers usually ignore the key, but the client still sends a bearer header
+            (None, LlmProviderKind::OpenAiCompatible) => Ok("local".to_string()),
+            (None, provider) => Err(LlmError::MissingApiKey(format!("{} API key", provider))),
+        }
+    }
+
+    fn configure<M: CompletionModel>(
+        &self,
+        builder: AgentBuilder<M>,
+        request: &LlmRequest,
+    ) -> AgentBuilder<M> {
+        let mut builder = builder.preamble(&request.preamble);
+        if let Some(max_tokens) = self.config.max_tokens {
+            builder = builder.max_tokens(max_tokens);
+        }
+        if let Some(temperature) = self.config.temperature {
+            builder = builder.temperature(temperature);
+        }
+        builder
+    }
+
+    async fn prompt(&self, request: &LlmRequest) -> Result<LlmResponse, LlmError> {
+        let api_key = self.api_key()?;
+        let response = match self.config.provider {
+            LlmProviderKind::OpenAi | LlmProviderKind::OpenAiCompatible => {
+                let mut client_builder = openai::Client::builder(&api_key);
+                if let Some(base_url) = &self.config.base_url {
+                    client_builder = client_builder.base_url(base_url);
+                }
+                let client = client_builder
+                    .build()
+                    .map_err(|e| LlmError::Client(e.to_string()))?;
+                let builder = client
+                    .completion_model(&self.config.model)
+                    .completions_api()
+                    .into_agent_builder();
+                let agent = self.configure(builder, request).build();
+                agent
+                    .prompt(request.prompt.clone())
+                    .extended_details()
+                    .await?
+            }
+            LlmProviderKind::Anthropic => {
+                let mut client_builder = anthropic::Client::builder(&api_key);
+                if let Some(base_url) = &self.config.base_url {
+                    client_builder = client_builder.base_url(base_url);
+                }
+                let client = client_builder
+                    .build()
+                    .map_err(|e| LlmError::Client(e.to_string()))?;
+                let agent = self.configure(client.agent(&self.config.model), request).build();
+                agent
+                    .prompt(request.prompt.clone())
+                    .extended_details()
+                    .await?
+            }
+        };
+        Ok(LlmResponse {
+            content: response.output,
+            model: self.config.model.clone(),
+            usage: LlmUsage {
+                input_tokens: response.total_usage.input_tokens,
+                output_tokens: response.total_usage.output_tokens,
+            },
+        })
+    }
+}
+
+impl LlmProvider for RigLlmProvider {
+    fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(self.prompt(request))
+    }
+}
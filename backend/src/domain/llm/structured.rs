@@ -0,0 +1,112 @@
+use super::provider::{LlmError, LlmProvider, LlmRequest};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use tracing::warn;
+
+/// Struct an agent expects the model to return as JSON.
+pub trait StructuredOutput: DeserializeOwned + JsonSchema {
+    /// Checks that go beyond the schema (non-empty fields, ranges, ...).
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum StructuredOutputError {
+    #[error(transparent)]
+    Llm(#[from] LlmError),
+    #[error("Invalid structured output after {attempts} attempts: {error}")]
+    Invalid {
+        attempts: usize,
+        error: String,
+        response: String,
+    },
+}
+
+/// JSON schema derived from the Rust struct, pretty-printed for prompts.
+pub fn json_schema_for<T: JsonSchema>() -> String {
+    serde_json::to_string_pretty(&schemars::schema_for!(T)).unwrap_or_default()
+}
+
+/// Extracts a JSON object or array from model output: strips markdown fences
+/// and any text around the first balanced `{...}` / `[...]`.
+pub fn extract_json(text: &str) -> Option<&str> {
+    let start = text.find(['{', '['])?;
+    let open = text[start..].chars().next()?;
+    let close = if open == '{' { '}' } else { ']' };
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (offset, c) in text[start..].char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            c if c == open => depth += 1,
+            c if c == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[start..start + offset + c.len_utf8()]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses and validates a model response into `T`.
+pub fn parse_structured<T: StructuredOutput>(response: &str) -> Result<T, String> {
+    let json = extract_json(response).ok_or("response contains no JSON object")?;
+    let value = serde_json::from_str::<T>(json).map_err(|e| e.to_string())?;
+    value.validate()?;
+    Ok(value)
+}
+
+/// Asks for `T` as JSON following its schema. When the answer does not parse or validate,
+/// the error is sent back to the model, at most `max_repairs` times.
+pub async fn complete_structured<T: StructuredOutput>(
+    llm: &dyn LlmProvider,
+    request: &LlmRequest,
+    max_repairs: usize,
+) -> Result<T, StructuredOutputError> {
+    let preamble = format!(
+        "{}\n\nRespond with a single JSON value matching this JSON schema:\n{}\nDo not wrap it in markdown.",
+        request.preamble,
+        json_schema_for::<T>()
+    );
+    let mut structured_request = LlmRequest::new(&request.agent, &preamble, request.prompt.clone());
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let response = llm.complete(&structured_request).await?;
+        match parse_structured::<T>(&response.content) {
+            Ok(value) => return Ok(value),
+            Err(error) if attempts <= max_repairs => {
+                warn!(
+                    "Agent {} returned invalid JSON (attempt {}): {}",
+                    request.agent, attempts, error
+                );
+                structured_request.prompt = format!(
+                    "{}\n\nYour previous response:\n{}\n\nIt could not be used: {}\nReturn only the corrected JSON.",
+                    request.prompt, response.content, error
+                );
+            }
+            Err(error) => {
+                return Err(StructuredOutputError::Invalid {
+                    attempts,
+                    error,
+                    response: response.content,
+                })
+            }
+        }
+    }
+}
@@ -3,7 +3,12 @@ pub mod sources;
 pub mod infra;
 pub mod llm;
 pub mod content;
+pub mod feeds;
 pub mod orchestrator;
+pub mod pipeline;
 pub mod publishing;
+pub mod scheduler;
+pub mod themes;
+pub mod usage;
 
 pub use orchestrator::Orchestrator;
\ No newline at end of file
@@ -1,45 +1,347 @@
 use crate::appstate;
 use crate::domain::{
-    content::{chunker::Chunk, EmbeddingsRepository, TextChunker},
-    infra::postgres::{posts_repo::PostsRepositoryPostgres, sources_repo::SourcesRepositoryImpl},
-    llm::agents::{QAController, Researcher, Stylizer, TopicGenerator, TopicGeneratorResult},
+    content::{
+        chunker::Chunk, embedding_cache_stats, fit_to_budget, token_counter, DiversityConfig,
+        EmbeddingsRepository, HybridWeights, NamedEmbeddingModel, NoopReranker, PostTopicHistory,
+        Reranker, RetrievedChunks, TopicNoveltyConfig,
+    },
+    infra::postgres::{
+        pipeline_repo::PipelineRepositoryPostgres, posts_repo::PostsRepositoryPostgres,
+        sources_repo::SourcesRepositoryImpl, themes_repo::ThemeRepositoryPostgres,
+        usage_repo::UsageRepositoryPostgres,
+    },
+    llm::{
+        agents::{
+            LlmReranker, QAController, Researcher, Stylizer, TopicGenerator, TopicGeneratorResult,
+        },
+        AgentProviders,
+    },
+    pipeline::{events, PipelineRepository, PipelineTracker},
     posts::{PostRepository},
-    publishing::Publisher,
-    sources::{SourceRepository, HtmlParser, HttpFetcher},
+    publishing::{
+        sanitize_telegram_html, split_html_message, visible_len, ApprovalBot, Publisher,
+        TELEGRAM_MESSAGE_LIMIT,
+    },
+    sources::{SourceCollector, SourceRepository, WebSourceCollector},
+    themes::ThemeRepository,
+    usage::UsageRepository,
 };
+use adco_shared::pipeline::{PipelineRun, PipelineStageKind, RunRequest};
 use adco_shared::post::Post;
 use adco_shared::source::Source;
-use std::path::Path;
+use rig::providers::openai;
+use serde_json::json;
+use sqlx::PgPool;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::{
     fs::File,
     io::{self, Write},
 };
-use tracing::{error, info};
+use tracing::{error, info, warn};
+use uuid::Uuid;
 
-pub struct Orchestrator {}
+/// How the orchestrator decides whether a finished post goes to Telegram.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PublishApproval {
+    /// Ask on stdin.
+    Stdin,
+    /// Send the draft to the Telegram admin chat and let the buttons decide.
+    Telegram,
+    /// Keep the post as a draft.
+    Never,
+}
+
+impl PublishApproval {
+    /// Telegram when an admin chat is configured, otherwise drafts stay drafts.
+    pub fn unattended() -> Self {
+        if ApprovalBot::is_configured() {
+            PublishApproval::Telegram
+        } else {
+            PublishApproval::Never
+        }
+    }
+}
+
+pub struct Orchestrator<M: NamedEmbeddingModel = openai::EmbeddingModel> {
+    pg_pool: PgPool,
+    llm: AgentProviders,
+    embedding_model: M,
+    source_collector: Arc<dyn SourceCollector>,
+    publish_approval: PublishApproval,
+    output_dir: PathBuf,
+    topic_novelty: TopicNoveltyConfig,
+    retrieval_weights: HybridWeights,
+    retrieval_diversity: DiversityConfig,
+    reranker: Arc<dyn Reranker>,
+    research_context_tokens: usize,
+}
+
+/// Tokens of retrieved chunks passed to the Researcher when ADCO_RESEARCH_CONTEXT_TOKENS is not set.
+pub const DEFAULT_RESEARCH_CONTEXT_TOKENS: usize = 8000;
+
+/// `ADCO_RERANKER=llm` scores retrieved chunks with [`LlmReranker`]; unset or `noop`
+/// keeps the search order.
+fn reranker_from_env() -> Result<Arc<dyn Reranker>, anyhow::Error> {
+    match std::env::var("ADCO_RERANKER").unwrap_or_default().as_str() {
+        "" | "noop" => Ok(Arc::new(NoopReranker)),
+        "llm" => Ok(Arc::new(LlmReranker::from_env()?)),
+        other => anyhow::bail!("Unknown ADCO_RERANKER {}, expected llm or noop", other),
+    }
+}
 
 impl Orchestrator {
-    pub async fn orchestrate(&self) -> Result<(), anyhow::Error> {
-        let topic_generator = TopicGenerator::new();
-        let generator_result = topic_generator.generate_topic().await?;
+    pub async fn from_env() -> Result<Self, anyhow::Error> {
+        let pg_pool = appstate::APP_STATE.get_pg_pool().await;
+        Ok(Self::new(
+            &pg_pool,
+            AgentProviders::from_env()?,
+            EmbeddingsRepository::default_model(),
+            Arc::new(WebSourceCollector::new()),
+        )
+        .topic_novelty(TopicNoveltyConfig::from_env())
+        .retrieval_weights(HybridWeights::from_env())
+        .retrieval_diversity(DiversityConfig::from_env())
+        .reranker(reranker_from_env()?)
+        .research_context_tokens(
+            std::env::var("ADCO_RESEARCH_CONTEXT_TOKENS")
+                .ok()
+                .and_then(|tokens| tokens.parse().ok())
+                .unwrap_or(DEFAULT_RESEARCH_CONTEXT_TOKENS),
+        ))
+    }
+}
+
+impl<M: NamedEmbeddingModel + 'static> Orchestrator<M> {
+    pub fn new(
+        pg_pool: &PgPool,
+        llm: AgentProviders,
+        embedding_model: M,
+        source_collector: Arc<dyn SourceCollector>,
+    ) -> Self {
+        Self {
+            pg_pool: pg_pool.clone(),
+            llm,
+            embedding_model,
+            source_collector,
+            publish_approval: PublishApproval::Stdin,
+            output_dir: PathBuf::from("posts"),
+            topic_novelty: TopicNoveltyConfig::default(),
+            retrieval_weights: HybridWeights::default(),
+            retrieval_diversity: DiversityConfig::default(),
+            reranker: Arc::new(NoopReranker),
+            research_context_tokens: DEFAULT_RESEARCH_CONTEXT_TOKENS,
+        }
+    }
+
+    pub fn publish_approval(mut self, publish_approval: PublishApproval) -> Self {
+        self.publish_approval = publish_approval;
+        self
+    }
+
+    /// How generated topics are checked against the topics of existing posts.
+    pub fn topic_novelty(mut self, topic_novelty: TopicNoveltyConfig) -> Self {
+        self.topic_novelty = topic_novelty;
+        self
+    }
+
+    /// Weights of vector and full-text rankings when retrieving chunks for the Researcher.
+    pub fn retrieval_weights(mut self, retrieval_weights: HybridWeights) -> Self {
+        self.retrieval_weights = retrieval_weights;
+        self
+    }
+
+    /// MMR re-ranking and the limit of chunks per source for the Researcher's context.
+    pub fn retrieval_diversity(mut self, retrieval_diversity: DiversityConfig) -> Self {
+        self.retrieval_diversity = retrieval_diversity;
+        self
+    }
+
+    /// Re-scores the over-fetched chunks against the topic before MMR picks them.
+    pub fn reranker(mut self, reranker: Arc<dyn Reranker>) -> Self {
+        self.reranker = reranker;
+        self
+    }
+
+    /// Budget of the retrieved context given to the Researcher; the best ranked chunks
+    /// that fit are kept.
+    pub fn research_context_tokens(mut self, tokens: usize) -> Self {
+        self.research_context_tokens = tokens;
+        self
+    }
+
+    /// Directory for the markdown copies of research, stylized and final posts.
+    pub fn output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = output_dir.into();
+        self
+    }
+
+    /// Starts a new run on a random theme, returns its id.
+    pub async fn orchestrate(&self) -> Result<Uuid, anyhow::Error> {
+        self.orchestrate_with(&RunRequest::default()).await
+    }
+
+    /// Starts a new run for the requested topic or theme, returns its id.
+    pub async fn orchestrate_with(&self, request: &RunRequest) -> Result<Uuid, anyhow::Error> {
+        let run_id = self.create_run(request).await?;
+        self.resume(run_id).await?;
+        Ok(run_id)
+    }
+
+    /// Creates a run per request up front, then executes them one by one.
+    /// A failed run is logged and does not stop the rest of the batch.
+    pub async fn orchestrate_batch(
+        &self,
+        requests: &[RunRequest],
+    ) -> Result<Vec<Uuid>, anyhow::Error> {
+        let mut run_ids = Vec::with_capacity(requests.len());
+        for request in requests {
+            run_ids.push(self.create_run(request).await?);
+        }
+        self.resume_all(&run_ids).await;
+        Ok(run_ids)
+    }
+
+    pub async fn resume_all(&self, run_ids: &[Uuid]) {
+        for (index, run_id) in run_ids.iter().enumerate() {
+            info!("Batch run {}/{}: {}", index + 1, run_ids.len(), run_id);
+            if let Err(e) = self.resume(*run_id).await {
+                error!("Pipeline run {} failed: {:?}", run_id, e);
+            }
+        }
+    }
+
+    /// Records a new run without executing it, so callers get its id right away.
+    pub async fn create_run(&self, request: &RunRequest) -> Result<Uuid, anyhow::Error> {
+        let mut run = PipelineRun::new();
+        run.meta = Some(serde_json::to_value(request)?);
+        let run_id = PipelineRepositoryPostgres::new(&self.pg_pool)
+            .create_run(&run)
+            .await?;
+        // Open the event stream now, clients may subscribe before the run gets going
+        events::open(run_id);
+        Ok(run_id)
+    }
+
+    /// Executes a created or interrupted run from its last completed stage.
+    pub async fn resume(&self, run_id: Uuid) -> Result<(), anyhow::Error> {
+        let result = events::scope(run_id, async {
+            let mut tracker = PipelineTracker::resume(
+                PipelineRepositoryPostgres::new(&self.pg_pool),
+                UsageRepositoryPostgres::new(&self.pg_pool),
+                run_id,
+            )
+            .await?;
+            let result = self.run_pipeline(&mut tracker).await;
+            if let Err(e) = &result {
+                tracker.fail(e).await;
+            }
+            result
+        })
+        .await;
+        // Also ends the stream when the run could not be loaded
+        events::close(run_id);
+        result
+    }
+
+    async fn run_pipeline<R: PipelineRepository, U: UsageRepository>(
+        &self,
+        tracker: &mut PipelineTracker<R, U>,
+    ) -> Result<(), anyhow::Error> {
+        let request: RunRequest = tracker
+            .run()
+            .meta
+            .clone()
+            .map(serde_json::from_value)
+            .transpose()?
+            .unwrap_or_default();
+        let generator_result = tracker
+            .stage(PipelineStageKind::TopicGeneration, &request, || async {
+                let theme_repo = ThemeRepositoryPostgres::new(&self.pg_pool);
+                let topic_history =
+                    PostTopicHistory::new(&self.pg_pool, self.embedding_model.clone());
+                let topic_generator = TopicGenerator::new(self.llm.topic_generator.clone())
+                    .with_history(Arc::new(topic_history), self.topic_novelty);
+                let result = match &request.topic {
+                    Some(topic) => topic_generator.search_queries_for_topic(topic).await?,
+                    None => {
+                        topic_generator
+                            .with_themes(theme_repo.get_enabled_themes().await?)
+                            .generate_topic(request.theme.as_deref())
+                            .await?
+                    }
+                };
+                if let Some(theme) = &result.theme {
+                    theme_repo.mark_theme_used(theme, chrono::Utc::now()).await?;
+                }
+                Ok(result)
+            })
+            .await?;
         let topic = generator_result.topic.clone();
         info!("Generated topic: {}", generator_result.topic);
         info!("Search query: {}", generator_result.full_search_query);
-        let now = chrono::Utc::now().format("%Y-%m-%d_%H-%M-%S").to_string();
-        let chunks = self.retrieve_relevant_chunks(&generator_result).await?;
-        info!("Retrieved {} relevant chunks", chunks.len());
-        let context = chunks
+        let now = tracker
+            .run()
+            .created_at
+            .unwrap_or_else(chrono::Utc::now)
+            .format("%Y-%m-%d_%H-%M-%S")
+            .to_string();
+        let run_dir = self.output_dir.join(&now);
+        let run_dir = run_dir.to_string_lossy();
+        let retrieved = tracker
+            .stage(PipelineStageKind::Retrieval, &generator_result, || {
+                self.retrieve_relevant_chunks(&generator_result)
+            })
+            .await?;
+        let diversity = &retrieved.diversity;
+        info!(
+            "Retrieved {} relevant chunks from {} sources (of {} candidates, {} skipped by the per-source limit, mean similarity {:.2})",
+            diversity.selected,
+            diversity.distinct_sources,
+            diversity.candidates,
+            diversity.skipped_by_source_limit,
+            diversity.mean_pairwise_similarity
+        );
+        let chunks: Vec<Chunk> = retrieved
+            .chunks
+            .into_iter()
+            .map(|retrieved| retrieved.chunk)
+            .collect();
+        let blocks: Vec<String> = chunks
             .iter()
             .map(|chunk| {
+                let section = chunk
+                    .section
+                    .as_ref()
+                    .map(|section| format!("Section: {}\n", section))
+                    .unwrap_or_default();
                 format!(
-                    "Source: {}\nURL: {}\n\n{}",
-                    chunk.source_title, chunk.source_url, chunk.text
+                    "Source: {}\n{}URL: {}\n\n{}",
+                    chunk.source_title, section, chunk.source_url, chunk.text
                 )
             })
-            .collect::<Vec<String>>()
+            .collect();
+        let kept = fit_to_budget(
+            token_counter().as_ref(),
+            &blocks,
+            self.research_context_tokens,
+        );
+        if kept.len() < chunks.len() {
+            warn!(
+                "Context budget of {} tokens fits {} of {} chunks",
+                self.research_context_tokens,
+                kept.len(),
+                chunks.len()
+            );
+        }
+        let context = kept
+            .iter()
+            .map(|&index| blocks[index].as_str())
+            .collect::<Vec<&str>>()
             .join("\n");
-        let stylizer_context = chunks
+        let stylizer_context = kept
             .iter()
+            .map(|&index| &chunks[index])
             .map(|chunk| {
                 format!(
                     "Source: {}\nURL: {}\n\n",
@@ -49,103 +351,178 @@ impl Orchestrator {
             .collect::<Vec<String>>()
             .join("\n");
         info!("Context length: {} characters", context.len());
-        let researcher = Researcher::new();
-        let researcher_result = researcher
-            .research(generator_result.topic, context.clone())
-            .await?;
-        info!(
-            "Research completed, content length: {} characters",
-            researcher_result.content.len()
-        );
-        let research_full_text = format!(
-            "CONTEXT:\n{}\n\nRESEARCH:\n{}",
-            context, researcher_result.content
-        );
-        self.save_document_to_file(
-            &research_full_text.clone(),
-            format!("posts/{}", now).as_str(),
-            "research",
-        )
-        .await?;
-        info!("Research saved to file");
-        let stylizer = Stylizer::new();
-        let stylizer_result = stylizer
-            .stylize(researcher_result.content, stylizer_context)
+        let research_input = json!({ "topic": topic, "context": context });
+        let researcher_result = tracker
+            .stage(PipelineStageKind::Research, &research_input, || async {
+                let researcher = Researcher::new(self.llm.researcher.clone());
+                let researcher_result = researcher
+                    .research(topic.clone(), context.clone())
+                    .await?;
+                info!(
+                    "Research completed, content length: {} characters",
+                    researcher_result.content.len()
+                );
+                let research_full_text = format!(
+                    "CONTEXT:\n{}\n\nRESEARCH:\n{}",
+                    context, researcher_result.content
+                );
+                self.save_document_to_file(
+                    &research_full_text.clone(),
+                    &run_dir,
+                    "research",
+                )
+                .await?;
+                info!("Research saved to file");
+                Ok(researcher_result)
+            })
             .await?;
-        info!(
-            "Post styling completed, content length: {} characters",
-            stylizer_result.content.len()
-        );
+        let stylizer_input = json!({ "research": researcher_result.content, "sources": stylizer_context });
+        let stylizer_result = tracker
+            .stage(PipelineStageKind::Stylizing, &stylizer_input, || async {
+                let stylizer = Stylizer::new(self.llm.stylizer.clone());
+                let stylizer_result = stylizer
+                    .stylize(researcher_result.content.clone(), stylizer_context.clone())
+                    .await?;
+                info!(
+                    "Post styling completed, content length: {} characters",
+                    stylizer_result.content.len()
+                );
 
-        self.save_document_to_file(
-            &stylizer_result.content.clone(),
-            format!("posts/{}", now).as_str(),
-            "stylized_post",
-        )
-        .await?;
-        info!("Stylized post saved to file");
-        let qa_controller = QAController::new();
-        let qa_controller_result = qa_controller.qa(stylizer_result.content).await?;
-        info!(
-            "QA controller completed, content length: {} characters",
-            qa_controller_result.content.len()
-        );
+                self.save_document_to_file(
+                    &stylizer_result.content.clone(),
+                    &run_dir,
+                    "stylized_post",
+                )
+                .await?;
+                info!("Stylized post saved to file");
+                Ok(stylizer_result)
+            })
+            .await?;
+        let qa_controller_result = tracker
+            .stage(PipelineStageKind::QualityControl, &stylizer_result, || async {
+                let qa_controller = QAController::new(self.llm.qa_controller.clone());
+                let qa_controller_result =
+                    qa_controller.qa(stylizer_result.content.clone()).await?;
+                info!(
+                    "QA controller completed, content length: {} characters",
+                    qa_controller_result.content.len()
+                );
+                Ok(qa_controller_result)
+            })
+            .await?;
         let publisher_preamble =
             format!("Powered by ADCO (https://github.com/pockerhead/ADCO)\n\n");
-        let post_text = format!("{}", publisher_preamble + &qa_controller_result.content);
-        let pg_pool = appstate::APP_STATE.get_pg_pool().await;
-        let post_repo = PostsRepositoryPostgres::new(&pg_pool);
-        let post = Post::new(topic, post_text.clone(), post_text.clone(), "".to_string());
-        post_repo.create_post(&post).await?;
-        self.save_document_to_file(
-            &post_text.clone(),
-            format!("posts/{}", now).as_str(),
-            "final_post",
-        )
-        .await?;
-        info!("Post saved to file");
-        if self.confirm_publish().await {
-            let publisher = Publisher::from_env()?;
-            publisher.publish(&post_text.clone()).await?;
-            info!("Post published to Telegram");
-        } else {
-            info!("Post not published to Telegram");
+        let draft_text = format!("{}", publisher_preamble + &qa_controller_result.content);
+        let sanitized = sanitize_telegram_html(&draft_text);
+        for change in &sanitized.changes {
+            warn!("Post HTML fixed: {}", change);
+        }
+        let post_text = sanitized.html;
+        let post_length = visible_len(&post_text);
+        if post_length > TELEGRAM_MESSAGE_LIMIT {
+            warn!(
+                "Post is {} characters with the preamble, over the Telegram limit of {}; it will be split into {} messages",
+                post_length,
+                TELEGRAM_MESSAGE_LIMIT,
+                split_html_message(&post_text, TELEGRAM_MESSAGE_LIMIT).len()
+            );
         }
+        let post_id = tracker
+            .stage(PipelineStageKind::PostCreation, &post_text, || async {
+                let post_repo = PostsRepositoryPostgres::new(&self.pg_pool);
+                let post = Post::new(topic.clone(), draft_text.clone(), post_text.clone(), "".to_string());
+                let post_id = post_repo.create_post(&post).await?;
+                self.save_document_to_file(
+                    &post_text.clone(),
+                    &run_dir,
+                    "final_post",
+                )
+                .await?;
+                info!("Post saved to file");
+                Ok(post_id)
+            })
+            .await?;
+        tracker.attach_post(post_id).await?;
+        tracker
+            .stage(PipelineStageKind::Publishing, &post_id, || async {
+                let post_repo = PostsRepositoryPostgres::new(&self.pg_pool);
+                let mut post = post_repo
+                    .get_post_by_id(post_id)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("Post not found: {}", post_id))?;
+                match self.publish_approval {
+                    PublishApproval::Stdin if self.confirm_publish().await => {
+                        let publisher = Publisher::from_env()?;
+                        publisher.publish_post(&post_repo, &mut post).await?;
+                        info!("Post published to Telegram");
+                        Ok(true)
+                    }
+                    PublishApproval::Telegram => {
+                        ApprovalBot::from_env(&self.pg_pool)?
+                            .request_approval(&post)
+                            .await?;
+                        Ok(false)
+                    }
+                    _ => {
+                        info!("Post not published to Telegram");
+                        Ok(false)
+                    }
+                }
+            })
+            .await?;
+        tracker.complete().await?;
         Ok(())
     }
 
     async fn retrieve_relevant_chunks(
         &self,
         generator_result: &TopicGeneratorResult,
-    ) -> Result<Vec<Chunk>, anyhow::Error> {
-        let pg_pool = appstate::APP_STATE.get_pg_pool().await;
-        let embeddings_repository = EmbeddingsRepository::new(&pg_pool);
+    ) -> Result<RetrievedChunks, anyhow::Error> {
+        let embeddings_repository =
+            EmbeddingsRepository::with_model(&self.pg_pool, self.embedding_model.clone())
+                .with_reranker(self.reranker.clone());
 
-        let mut sources: Vec<Source> = self.get_sources(generator_result).await?;
+        let mut sources: Vec<Source> = self
+            .source_collector
+            .collect(
+                &generator_result.short_search_query,
+                &generator_result.full_search_query,
+            )
+            .await?;
         info!("Found {} sources", sources.len());
         for source in sources.iter_mut() {
-            let source_repo = SourcesRepositoryImpl::new(&pg_pool);
-            match source_repo.create_source(&source).await {
-                Ok(source_id) => {
-                    source.id = Some(source_id.clone());
+            let source_repo = SourcesRepositoryImpl::new(&self.pg_pool);
+            match source_repo.upsert_source(source).await {
+                Ok(upsert) => {
+                    source.id = Some(upsert.id());
+                    if !upsert.needs_indexing() {
+                        info!("Source {} is already indexed", source.url);
+                        continue;
+                    }
                 }
                 Err(e) => {
-                    error!("Error creating source: {:?}", e);
+                    // Chunks of an unsaved source would have no source_id
+                    error!("Error creating source, not indexing it: {:?}", e);
+                    continue;
                 }
             }
-            let chunker = TextChunker::new(1000, 250);
-            let chunks = chunker.chunk_text_from_source(&source);
-            info!("Created {} chunks for source", chunks.len());
-            embeddings_repository.save_chunks(chunks).await?;
+            embeddings_repository.index_source(source).await?;
         }
-        let results = embeddings_repository
-            .search_chunks(&generator_result.full_search_query.clone(), 10)
+        let retrieved = embeddings_repository
+            .retrieve_chunks(
+                &generator_result.full_search_query,
+                &generator_result.topic,
+                10,
+                self.retrieval_weights,
+                self.retrieval_diversity,
+            )
             .await?;
-        let mut chunks: Vec<Chunk> = Vec::new();
-        for (_, _, chunk) in results {
-            chunks.push(chunk);
-        }
-        Ok(chunks)
+        let cache = embedding_cache_stats();
+        info!(
+            "Embedding cache since start: {} hits, {} misses",
+            cache.hits, cache.misses
+        );
+        Ok(retrieved)
     }
 
     async fn confirm_publish(&self) -> bool {
@@ -158,65 +535,6 @@ impl Orchestrator {
         matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
     }
 
-    async fn get_sources(
-        &self,
-        generator_result: &TopicGeneratorResult,
-    ) -> Result<Vec<Source>, anyhow::Error> {
-        let fetcher = HttpFetcher::new();
-        let parser = HtmlParser::new();
-        let mut sources = Vec::new();
-        let mut urls_to_scrape: Vec<(String, Option<String>)> = Vec::new();
-
-        // Collect URLs from HackerNews
-        match fetcher
-            .search_hackernews(&generator_result.short_search_query)
-            .await
-        {
-            Ok(hn_response) => {
-                for hit in hn_response.hits.iter().take(5) {
-                    if let Some(url) = &hit.url {
-                        urls_to_scrape.push((url.clone(), None));
-                    }
-                }
-            }
-            Err(e) => {
-                error!("Error fetching from HackerNews: {:?}", e);
-            }
-        }
-        info!("Collected {} URLs from HackerNews", urls_to_scrape.len());
-        // Collect URLs from arXiv (use PDF links)
-        match fetcher
-            .search_arxiv(&generator_result.full_search_query)
-            .await
-        {
-            Ok(arxiv_entries) => {
-                for entry in arxiv_entries.iter().take(5) {
-                    // Convert arXiv id to PDF URL
-                    // e.g. "http://arxiv.org/abs/2310.00266v1" -> "http://arxiv.org/pdf/2310.00266v1.pdf"
-                    let pdf_url = entry.id.replace("/abs/", "/pdf/") + ".pdf";
-                    urls_to_scrape.push((pdf_url, Some(entry.title.clone())));
-                }
-            }
-            Err(e) => {
-                error!("Error fetching from arXiv: {:?}", e);
-            }
-        }
-        info!("Collected {} URLs to scrape", urls_to_scrape.len());
-        // Now scrape all collected URLs
-        for url in urls_to_scrape.iter() {
-            match parser.scrap_source_from_url(&url.0, url.1.as_deref()).await {
-                Ok(source) => {
-                    sources.push(source);
-                }
-                Err(e) => {
-                    error!("Error scraping source from url {}: {:?}", url.0, e);
-                }
-            }
-        }
-        info!("Scraped {} sources", sources.len());
-        Ok(sources)
-    }
-
     async fn save_document_to_file(
         &self,
         document: &str,
@@ -0,0 +1,94 @@
+use adco_shared::pipeline::PipelineEvent;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use uuid::Uuid;
+
+/// Events kept for subscribers that fall behind; older ones are dropped.
+const EVENT_BUFFER: usize = 256;
+
+/// Live event channels of the runs executing in this process.
+static RUN_CHANNELS: Lazy<Mutex<HashMap<Uuid, broadcast::Sender<PipelineEvent>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+tokio::task_local! {
+    static CURRENT_RUN: Uuid;
+}
+
+/// Opens the event channel of a run that starts executing here.
+pub fn open(run_id: Uuid) {
+    RUN_CHANNELS
+        .lock()
+        .unwrap()
+        .entry(run_id)
+        .or_insert_with(|| broadcast::channel(EVENT_BUFFER).0);
+}
+
+/// Closes the channel, subscribers get the buffered events and then the end of the stream.
+pub fn close(run_id: Uuid) {
+    RUN_CHANNELS.lock().unwrap().remove(&run_id);
+}
+
+/// Sends the event to the subscribers of the run, if it is open.
+pub fn publish(run_id: Uuid, event: PipelineEvent) {
+    if let Some(sender) = RUN_CHANNELS.lock().unwrap().get(&run_id) {
+        // No subscribers is not an error
+        let _ = sender.send(event);
+    }
+}
+
+/// Subscribes to a run executing in this process; `None` when it is not running here.
+pub fn subscribe(run_id: Uuid) -> Option<broadcast::Receiver<PipelineEvent>> {
+    RUN_CHANNELS
+        .lock()
+        .unwrap()
+        .get(&run_id)
+        .map(|sender| sender.subscribe())
+}
+
+/// Runs `future` with log lines forwarded to the run's subscribers by [`RunLogLayer`].
+pub async fn scope<F: Future>(run_id: Uuid, future: F) -> F::Output {
+    CURRENT_RUN.scope(run_id, future).await
+}
+
+/// Tracing layer that turns log events emitted inside [`scope`] into `PipelineEvent::Log`.
+pub struct RunLogLayer;
+
+impl<S: Subscriber> Layer<S> for RunLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let Ok(run_id) = CURRENT_RUN.try_with(|run_id| *run_id) else {
+            return;
+        };
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        publish(
+            run_id,
+            PipelineEvent::Log {
+                level: event.metadata().level().to_string(),
+                message: visitor.message,
+            },
+        );
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.message
+                .push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+}
@@ -0,0 +1,6 @@
+pub mod events;
+pub mod repository;
+pub mod tracker;
+
+pub use repository::*;
+pub use tracker::*;
@@ -0,0 +1,20 @@
+use adco_shared::pipeline::{PipelineRun, PipelineStage, PipelineStageKind};
+use uuid::Uuid;
+use thiserror::Error;
+
+pub trait PipelineRepository {
+    async fn create_run(&self, run: &PipelineRun) -> Result<Uuid, PipelineRepositoryError>;
+    async fn get_run_by_id(&self, id: Uuid) -> Result<Option<PipelineRun>, PipelineRepositoryError>;
+    async fn update_run(&self, run: &PipelineRun) -> Result<(), PipelineRepositoryError>;
+    async fn get_stages(&self, run_id: Uuid) -> Result<Vec<PipelineStage>, PipelineRepositoryError>;
+    async fn get_stage(&self, run_id: Uuid, stage: PipelineStageKind) -> Result<Option<PipelineStage>, PipelineRepositoryError>;
+    async fn save_stage(&self, stage: &PipelineStage) -> Result<Uuid, PipelineRepositoryError>;
+}
+
+#[derive(Error, Debug)]
+pub enum PipelineRepositoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error("Pipeline run not found: {id}")]
+    NotFound { id: Uuid },
+}
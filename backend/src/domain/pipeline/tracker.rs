@@ -0,0 +1,225 @@
+use super::events;
+use super::repository::PipelineRepository;
+use crate::domain::usage::{UsageRepository, UsageScope};
+use adco_shared::pipeline::{
+    PipelineEvent, PipelineRun, PipelineRunStatus, PipelineStage, PipelineStageKind,
+    PipelineStageStatus,
+};
+use adco_shared::usage::LlmCall;
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
+use std::time::Instant;
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// Records every pipeline stage as a checkpoint and replays completed ones on resume.
+/// Model calls made inside a stage are saved with the run and stage they belong to.
+/// Stage transitions are published to the run's [`events`] subscribers.
+pub struct PipelineTracker<R: PipelineRepository, U: UsageRepository> {
+    repository: R,
+    usage_repository: U,
+    run: PipelineRun,
+}
+
+impl<R: PipelineRepository, U: UsageRepository> PipelineTracker<R, U> {
+    pub async fn start(repository: R, usage_repository: U) -> Result<Self, anyhow::Error> {
+        let mut run = PipelineRun::new();
+        let run_id = repository.create_run(&run).await?;
+        run.id = Some(run_id);
+        events::open(run_id);
+        info!("Pipeline run {} started", run_id);
+        Ok(Self {
+            repository,
+            usage_repository,
+            run,
+        })
+    }
+
+    pub async fn resume(
+        repository: R,
+        usage_repository: U,
+        run_id: Uuid,
+    ) -> Result<Self, anyhow::Error> {
+        let mut run = repository
+            .get_run_by_id(run_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Pipeline run not found: {}", run_id))?;
+        if run.status == PipelineRunStatus::Completed {
+            return Err(anyhow::anyhow!("Pipeline run {} is already completed", run_id));
+        }
+        // Running again once a stage starts
+        run.status = PipelineRunStatus::Queued;
+        run.error = None;
+        run.finished_at = None;
+        repository.update_run(&run).await?;
+        events::open(run_id);
+        match run.current_stage {
+            Some(stage) => info!("Pipeline run {} resumed after stage {}", run_id, stage),
+            None => info!("Pipeline run {} started", run_id),
+        }
+        Ok(Self {
+            repository,
+            usage_repository,
+            run,
+        })
+    }
+
+    pub fn run(&self) -> &PipelineRun {
+        &self.run
+    }
+
+    pub fn run_id(&self) -> Uuid {
+        self.run.id.unwrap_or_default()
+    }
+
+    /// Runs `stage_fn` unless the stage already has a completed checkpoint,
+    /// in which case the stored output is returned instead.
+    pub async fn stage<T, I, F, Fut>(
+        &mut self,
+        kind: PipelineStageKind,
+        input: &I,
+        stage_fn: F,
+    ) -> Result<T, anyhow::Error>
+    where
+        T: Serialize + DeserializeOwned,
+        I: Serialize + ?Sized,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, anyhow::Error>>,
+    {
+        let run_id = self.run_id();
+        let mut stage = match self.repository.get_stage(run_id, kind).await? {
+            Some(stage) if stage.status == PipelineStageStatus::Completed => {
+                if let Some(output) = stage.output {
+                    info!("Stage {} restored from checkpoint", kind);
+                    events::publish(run_id, PipelineEvent::StageRestored { stage: kind });
+                    return Ok(serde_json::from_value(output)?);
+                }
+                stage
+            }
+            Some(stage) => stage,
+            None => PipelineStage::new(run_id, kind),
+        };
+
+        stage.status = PipelineStageStatus::Running;
+        stage.attempts += 1;
+        stage.input = Some(serde_json::to_value(input)?);
+        stage.output = None;
+        stage.error = None;
+        stage.started_at = Some(chrono::Utc::now());
+        stage.finished_at = None;
+        stage.duration_ms = None;
+        self.repository.save_stage(&stage).await?;
+        self.run.status = PipelineRunStatus::Running;
+        self.run.current_stage = Some(kind);
+        self.repository.update_run(&self.run).await?;
+        info!("Stage {} started (attempt {})", kind, stage.attempts);
+        events::publish(
+            run_id,
+            PipelineEvent::StageStarted {
+                stage: kind,
+                attempt: stage.attempts,
+            },
+        );
+
+        let start_time = Instant::now();
+        let usage_scope = UsageScope::new();
+        let result = usage_scope.run(stage_fn()).await;
+        stage.finished_at = Some(chrono::Utc::now());
+        stage.duration_ms = Some(start_time.elapsed().as_millis() as i64);
+        self.save_usage(kind, usage_scope.take()).await;
+
+        match result {
+            Ok(output) => {
+                stage.status = PipelineStageStatus::Completed;
+                stage.output = Some(serde_json::to_value(&output)?);
+                self.repository.save_stage(&stage).await?;
+                info!("Stage {} completed in {:?}", kind, start_time.elapsed());
+                events::publish(
+                    run_id,
+                    PipelineEvent::StageCompleted {
+                        stage: kind,
+                        duration_ms: stage.duration_ms.unwrap_or_default(),
+                    },
+                );
+                Ok(output)
+            }
+            Err(e) => {
+                error!("Stage {} failed: {:?}", kind, e);
+                stage.status = PipelineStageStatus::Failed;
+                stage.error = Some(format!("{:#}", e));
+                self.repository.save_stage(&stage).await?;
+                self.run.status = PipelineRunStatus::Failed;
+                self.run.error = Some(format!("{}: {:#}", kind, e));
+                self.run.finished_at = Some(chrono::Utc::now());
+                self.repository.update_run(&self.run).await?;
+                events::publish(
+                    run_id,
+                    PipelineEvent::StageFailed {
+                        stage: kind,
+                        error: format!("{:#}", e),
+                    },
+                );
+                self.finish_events();
+                Err(e)
+            }
+        }
+    }
+
+    /// Failed stages cost money too, so usage is saved regardless of the outcome;
+    /// a failure to save it must not fail the stage.
+    async fn save_usage(&self, kind: PipelineStageKind, mut calls: Vec<LlmCall>) {
+        if calls.is_empty() {
+            return;
+        }
+        for call in calls.iter_mut() {
+            call.run_id = Some(self.run_id());
+            call.stage = Some(kind);
+        }
+        if let Err(e) = self.usage_repository.save_calls(&calls).await {
+            error!("Failed to save usage of stage {}: {:?}", kind, e);
+        }
+    }
+
+    pub async fn attach_post(&mut self, post_id: Uuid) -> Result<(), anyhow::Error> {
+        self.run.post_id = Some(post_id);
+        self.repository.update_run(&self.run).await?;
+        Ok(())
+    }
+
+    pub async fn complete(&mut self) -> Result<(), anyhow::Error> {
+        self.run.status = PipelineRunStatus::Completed;
+        self.run.finished_at = Some(chrono::Utc::now());
+        self.repository.update_run(&self.run).await?;
+        info!("Pipeline run {} completed", self.run_id());
+        self.finish_events();
+        Ok(())
+    }
+
+    /// Marks the run failed for an error outside of stages; a run already failed by
+    /// a stage is left as it is.
+    pub async fn fail(&mut self, error: &anyhow::Error) {
+        if self.run.status == PipelineRunStatus::Failed {
+            return;
+        }
+        error!("Pipeline run {} failed: {:?}", self.run_id(), error);
+        self.run.status = PipelineRunStatus::Failed;
+        self.run.error = Some(format!("{:#}", error));
+        self.run.finished_at = Some(chrono::Utc::now());
+        if let Err(e) = self.repository.update_run(&self.run).await {
+            error!("Failed to save failure of run {}: {:?}", self.run_id(), e);
+        }
+        self.finish_events();
+    }
+
+    fn finish_events(&self) {
+        events::publish(
+            self.run_id(),
+            PipelineEvent::RunFinished {
+                status: self.run.status.clone(),
+                post_id: self.run.post_id,
+                error: self.run.error.clone(),
+            },
+        );
+        events::close(self.run_id());
+    }
+}
@@ -0,0 +1,77 @@
+use adco_shared::post::{Post, PostStatus};
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PostTransitionError {
+    #[error("Post cannot go from {from} to {to}")]
+    InvalidTransition { from: PostStatus, to: PostStatus },
+    #[error("Scheduled time {0} is in the past")]
+    ScheduledInPast(DateTime<Utc>),
+    #[error("Published posts cannot be edited or deleted")]
+    AlreadyPublished,
+    #[error("Post is being published")]
+    BeingPublished,
+}
+
+/// Allowed status changes: draft -> scheduled -> published, with a way back
+/// from scheduled and rejected to draft. Published is final.
+pub fn can_transition(from: &PostStatus, to: &PostStatus) -> bool {
+    matches!(
+        (from, to),
+        (PostStatus::Draft, PostStatus::Scheduled)
+            | (PostStatus::Draft, PostStatus::Published)
+            | (PostStatus::Draft, PostStatus::Rejected)
+            | (PostStatus::Scheduled, PostStatus::Draft)
+            | (PostStatus::Scheduled, PostStatus::Published)
+            | (PostStatus::Rejected, PostStatus::Draft)
+    )
+}
+
+pub fn check_transition(post: &Post, to: PostStatus) -> Result<(), PostTransitionError> {
+    if can_transition(&post.status, &to) {
+        Ok(())
+    } else {
+        Err(PostTransitionError::InvalidTransition {
+            from: post.status.clone(),
+            to,
+        })
+    }
+}
+
+pub fn schedule(
+    post: &mut Post,
+    at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<(), PostTransitionError> {
+    check_transition(post, PostStatus::Scheduled)?;
+    if at <= now {
+        return Err(PostTransitionError::ScheduledInPast(at));
+    }
+    post.status = PostStatus::Scheduled;
+    post.scheduled_at = Some(at);
+    Ok(())
+}
+
+pub fn unschedule(post: &mut Post) -> Result<(), PostTransitionError> {
+    check_transition(post, PostStatus::Draft)?;
+    post.status = PostStatus::Draft;
+    post.scheduled_at = None;
+    Ok(())
+}
+
+pub fn reject(post: &mut Post) -> Result<(), PostTransitionError> {
+    check_transition(post, PostStatus::Rejected)?;
+    post.status = PostStatus::Rejected;
+    post.scheduled_at = None;
+    Ok(())
+}
+
+pub fn check_editable(post: &Post) -> Result<(), PostTransitionError> {
+    match post.status {
+        PostStatus::Published => return Err(PostTransitionError::AlreadyPublished),
+        PostStatus::Publishing => return Err(PostTransitionError::BeingPublished),
+        _ => {}
+    }
+    Ok(())
+}
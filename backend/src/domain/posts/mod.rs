@@ -1,3 +1,5 @@
+pub mod lifecycle;
 pub mod repository;
 
-pub use repository::*;
\ No newline at end of file
+pub use lifecycle::*;
+pub use repository::*;
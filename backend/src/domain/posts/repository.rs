@@ -1,4 +1,6 @@
-use adco_shared::post::Post;
+use crate::domain::content::SimilarTopic;
+use adco_shared::post::{Post, PostStatus};
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 use thiserror::Error;
 
@@ -6,7 +8,39 @@ pub trait PostRepository {
     async fn create_post(&self, post: &Post) -> Result<Uuid, PostRepositoryError>;
     async fn get_post_by_id(&self, id: Uuid) -> Result<Option<Post>, PostRepositoryError>;
     async fn update_post(&self, post: &Post) -> Result<(), PostRepositoryError>;
+    /// [`update_post`](Self::update_post) only if the stored status is still `expected`,
+    /// otherwise fails with [`PostRepositoryError::StatusChanged`].
+    async fn update_post_if_status(&self, post: &Post, expected: PostStatus) -> Result<(), PostRepositoryError>;
     async fn delete_post(&self, id: Uuid) -> Result<(), PostRepositoryError>;
+    /// [`delete_post`](Self::delete_post) only if the stored status is still `expected`,
+    /// otherwise fails with [`PostRepositoryError::StatusChanged`].
+    async fn delete_post_if_status(&self, id: Uuid, expected: PostStatus) -> Result<(), PostRepositoryError>;
+    /// Newest first, optionally only posts with `status`.
+    async fn list_posts(&self, status: Option<PostStatus>, limit: i64, offset: i64) -> Result<Vec<Post>, PostRepositoryError>;
+    async fn count_posts(&self, status: Option<PostStatus>) -> Result<i64, PostRepositoryError>;
+    /// Scheduled posts whose `scheduled_at` is at or before `now`.
+    async fn get_due_scheduled_posts(&self, now: DateTime<Utc>) -> Result<Vec<Post>, PostRepositoryError>;
+    /// Changes the status only if it is still `from`, so concurrent changes of one post
+    /// can't both succeed; otherwise fails with [`PostRepositoryError::StatusChanged`].
+    async fn transition_status(&self, id: Uuid, from: PostStatus, to: PostStatus) -> Result<(), PostRepositoryError>;
+    /// Saves the sent messages of a post claimed as publishing and marks it published.
+    async fn mark_published(&self, id: Uuid, message_ids: &[i64], published_at: DateTime<Utc>) -> Result<(), PostRepositoryError>;
+    /// Counts a failed send of a post claimed as publishing and returns it to `retry_status`,
+    /// or to draft without a schedule once it failed `max_attempts` times.
+    async fn record_publish_failure(&self, id: Uuid, error: &str, retry_status: PostStatus, max_attempts: i32) -> Result<Post, PostRepositoryError>;
+    /// Topics of posts, except rejected ones, that have no embedding made with `model`.
+    async fn get_unembedded_post_topics(&self, model: &str) -> Result<Vec<PostTopic>, PostRepositoryError>;
+    async fn save_topic_embedding(&self, id: Uuid, model: &str, embedding: &[f32]) -> Result<(), PostRepositoryError>;
+    /// Up to `limit` topics, except rejected ones, whose `model` embeddings are closest to
+    /// `embedding`, most similar first.
+    async fn nearest_post_topics(&self, embedding: &[f32], model: &str, limit: usize) -> Result<Vec<SimilarTopic>, PostRepositoryError>;
+}
+
+/// Topic of an existing post.
+#[derive(Debug, Clone)]
+pub struct PostTopic {
+    pub post_id: Uuid,
+    pub topic: String,
 }
 
 #[derive(Error, Debug)]
@@ -15,4 +49,6 @@ pub enum PostRepositoryError {
     DatabaseError(#[from] sqlx::Error),
     #[error("Post not found: {id}")]
     NotFound { id: Uuid },
+    #[error("Post {id} is no longer {expected}")]
+    StatusChanged { id: Uuid, expected: PostStatus },
 }
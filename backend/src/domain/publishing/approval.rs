@@ -0,0 +1,247 @@
+use super::message_splitter::{split_html_message, TELEGRAM_MESSAGE_LIMIT};
+use super::publisher::Publisher;
+use crate::domain::{
+    infra::postgres::posts_repo::PostsRepositoryPostgres, posts::PostRepository, scheduler,
+};
+use adco_shared::pipeline::RunRequest;
+use adco_shared::post::{Post, PostStatus};
+use chrono::{DateTime, NaiveTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Admin decision on a draft, sent back as inline button callback data `<action>:<post_id>`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ApprovalAction {
+    PublishNow,
+    Schedule,
+    Regenerate,
+    Reject,
+}
+
+impl ApprovalAction {
+    pub const ALL: [ApprovalAction; 4] = [
+        ApprovalAction::PublishNow,
+        ApprovalAction::Schedule,
+        ApprovalAction::Regenerate,
+        ApprovalAction::Reject,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ApprovalAction::PublishNow => "Publish now",
+            ApprovalAction::Schedule => "Schedule",
+            ApprovalAction::Regenerate => "Regenerate",
+            ApprovalAction::Reject => "Reject",
+        }
+    }
+
+    pub fn callback_data(&self, post_id: Uuid) -> String {
+        format!("{}:{}", self, post_id)
+    }
+
+    pub fn parse_callback_data(data: &str) -> Option<(Self, Uuid)> {
+        let (action, post_id) = data.split_once(':')?;
+        let action = Self::ALL.into_iter().find(|a| a.to_string() == action)?;
+        Some((action, Uuid::parse_str(post_id).ok()?))
+    }
+}
+
+impl std::fmt::Display for ApprovalAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ApprovalAction::PublishNow => write!(f, "publish"),
+            ApprovalAction::Schedule => write!(f, "schedule"),
+            ApprovalAction::Regenerate => write!(f, "regenerate"),
+            ApprovalAction::Reject => write!(f, "reject"),
+        }
+    }
+}
+
+/// Next occurrence of `time` (UTC) after `now`.
+pub fn next_publish_slot(now: DateTime<Utc>, time: NaiveTime) -> DateTime<Utc> {
+    let today = now.date_naive().and_time(time).and_utc();
+    if today > now {
+        today
+    } else {
+        today + chrono::Duration::days(1)
+    }
+}
+
+/// Sends drafts to a private admin chat with inline buttons and applies the decisions.
+pub struct ApprovalBot {
+    bot: Bot,
+    admin_chat_id: ChatId,
+    publish_time: NaiveTime,
+    pg_pool: PgPool,
+}
+
+impl ApprovalBot {
+    /// Reads `TELEGRAM_ADMIN_CHAT_ID` and `ADCO_PUBLISH_TIME` (`HH:MM` UTC, default 18:00),
+    /// the time "Schedule" puts the post at.
+    pub fn from_env(pg_pool: &PgPool) -> Result<Self, anyhow::Error> {
+        let admin_chat_id = std::env::var("TELEGRAM_ADMIN_CHAT_ID")?.parse::<i64>()?;
+        let publish_time = std::env::var("ADCO_PUBLISH_TIME").unwrap_or("18:00".to_string());
+        Ok(Self {
+            bot: Bot::from_env(),
+            admin_chat_id: ChatId(admin_chat_id),
+            publish_time: NaiveTime::parse_from_str(&publish_time, "%H:%M")?,
+            pg_pool: pg_pool.clone(),
+        })
+    }
+
+    pub fn is_configured() -> bool {
+        std::env::var("TELEGRAM_ADMIN_CHAT_ID").is_ok()
+    }
+
+    pub async fn request_approval(&self, post: &Post) -> Result<(), anyhow::Error> {
+        let post_id = post
+            .id
+            .ok_or_else(|| anyhow::anyhow!("Post is not saved yet"))?;
+        let keyboard = InlineKeyboardMarkup::new(vec![
+            ApprovalAction::ALL[..2]
+                .iter()
+                .map(|a| InlineKeyboardButton::callback(a.label(), a.callback_data(post_id)))
+                .collect::<Vec<_>>(),
+            ApprovalAction::ALL[2..]
+                .iter()
+                .map(|a| InlineKeyboardButton::callback(a.label(), a.callback_data(post_id)))
+                .collect::<Vec<_>>(),
+        ]);
+        // Same split as in the channel, buttons go under the last part
+        let mut messages = split_html_message(&post.post_text, TELEGRAM_MESSAGE_LIMIT);
+        let last = messages.pop().unwrap_or_default();
+        for message in messages {
+            self.bot
+                .send_message(self.admin_chat_id, message)
+                .parse_mode(ParseMode::Html)
+                .await?;
+        }
+        self.bot
+            .send_message(self.admin_chat_id, last)
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+        info!("Post {} sent for approval", post_id);
+        Ok(())
+    }
+
+    /// Listens for button presses until the process stops.
+    pub async fn run(self) {
+        let bot = self.bot.clone();
+        let approval_bot = Arc::new(self);
+        let handler = Update::filter_callback_query().endpoint(
+            |approval_bot: Arc<ApprovalBot>, query: CallbackQuery| async move {
+                approval_bot.handle_callback(query).await;
+                respond(())
+            },
+        );
+        info!("Approval bot listening for decisions");
+        Dispatcher::builder(bot, handler)
+            .dependencies(dptree::deps![approval_bot])
+            .build()
+            .dispatch()
+            .await;
+    }
+
+    async fn handle_callback(&self, query: CallbackQuery) {
+        let answer = match self.apply_callback(&query).await {
+            Ok(answer) => answer,
+            Err(e) => {
+                error!("Approval callback failed: {:?}", e);
+                format!("Failed: {}", e)
+            }
+        };
+        if let Err(e) = self.bot.answer_callback_query(query.id).text(answer).await {
+            warn!("Failed to answer callback query: {:?}", e);
+        }
+    }
+
+    async fn apply_callback(&self, query: &CallbackQuery) -> Result<String, anyhow::Error> {
+        let message = query
+            .message
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Message is too old"))?;
+        if message.chat().id != self.admin_chat_id {
+            return Err(anyhow::anyhow!("Not an admin chat"));
+        }
+        let (action, post_id) = query
+            .data
+            .as_deref()
+            .and_then(ApprovalAction::parse_callback_data)
+            .ok_or_else(|| anyhow::anyhow!("Unknown action"))?;
+        let answer = self.apply(action, post_id).await?;
+        info!("Post {}: {}", post_id, answer);
+        // Decisions are final, drop the buttons
+        self.bot
+            .edit_message_reply_markup(message.chat().id, message.id())
+            .await?;
+        Ok(answer)
+    }
+
+    pub async fn apply(
+        &self,
+        action: ApprovalAction,
+        post_id: Uuid,
+    ) -> Result<String, anyhow::Error> {
+        let post_repo = PostsRepositoryPostgres::new(&self.pg_pool);
+        let mut post = post_repo
+            .get_post_by_id(post_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Post not found: {}", post_id))?;
+        if post.status != PostStatus::Draft {
+            return Ok(format!("Post is already {}", post.status));
+        }
+        match action {
+            ApprovalAction::PublishNow => {
+                Publisher::from_env()?
+                    .publish_post(&post_repo, &mut post)
+                    .await?;
+                Ok("Published".to_string())
+            }
+            ApprovalAction::Schedule => {
+                let scheduled_at = next_publish_slot(Utc::now(), self.publish_time);
+                post.status = PostStatus::Scheduled;
+                post.scheduled_at = Some(scheduled_at);
+                post_repo
+                    .update_post_if_status(&post, PostStatus::Draft)
+                    .await?;
+                Ok(format!(
+                    "Scheduled for {}",
+                    scheduled_at.format("%Y-%m-%d %H:%M UTC")
+                ))
+            }
+            ApprovalAction::Regenerate => {
+                // Rejecting first lets only one click start a run for the draft
+                post.status = PostStatus::Rejected;
+                post_repo
+                    .update_post_if_status(&post, PostStatus::Draft)
+                    .await?;
+                let request = RunRequest::topic(&post.topic);
+                let Some(run_id) = scheduler::start_pipeline_run(&self.pg_pool, request).await?
+                else {
+                    // As an error the buttons stay, so the admin can try again
+                    post.status = PostStatus::Draft;
+                    post_repo
+                        .update_post_if_status(&post, PostStatus::Rejected)
+                        .await?;
+                    anyhow::bail!("Another run is in progress, the post is kept; try again later");
+                };
+                info!("Regeneration of post {} started as run {}", post_id, run_id);
+                Ok("Rejected, generating a new post on the same topic".to_string())
+            }
+            ApprovalAction::Reject => {
+                post.status = PostStatus::Rejected;
+                post_repo
+                    .update_post_if_status(&post, PostStatus::Draft)
+                    .await?;
+                Ok("Rejected".to_string())
+            }
+        }
+    }
+}
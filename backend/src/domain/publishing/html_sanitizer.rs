@@ -0,0 +1,400 @@
+/// What the sanitizer had to change to make a post valid Telegram HTML.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SanitizerChange {
+    /// A supported alias or block element turned into a Telegram tag (`<strong>` -> `<b>`, `<h2>` -> `<b>`).
+    ConvertedTag {
+        from: String,
+        to: String,
+    },
+    /// Unsupported tag removed, its text kept (`<br>` and block elements become line breaks).
+    StrippedTag(String),
+    StrippedAttributes(String),
+    /// Closing tag without a matching opening tag.
+    DroppedClosingTag(String),
+    /// Tag left open at the end of the post.
+    ClosedTag(String),
+    /// Tags closed in the wrong order were closed and reopened around the mismatch.
+    ReorderedTag(String),
+    EscapedCharacter(char),
+    /// Named entity Telegram does not know, replaced with the character itself.
+    ReplacedEntity(String),
+}
+
+impl std::fmt::Display for SanitizerChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SanitizerChange::ConvertedTag { from, to } => {
+                write!(f, "converted <{}> to <{}>", from, to)
+            }
+            SanitizerChange::StrippedTag(tag) => write!(f, "stripped unsupported <{}>", tag),
+            SanitizerChange::StrippedAttributes(tag) => {
+                write!(f, "stripped attributes of <{}>", tag)
+            }
+            SanitizerChange::DroppedClosingTag(tag) => write!(f, "dropped unmatched </{}>", tag),
+            SanitizerChange::ClosedTag(tag) => write!(f, "closed unclosed <{}>", tag),
+            SanitizerChange::ReorderedTag(tag) => write!(f, "fixed nesting of <{}>", tag),
+            SanitizerChange::EscapedCharacter(c) => write!(f, "escaped '{}'", c),
+            SanitizerChange::ReplacedEntity(entity) => write!(f, "replaced &{};", entity),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SanitizedHtml {
+    pub html: String,
+    pub changes: Vec<SanitizerChange>,
+}
+
+impl SanitizedHtml {
+    pub fn is_changed(&self) -> bool {
+        !self.changes.is_empty()
+    }
+}
+
+/// Named entities the Bot API accepts; everything else has to be numeric or a plain character.
+const TELEGRAM_ENTITIES: [&str; 4] = ["lt", "gt", "amp", "quot"];
+
+const NAMED_ENTITIES: &[(&str, &str)] = &[
+    ("nbsp", "\u{a0}"),
+    ("mdash", "—"),
+    ("ndash", "–"),
+    ("laquo", "«"),
+    ("raquo", "»"),
+    ("hellip", "…"),
+    ("copy", "©"),
+    ("apos", "'"),
+];
+
+enum TagKind {
+    /// Telegram tag, possibly under another name.
+    Inline(&'static str),
+    /// Heading: bold text on its own line.
+    Heading,
+    ListItem,
+    /// Ends the current line (`<br>`) or paragraph (`<p>`, `<div>`, ...).
+    Break(&'static str),
+    Unsupported,
+}
+
+fn tag_kind(name: &str) -> TagKind {
+    match name {
+        "b" | "strong" => TagKind::Inline("b"),
+        "i" | "em" => TagKind::Inline("i"),
+        "u" | "ins" => TagKind::Inline("u"),
+        "s" | "strike" | "del" => TagKind::Inline("s"),
+        "a" => TagKind::Inline("a"),
+        "code" => TagKind::Inline("code"),
+        "pre" => TagKind::Inline("pre"),
+        "blockquote" => TagKind::Inline("blockquote"),
+        "tg-spoiler" => TagKind::Inline("tg-spoiler"),
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => TagKind::Heading,
+        "li" => TagKind::ListItem,
+        "br" => TagKind::Break("\n"),
+        "p" | "div" | "ul" | "ol" | "hr" => TagKind::Break("\n\n"),
+        _ => TagKind::Unsupported,
+    }
+}
+
+struct ParsedTag {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attributes: String,
+}
+
+/// Parses `<...>` at the start of `text`; `None` when it is not a tag but a stray `<`.
+fn parse_tag(text: &str) -> Option<(ParsedTag, usize)> {
+    let end = text.find('>')?;
+    let inner = &text[1..end];
+    if inner.contains('<') {
+        return None;
+    }
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(inner) => (true, inner),
+        None => (false, inner),
+    };
+    let (self_closing, inner) = match inner.strip_suffix('/') {
+        Some(inner) => (true, inner),
+        None => (false, inner),
+    };
+    let name_end = inner
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(inner.len());
+    let name = &inner[..name_end];
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let attributes = inner[name_end..].trim();
+    if !attributes.is_empty() && !inner[name_end..].starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some((
+        ParsedTag {
+            name: name.to_lowercase(),
+            closing,
+            self_closing,
+            attributes: attributes.to_string(),
+        },
+        end + 1,
+    ))
+}
+
+/// Value of the attribute called exactly `name`, walking the attributes one by one
+/// so that neither `data-href` nor a quoted value containing `href=` matches.
+fn attribute_value(attributes: &str, name: &str) -> Option<String> {
+    let mut rest = attributes.trim_start();
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let attribute = &rest[..name_end];
+        rest = rest[name_end..].trim_start();
+        let mut value = None;
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (raw, len) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let end = after[1..].find(quote)?;
+                    (&after[1..1 + end], end + 2)
+                }
+                _ => {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    (&after[..end], end)
+                }
+            };
+            value = Some(raw);
+            rest = after[len..].trim_start();
+        }
+        if attribute.eq_ignore_ascii_case(name) {
+            return value.map(str::to_string);
+        }
+    }
+    None
+}
+
+/// Parses an entity at the start of `text` (`&name;`, `&#123;`, `&#x1F;`), returns its body and length.
+fn parse_entity(text: &str) -> Option<(&str, usize)> {
+    let end = text[1..].find(';')? + 1;
+    let body = &text[1..end];
+    let valid = match body.strip_prefix('#') {
+        Some(number) => match number.strip_prefix(['x', 'X']) {
+            Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            None => !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()),
+        },
+        None => !body.is_empty() && body.chars().all(|c| c.is_ascii_alphanumeric()),
+    };
+    valid.then_some((body, end + 1))
+}
+
+struct Sanitizer {
+    html: String,
+    changes: Vec<SanitizerChange>,
+    /// Open Telegram tags as (name, opening tag as emitted).
+    open: Vec<(&'static str, String)>,
+}
+
+impl Sanitizer {
+    fn change(&mut self, change: SanitizerChange) {
+        if !self.changes.contains(&change) {
+            self.changes.push(change);
+        }
+    }
+
+    fn open_tag(&mut self, name: &'static str, tag: String) {
+        self.html.push_str(&tag);
+        self.open.push((name, tag));
+    }
+
+    fn close_tag(&mut self, name: &str) {
+        let Some(index) = self.open.iter().rposition(|(open, _)| *open == name) else {
+            self.change(SanitizerChange::DroppedClosingTag(name.to_string()));
+            return;
+        };
+        // Close everything opened inside, then reopen it: <b><i></b></i> -> <b><i></i></b><i></i>
+        let inner = self.open.split_off(index + 1);
+        for (inner_name, _) in inner.iter().rev() {
+            self.html.push_str(&format!("</{}>", inner_name));
+            self.change(SanitizerChange::ReorderedTag(inner_name.to_string()));
+        }
+        self.open.pop();
+        self.html.push_str(&format!("</{}>", name));
+        for (inner_name, tag) in inner {
+            self.open_tag(inner_name, tag);
+        }
+    }
+
+    fn tag(&mut self, tag: ParsedTag) {
+        match tag_kind(&tag.name) {
+            TagKind::Inline(name) => {
+                if name != tag.name {
+                    self.change(SanitizerChange::ConvertedTag {
+                        from: tag.name.clone(),
+                        to: name.to_string(),
+                    });
+                }
+                if tag.closing {
+                    self.close_tag(name);
+                    return;
+                }
+                if tag.self_closing {
+                    self.change(SanitizerChange::StrippedTag(tag.name));
+                    return;
+                }
+                let opening = if name == "a" {
+                    match attribute_value(&tag.attributes, "href") {
+                        Some(href) => format!("<a href=\"{}\">", self.escape_attribute(&href)),
+                        None => {
+                            self.change(SanitizerChange::StrippedTag("a".to_string()));
+                            return;
+                        }
+                    }
+                } else {
+                    if !tag.attributes.is_empty() {
+                        self.change(SanitizerChange::StrippedAttributes(name.to_string()));
+                    }
+                    format!("<{}>", name)
+                };
+                self.open_tag(name, opening);
+            }
+            TagKind::Heading => {
+                self.change(SanitizerChange::ConvertedTag {
+                    from: tag.name.clone(),
+                    to: "b".to_string(),
+                });
+                if tag.closing {
+                    self.close_tag("b");
+                    self.html.push('\n');
+                } else {
+                    self.open_tag("b", "<b>".to_string());
+                }
+            }
+            TagKind::ListItem => {
+                self.change(SanitizerChange::StrippedTag(tag.name));
+                self.html.push_str(if tag.closing { "\n" } else { "• " });
+            }
+            TagKind::Break(separator) => {
+                self.change(SanitizerChange::StrippedTag(tag.name));
+                if tag.closing || tag.self_closing || separator == "\n" {
+                    self.html.push_str(separator);
+                }
+            }
+            TagKind::Unsupported => self.change(SanitizerChange::StrippedTag(tag.name)),
+        }
+    }
+
+    fn entity(&mut self, body: &str, raw: &str) -> String {
+        if TELEGRAM_ENTITIES.contains(&body) || body.starts_with('#') {
+            raw.to_string()
+        } else if let Some((_, value)) = NAMED_ENTITIES.iter().find(|(name, _)| *name == body) {
+            self.change(SanitizerChange::ReplacedEntity(body.to_string()));
+            value.to_string()
+        } else {
+            self.change(SanitizerChange::EscapedCharacter('&'));
+            format!("&amp;{}", &raw[1..])
+        }
+    }
+
+    /// Escapes an attribute value by the same rules as text, plus `"` for the quotes around it.
+    fn escape_attribute(&mut self, value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        let mut i = 0;
+        while i < value.len() {
+            let rest = &value[i..];
+            let c = rest.chars().next().unwrap_or_default();
+            match c {
+                '"' => escaped.push_str("&quot;"),
+                '<' | '>' => {
+                    escaped.push_str(if c == '<' { "&lt;" } else { "&gt;" });
+                    self.change(SanitizerChange::EscapedCharacter(c));
+                }
+                '&' => match parse_entity(rest) {
+                    Some((body, len)) => {
+                        let entity = self.entity(body, &rest[..len]);
+                        escaped.push_str(&entity.replace('"', "&quot;"));
+                        i += len;
+                        continue;
+                    }
+                    None => {
+                        escaped.push_str("&amp;");
+                        self.change(SanitizerChange::EscapedCharacter('&'));
+                    }
+                },
+                _ => escaped.push(c),
+            }
+            i += c.len_utf8();
+        }
+        escaped
+    }
+}
+
+/// Makes a post safe for `ParseMode::Html`: converts or strips unsupported tags,
+/// escapes stray `<`, `>` and `&`, replaces unknown entities and balances tags.
+pub fn sanitize_telegram_html(input: &str) -> SanitizedHtml {
+    let mut sanitizer = Sanitizer {
+        html: String::with_capacity(input.len()),
+        changes: Vec::new(),
+        open: Vec::new(),
+    };
+    let mut i = 0;
+    while i < input.len() {
+        let rest = &input[i..];
+        let c = rest.chars().next().unwrap_or_default();
+        match c {
+            '<' => match parse_tag(rest) {
+                Some((tag, len)) => {
+                    sanitizer.tag(tag);
+                    i += len;
+                    continue;
+                }
+                None => {
+                    sanitizer.html.push_str("&lt;");
+                    sanitizer.change(SanitizerChange::EscapedCharacter('<'));
+                }
+            },
+            '>' => {
+                sanitizer.html.push_str("&gt;");
+                sanitizer.change(SanitizerChange::EscapedCharacter('>'));
+            }
+            '&' => match parse_entity(rest) {
+                Some((body, len)) => {
+                    let entity = sanitizer.entity(body, &rest[..len]);
+                    sanitizer.html.push_str(&entity);
+                    i += len;
+                    continue;
+                }
+                None => {
+                    sanitizer.html.push_str("&amp;");
+                    sanitizer.change(SanitizerChange::EscapedCharacter('&'));
+                }
+            },
+            _ => sanitizer.html.push(c),
+        }
+        i += c.len_utf8();
+    }
+    while let Some((name, _)) = sanitizer.open.pop() {
+        sanitizer.html.push_str(&format!("</{}>", name));
+        sanitizer.change(SanitizerChange::ClosedTag(name.to_string()));
+    }
+    SanitizedHtml {
+        html: collapse_blank_lines(sanitizer.html.trim()),
+        changes: sanitizer.changes,
+    }
+}
+
+/// Converted block tags can leave runs of empty lines, keep at most one.
+fn collapse_blank_lines(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut newlines = 0;
+    for c in text.chars() {
+        if c == '\n' {
+            newlines += 1;
+            if newlines > 2 {
+                continue;
+            }
+        } else {
+            newlines = 0;
+        }
+        result.push(c);
+    }
+    result
+}
@@ -0,0 +1,171 @@
+/// Telegram limit for message text, counted after HTML entities are parsed.
+pub const TELEGRAM_MESSAGE_LIMIT: usize = 4096;
+
+/// Length of the text Telegram will count: tags removed, entities decoded,
+/// measured in UTF-16 code units like the Bot API does.
+pub fn visible_len(html: &str) -> usize {
+    let mut len = 0;
+    let mut in_tag = false;
+    let mut in_entity = false;
+    for c in html.chars() {
+        match c {
+            '<' if !in_tag => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if in_tag => {}
+            '&' => {
+                in_entity = true;
+                len += 1;
+            }
+            ';' if in_entity => in_entity = false,
+            _ if in_entity && (c.is_ascii_alphanumeric() || c == '#') => {}
+            _ => {
+                in_entity = false;
+                len += c.len_utf16();
+            }
+        }
+    }
+    len
+}
+
+/// Splits a Telegram HTML post into messages of at most `limit` visible characters.
+///
+/// Cuts on paragraphs first, then lines, then words; tags still open at a cut are
+/// closed at the end of the message and reopened at the start of the next one.
+pub fn split_html_message(html: &str, limit: usize) -> Vec<String> {
+    if visible_len(html) <= limit {
+        return vec![html.to_string()];
+    }
+    let mut chunks = Vec::new();
+    pack(html, &["\n\n", "\n", " "], limit, &mut chunks);
+    balance_tags(chunks)
+        .into_iter()
+        .filter(|chunk| visible_len(chunk.trim()) > 0)
+        .collect()
+}
+
+fn pack(text: &str, separators: &[&str], limit: usize, out: &mut Vec<String>) {
+    let separator = separators[0];
+    let mut current = String::new();
+    for piece in split_outside_tags(text, separator) {
+        let candidate = if current.is_empty() {
+            piece.to_string()
+        } else {
+            format!("{}{}{}", current, separator, piece)
+        };
+        if visible_len(&candidate) <= limit {
+            current = candidate;
+            continue;
+        }
+        if !current.is_empty() {
+            out.push(std::mem::take(&mut current));
+        }
+        if visible_len(piece) <= limit {
+            current = piece.to_string();
+        } else if separators.len() > 1 {
+            pack(piece, &separators[1..], limit, out);
+        } else {
+            hard_split(piece, limit, out);
+        }
+    }
+    if !current.is_empty() {
+        out.push(current);
+    }
+}
+
+/// Splits on `separator`, ignoring separators inside `<...>`.
+fn split_outside_tags<'a>(text: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut in_tag = false;
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if rest.starts_with('<') {
+            in_tag = true;
+        } else if rest.starts_with('>') {
+            in_tag = false;
+        } else if !in_tag && rest.starts_with(separator) {
+            pieces.push(&text[start..i]);
+            i += separator.len();
+            start = i;
+            continue;
+        }
+        i += rest.chars().next().map(char::len_utf8).unwrap_or(1);
+    }
+    pieces.push(&text[start..]);
+    pieces
+}
+
+/// Last resort for a single huge word: cuts between characters, never inside a tag or entity.
+fn hard_split(text: &str, limit: usize, out: &mut Vec<String>) {
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let mut token = c.to_string();
+        if c == '<' || c == '&' {
+            let end = if c == '<' { '>' } else { ';' };
+            while let Some(&next) = chars.peek() {
+                token.push(next);
+                chars.next();
+                if next == end {
+                    break;
+                }
+            }
+        }
+        if visible_len(&current) + visible_len(&token) > limit && !current.is_empty() {
+            out.push(std::mem::take(&mut current));
+        }
+        current.push_str(&token);
+    }
+    if !current.is_empty() {
+        out.push(current);
+    }
+}
+
+fn balance_tags(chunks: Vec<String>) -> Vec<String> {
+    // (tag name, opening tag as written)
+    let mut open: Vec<(String, String)> = Vec::new();
+    let mut balanced = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        let prefix: String = open.iter().map(|(_, tag)| tag.as_str()).collect();
+        for tag in tags(&chunk) {
+            let name = tag_name(tag);
+            if tag.starts_with("</") {
+                if let Some(index) = open.iter().rposition(|(open_name, _)| *open_name == name) {
+                    open.remove(index);
+                }
+            } else if !tag.ends_with("/>") {
+                open.push((name, tag.to_string()));
+            }
+        }
+        let suffix: String = open
+            .iter()
+            .rev()
+            .map(|(name, _)| format!("</{}>", name))
+            .collect();
+        balanced.push(format!("{}{}{}", prefix, chunk, suffix));
+    }
+    balanced
+}
+
+fn tags(html: &str) -> Vec<&str> {
+    let mut tags = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        tags.push(&rest[start..start + end + 1]);
+        rest = &rest[start + end + 1..];
+    }
+    tags
+}
+
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches('<')
+        .trim_start_matches('/')
+        .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
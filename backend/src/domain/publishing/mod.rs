@@ -1,3 +1,9 @@
+pub mod approval;
+pub mod html_sanitizer;
+pub mod message_splitter;
 pub mod publisher;
 
-pub use publisher::*;
\ No newline at end of file
+pub use approval::*;
+pub use html_sanitizer::*;
+pub use message_splitter::*;
+pub use publisher::*;
@@ -1,28 +1,121 @@
+use super::message_splitter::{split_html_message, visible_len, TELEGRAM_MESSAGE_LIMIT};
+use crate::domain::posts::PostRepository;
+use adco_shared::post::{Post, PostStatus};
 use teloxide::{
     payloads::SendMessageSetters,
     prelude::Requester,
-    types::{ParseMode, Recipient},
+    types::{MessageId, ParseMode, Recipient},
     Bot,
 };
+use tracing::warn;
 
 pub struct Publisher {
     bot: Bot,
     channel_username: String,
+    /// Failed sends after which a scheduled post is taken off the schedule.
+    max_attempts: i32,
 }
 
 impl Publisher {
+    /// Reads `TELEGRAM_CHANNEL_USERNAME` and `ADCO_PUBLISH_MAX_ATTEMPTS` (default 3).
     pub fn from_env() -> Result<Self, anyhow::Error> {
         let bot = Bot::from_env();
         let channel_username = std::env::var("TELEGRAM_CHANNEL_USERNAME")?;
-        Ok(Self { bot, channel_username: format!("@{}", channel_username) })
+        let max_attempts = std::env::var("ADCO_PUBLISH_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|v| v.parse::<i32>().ok())
+            .unwrap_or(3)
+            .max(1);
+        Ok(Self {
+            bot,
+            channel_username: format!("@{}", channel_username),
+            max_attempts,
+        })
     }
 
-    pub async fn publish(&self, post: &str) -> Result<(), anyhow::Error> {
-        let message = post.to_string();
-        self.bot
-            .send_message(Recipient::ChannelUsername(self.channel_username.clone()), message)
-            .parse_mode(ParseMode::Html)
+    /// Sends the post, split into several messages if it is over the Telegram limit.
+    /// Returns the ids of all sent messages. If a part fails, the parts already sent
+    /// are deleted, so a retry does not leave duplicates in the channel.
+    pub async fn publish(&self, post: &str) -> Result<Vec<i64>, anyhow::Error> {
+        let recipient = Recipient::ChannelUsername(self.channel_username.clone());
+        let messages = split_html_message(post, TELEGRAM_MESSAGE_LIMIT);
+        if messages.len() > 1 {
+            warn!(
+                "Post is {} characters long, sending it as {} messages",
+                visible_len(post),
+                messages.len()
+            );
+        }
+        let mut message_ids = Vec::new();
+        for message in messages {
+            match self
+                .bot
+                .send_message(recipient.clone(), message)
+                .parse_mode(ParseMode::Html)
+                .await
+            {
+                Ok(sent) => message_ids.push(sent.id.0 as i64),
+                Err(e) => {
+                    for message_id in message_ids {
+                        if let Err(e) = self
+                            .bot
+                            .delete_message(recipient.clone(), MessageId(message_id as i32))
+                            .await
+                        {
+                            warn!("Failed to delete partially published message: {:?}", e);
+                        }
+                    }
+                    return Err(e.into());
+                }
+            }
+        }
+        Ok(message_ids)
+    }
+
+    /// Publishes the post to the channel and marks it as published.
+    ///
+    /// The post is claimed first by moving it from its current status to publishing, so it
+    /// fails with [`crate::domain::posts::PostRepositoryError::StatusChanged`] when
+    /// another publisher got it. The message ids are saved right after sending. A failed send is recorded
+    /// on the post, which goes back to its status or, after `ADCO_PUBLISH_MAX_ATTEMPTS`
+    /// failures, to draft.
+    pub async fn publish_post<R: PostRepository>(
+        &self,
+        post_repo: &R,
+        post: &mut Post,
+    ) -> Result<(), anyhow::Error> {
+        let id = post
+            .id
+            .ok_or_else(|| anyhow::anyhow!("Post is not saved"))?;
+        let status = post.status.clone();
+        post_repo
+            .transition_status(id, status.clone(), PostStatus::Publishing)
             .await?;
-        Ok(())
+        post.status = PostStatus::Publishing;
+        match self.publish(&post.post_text).await {
+            Ok(message_ids) => {
+                let published_at = chrono::Utc::now();
+                post_repo
+                    .mark_published(id, &message_ids, published_at)
+                    .await?;
+                post.telegram_message_ids = message_ids;
+                post.status = PostStatus::Published;
+                post.published_at = Some(published_at);
+                post.publish_error = None;
+                Ok(())
+            }
+            Err(e) => {
+                *post = post_repo
+                    .record_publish_failure(id, &e.to_string(), status, self.max_attempts)
+                    .await?;
+                if post.publish_attempts >= self.max_attempts {
+                    warn!(
+                        "Post {} failed to publish {} times, moved back to draft",
+                        id, post.publish_attempts
+                    );
+                }
+                Err(e)
+            }
+        }
     }
 }
@@ -0,0 +1,250 @@
+use crate::domain::{
+    feeds::FeedPoller,
+    infra::postgres::{advisory_lock::AdvisoryLock, posts_repo::PostsRepositoryPostgres},
+    orchestrator::{Orchestrator, PublishApproval},
+    posts::{PostRepository, PostRepositoryError},
+    publishing::Publisher,
+};
+use adco_shared::pipeline::RunRequest;
+use sqlx::PgPool;
+use std::future::Future;
+use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Advisory lock keys, shared by every backend instance that uses the same database.
+const PIPELINE_LOCK_KEY: i64 = 0x4144_434F_0001;
+const PUBLISH_LOCK_KEY: i64 = 0x4144_434F_0002;
+const FEEDS_LOCK_KEY: i64 = 0x4144_434F_0003;
+
+/// Cron expressions (with seconds: `sec min hour day month weekday`) of the scheduled jobs.
+/// An empty value disables the job.
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    pub pipeline_cron: Option<String>,
+    pub publish_cron: Option<String>,
+    /// How often feeds are checked; each feed is polled only after its own interval.
+    pub feeds_cron: Option<String>,
+}
+
+impl SchedulerConfig {
+    /// `ADCO_SCHEDULER_PIPELINE_CRON` (default daily at 09:00 UTC),
+    /// `ADCO_SCHEDULER_PUBLISH_CRON` (default every minute) and
+    /// `ADCO_SCHEDULER_FEEDS_CRON` (default every 5 minutes).
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: &str| {
+            let value = std::env::var(name).unwrap_or_else(|_| default.to_string());
+            Some(value).filter(|value| !value.trim().is_empty())
+        };
+        Self {
+            pipeline_cron: read("ADCO_SCHEDULER_PIPELINE_CRON", "0 0 9 * * *"),
+            publish_cron: read("ADCO_SCHEDULER_PUBLISH_CRON", "0 * * * * *"),
+            feeds_cron: read("ADCO_SCHEDULER_FEEDS_CRON", "0 */5 * * * *"),
+        }
+    }
+}
+
+/// Runs the generation pipeline, publishes due scheduled posts and polls feeds on cron.
+pub struct Scheduler {
+    pg_pool: PgPool,
+    config: SchedulerConfig,
+}
+
+impl Scheduler {
+    pub fn new(pg_pool: &PgPool, config: SchedulerConfig) -> Self {
+        Self {
+            pg_pool: pg_pool.clone(),
+            config,
+        }
+    }
+
+    /// Registers the jobs and starts them. The returned scheduler must be kept alive.
+    pub async fn start(&self) -> Result<JobScheduler, anyhow::Error> {
+        let scheduler = JobScheduler::new().await?;
+        if let Some(cron) = &self.config.pipeline_cron {
+            let pg_pool = self.pg_pool.clone();
+            scheduler
+                .add(Job::new_async(cron.as_str(), move |_, _| {
+                    let pg_pool = pg_pool.clone();
+                    Box::pin(async move {
+                        if let Err(e) = run_pipeline(&pg_pool).await {
+                            error!("Scheduled pipeline run failed: {:?}", e);
+                        }
+                    })
+                })?)
+                .await?;
+            info!("Pipeline scheduled: {}", cron);
+        }
+        if let Some(cron) = &self.config.publish_cron {
+            let pg_pool = self.pg_pool.clone();
+            scheduler
+                .add(Job::new_async(cron.as_str(), move |_, _| {
+                    let pg_pool = pg_pool.clone();
+                    Box::pin(async move {
+                        if let Err(e) = publish_due_posts(&pg_pool).await {
+                            error!("Publishing scheduled posts failed: {:?}", e);
+                        }
+                    })
+                })?)
+                .await?;
+            info!("Scheduled posts publishing: {}", cron);
+        }
+        if let Some(cron) = &self.config.feeds_cron {
+            let pg_pool = self.pg_pool.clone();
+            scheduler
+                .add(Job::new_async(cron.as_str(), move |_, _| {
+                    let pg_pool = pg_pool.clone();
+                    Box::pin(async move {
+                        if let Err(e) = poll_due_feeds(&pg_pool).await {
+                            error!("Polling feeds failed: {:?}", e);
+                        }
+                    })
+                })?)
+                .await?;
+            info!("Feeds polling: {}", cron);
+        }
+        scheduler.start().await?;
+        Ok(scheduler)
+    }
+}
+
+/// Runs `run` holding the pipeline lock, so it never overlaps a scheduled, API or CLI run
+/// here or in another instance. Returns `None` without running when one is in progress.
+pub async fn with_pipeline_lock<T, F, Fut>(
+    pg_pool: &PgPool,
+    run: F,
+) -> Result<Option<T>, anyhow::Error>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, anyhow::Error>>,
+{
+    let Some(lock) = AdvisoryLock::try_acquire(pg_pool, PIPELINE_LOCK_KEY).await? else {
+        return Ok(None);
+    };
+    let result = run().await;
+    lock.release().await?;
+    result.map(Some)
+}
+
+/// Runs the pipeline unless a run is already in progress here or in another instance.
+/// Nobody is at the terminal, so drafts go to the Telegram admin chat if there is one.
+pub async fn run_pipeline(pg_pool: &PgPool) -> Result<(), anyhow::Error> {
+    let run_id = with_pipeline_lock(pg_pool, || async {
+        info!("Scheduled pipeline run starting");
+        let orchestrator = Orchestrator::from_env()
+            .await?
+            .publish_approval(PublishApproval::unattended());
+        orchestrator.orchestrate().await
+    })
+    .await?;
+    match run_id {
+        Some(run_id) => info!("Scheduled pipeline run {} finished", run_id),
+        None => warn!("Previous pipeline run is still in progress, skipping"),
+    }
+    Ok(())
+}
+
+/// Starts a run in the background and returns its id right away, or `None` when
+/// a run is already in progress.
+pub async fn start_pipeline_run(
+    pg_pool: &PgPool,
+    request: RunRequest,
+) -> Result<Option<Uuid>, anyhow::Error> {
+    let run_ids = start_pipeline_runs(pg_pool, vec![request]).await?;
+    Ok(run_ids.and_then(|run_ids| run_ids.into_iter().next()))
+}
+
+/// Creates a run per request and executes them one after another in the background.
+/// Returns the run ids right away, or `None` when a run is already in progress.
+/// The pipeline lock is held until the last run ends.
+pub async fn start_pipeline_runs(
+    pg_pool: &PgPool,
+    requests: Vec<RunRequest>,
+) -> Result<Option<Vec<Uuid>>, anyhow::Error> {
+    let Some(lock) = AdvisoryLock::try_acquire(pg_pool, PIPELINE_LOCK_KEY).await? else {
+        return Ok(None);
+    };
+    let created = async {
+        let orchestrator = Orchestrator::from_env()
+            .await?
+            .publish_approval(PublishApproval::unattended());
+        let mut run_ids = Vec::with_capacity(requests.len());
+        for request in &requests {
+            let run_id = orchestrator.create_run(request).await?;
+            info!("Pipeline run {} requested: {:?}", run_id, request);
+            run_ids.push(run_id);
+        }
+        Ok::<_, anyhow::Error>((orchestrator, run_ids))
+    }
+    .await;
+    let (orchestrator, run_ids) = match created {
+        Ok(created) => created,
+        Err(e) => {
+            lock.release().await?;
+            return Err(e);
+        }
+    };
+    let background_run_ids = run_ids.clone();
+    tokio::spawn(async move {
+        orchestrator.resume_all(&background_run_ids).await;
+        if let Err(e) = lock.release().await {
+            error!("Failed to release pipeline lock: {:?}", e);
+        }
+    });
+    Ok(Some(run_ids))
+}
+
+/// Publishes scheduled posts whose time has come and marks them as published.
+pub async fn publish_due_posts(pg_pool: &PgPool) -> Result<(), anyhow::Error> {
+    let Some(lock) = AdvisoryLock::try_acquire(pg_pool, PUBLISH_LOCK_KEY).await? else {
+        return Ok(());
+    };
+    let result = async {
+        let post_repo = PostsRepositoryPostgres::new(pg_pool);
+        let posts = post_repo
+            .get_due_scheduled_posts(chrono::Utc::now())
+            .await?;
+        if posts.is_empty() {
+            return Ok(());
+        }
+        let publisher = Publisher::from_env()?;
+        for mut post in posts {
+            match publisher.publish_post(&post_repo, &mut post).await {
+                Ok(()) => info!("Scheduled post {:?} published", post.id),
+                Err(e) if matches!(
+                    e.downcast_ref::<PostRepositoryError>(),
+                    Some(PostRepositoryError::StatusChanged { .. })
+                ) =>
+                {
+                    info!("Scheduled post {:?} was changed meanwhile, skipping", post.id)
+                }
+                Err(e) => error!(
+                    "Failed to publish post {:?} (attempt {}): {:?}",
+                    post.id, post.publish_attempts, e
+                ),
+            }
+        }
+        Ok::<(), anyhow::Error>(())
+    }
+    .await;
+    lock.release().await?;
+    result
+}
+
+/// Polls the feeds whose interval has passed, one instance at a time.
+pub async fn poll_due_feeds(pg_pool: &PgPool) -> Result<(), anyhow::Error> {
+    let Some(lock) = AdvisoryLock::try_acquire(pg_pool, FEEDS_LOCK_KEY).await? else {
+        return Ok(());
+    };
+    let result = async {
+        let reports = FeedPoller::new(pg_pool).poll_due(chrono::Utc::now()).await?;
+        let stored: usize = reports.iter().map(|report| report.stored).sum();
+        if stored > 0 {
+            info!("Feeds polled: {} new sources from {} feeds", stored, reports.len());
+        }
+        Ok::<(), anyhow::Error>(())
+    }
+    .await;
+    lock.release().await?;
+    result
+}
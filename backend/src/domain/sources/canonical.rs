@@ -0,0 +1,70 @@
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+
+/// Query parameters that only track where a visitor came from.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "yclid", "msclkid", "mc_cid", "mc_eid", "igshid", "ref", "ref_src",
+    "_hsenc", "_hsmi",
+];
+
+/// Key under which a source is stored: the same page reached through different links gets
+/// the same key. The original URL is still kept in `sources.url` for fetching.
+///
+/// The scheme becomes https, `www.`, tracking parameters and the fragment are dropped, the
+/// remaining parameters sorted, and every arXiv link (abs, pdf, any version) becomes
+/// `https://arxiv.org/abs/<id>`.
+pub fn canonical_url(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url.trim()) else {
+        return url.trim().to_string();
+    };
+    let host = parsed.host_str().unwrap_or_default().to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host).to_string();
+    if let Some(id) = arxiv_id(&host, parsed.path()) {
+        return format!("https://arxiv.org/abs/{}", id);
+    }
+
+    let mut params: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(name, _)| !name.starts_with("utm_") && !TRACKING_PARAMS.contains(&name.as_ref()))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    params.sort();
+    parsed.set_query(None);
+    if !params.is_empty() {
+        parsed.query_pairs_mut().extend_pairs(params);
+    }
+
+    let path = parsed.path().trim_end_matches('/');
+    let port = parsed.port().map(|p| format!(":{}", p)).unwrap_or_default();
+    match parsed.query() {
+        Some(query) => format!("https://{}{}{}?{}", host, port, path, query),
+        None => format!("https://{}{}{}", host, port, path),
+    }
+}
+
+/// `2101.00001` from `/abs/2101.00001v2`, `/pdf/2101.00001v1.pdf` and the like,
+/// old-style ids (`math/0601001`) included.
+fn arxiv_id(host: &str, path: &str) -> Option<String> {
+    if host != "arxiv.org" && host != "export.arxiv.org" {
+        return None;
+    }
+    let id = path
+        .strip_prefix("/abs/")
+        .or_else(|| path.strip_prefix("/pdf/"))?
+        .trim_end_matches('/');
+    let id = id.strip_suffix(".pdf").unwrap_or(id);
+    let id = match id.rfind('v') {
+        Some(at)
+            if at > 0 && id[at + 1..].chars().all(|c| c.is_ascii_digit()) && at + 1 < id.len() =>
+        {
+            &id[..at]
+        }
+        _ => id,
+    };
+    Some(id.to_string()).filter(|id| !id.is_empty())
+}
+
+/// sha256 of the source text, hex encoded; the same as `sha256(convert_to(raw_text, 'UTF8'))`.
+pub fn content_hash(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
@@ -0,0 +1,105 @@
+use super::{HtmlParser, HttpFetcher};
+use adco_shared::source::Source;
+use std::future::Future;
+use std::pin::Pin;
+use tracing::{error, info};
+
+pub type SourcesFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<Source>, anyhow::Error>> + Send + 'a>>;
+
+/// Finds and scrapes sources for the search queries of a topic.
+pub trait SourceCollector: Send + Sync {
+    fn collect<'a>(&'a self, short_search_query: &'a str, full_search_query: &'a str) -> SourcesFuture<'a>;
+}
+
+/// Collects sources from HackerNews and arXiv and scrapes them.
+#[derive(Default)]
+pub struct WebSourceCollector;
+
+impl WebSourceCollector {
+    pub fn new() -> Self {
+        WebSourceCollector {}
+    }
+
+    async fn collect_from_web(
+        &self,
+        short_search_query: &str,
+        full_search_query: &str,
+    ) -> Result<Vec<Source>, anyhow::Error> {
+        let fetcher = HttpFetcher::new();
+        let parser = HtmlParser::new();
+        let mut sources = Vec::new();
+        let mut urls_to_scrape: Vec<(String, Option<String>)> = Vec::new();
+
+        // Collect URLs from HackerNews
+        match fetcher
+            .search_hackernews(short_search_query)
+            .await
+        {
+            Ok(hn_response) => {
+                for hit in hn_response.hits.iter().take(5) {
+                    if let Some(url) = &hit.url {
+                        urls_to_scrape.push((url.clone(), None));
+                    }
+                }
+            }
+            Err(e) => {
+                error!("Error fetching from HackerNews: {:?}", e);
+            }
+        }
+        info!("Collected {} URLs from HackerNews", urls_to_scrape.len());
+        // Collect URLs from arXiv (use PDF links)
+        match fetcher
+            .search_arxiv(full_search_query)
+            .await
+        {
+            Ok(arxiv_entries) => {
+                for entry in arxiv_entries.iter().take(5) {
+                    // Convert arXiv id to PDF URL
+                    // e.g. "http://arxiv.org/abs/2310.00266v1" -> "http://arxiv.org/pdf/2310.00266v1.pdf"
+                    let pdf_url = entry.id.replace("/abs/", "/pdf/") + ".pdf";
+                    urls_to_scrape.push((pdf_url, Some(entry.title.clone())));
+                }
+            }
+            Err(e) => {
+                error!("Error fetching from arXiv: {:?}", e);
+            }
+        }
+        info!("Collected {} URLs to scrape", urls_to_scrape.len());
+        // Now scrape all collected URLs
+        for url in urls_to_scrape.iter() {
+            match parser.scrap_source_from_url(&url.0, url.1.as_deref()).await {
+                Ok(source) => {
+                    sources.push(source);
+                }
+                Err(e) => {
+                    error!("Error scraping source from url {}: {:?}", url.0, e);
+                }
+            }
+        }
+        info!("Scraped {} sources", sources.len());
+        Ok(sources)
+    }
+}
+
+impl SourceCollector for WebSourceCollector {
+    fn collect<'a>(&'a self, short_search_query: &'a str, full_search_query: &'a str) -> SourcesFuture<'a> {
+        Box::pin(self.collect_from_web(short_search_query, full_search_query))
+    }
+}
+
+/// Returns a fixed set of sources, for offline runs and tests.
+pub struct StaticSourceCollector {
+    sources: Vec<Source>,
+}
+
+impl StaticSourceCollector {
+    pub fn new(sources: Vec<Source>) -> Self {
+        Self { sources }
+    }
+}
+
+impl SourceCollector for StaticSourceCollector {
+    fn collect<'a>(&'a self, _short_search_query: &'a str, _full_search_query: &'a str) -> SourcesFuture<'a> {
+        Box::pin(async move { Ok(self.sources.clone()) })
+    }
+}
@@ -1,26 +1,26 @@
-use reqwest;
+use crate::domain::infra::http_client::ResilientHttpClient;
 use adco_shared::source::{HackerNewsResponse, ArxivFeed, ArxivEntry};
 use quick_xml::de::from_str;
 
 #[derive(Debug, Clone)]
 pub struct HttpFetcher {
-    client: reqwest::Client,
+    client: ResilientHttpClient,
 }
 
 impl HttpFetcher {
     pub fn new() -> Self {
         HttpFetcher {
-            client: reqwest::Client::new(),
+            client: ResilientHttpClient::new(),
         }
     }
 
     pub async fn fetch(&self, url: &str) -> Result<String, anyhow::Error> {
-        let response = self.client.get(url).send().await?;
-        let status = response.status().as_u16();
+        let response = self.client.get(url).await?;
+        let status = response.status;
         if status != 200 {
             return Err(HttpFetchError::StatusCode(status).into());
         }
-        let body = response.text().await?;
+        let body = response.text();
         if body.contains("You can’t perform that action") {
             return Err(HttpFetchError::BotDetection.into());
         }
@@ -29,24 +29,24 @@ impl HttpFetcher {
 
     pub async fn search_hackernews(&self, query: &str) -> Result<HackerNewsResponse, anyhow::Error> {
         let url = format!("http://hn.algolia.com/api/v1/search?query={}&tags=story", query);
-        let response = self.client.get(&url).send().await?;
-        let status = response.status().as_u16();
+        let response = self.client.get(&url).await?;
+        let status = response.status;
         if status != 200 {
             return Err(HttpFetchError::StatusCode(status).into());
         }
-        let body = response.json::<HackerNewsResponse>().await?;
+        let body = response.json::<HackerNewsResponse>()?;
         Ok(body)
     }
 
     pub async fn search_arxiv(&self, query: &str) -> Result<Vec<ArxivEntry>, anyhow::Error> {
         let url = format!("http://export.arxiv.org/api/query?search_query=all:{}&start=0&max_results=10",
                          query.replace(" ", "+"));
-        let response = self.client.get(&url).send().await?;
-        let status = response.status().as_u16();
+        let response = self.client.get(&url).await?;
+        let status = response.status;
         if status != 200 {
             return Err(HttpFetchError::StatusCode(status).into());
         }
-        let body = response.text().await?;
+        let body = response.text();
         // Parse Atom XML using quick_xml
         let feed: ArxivFeed = from_str(&body)?;
         Ok(feed.entry)
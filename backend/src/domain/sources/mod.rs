@@ -1,7 +1,11 @@
+pub mod canonical;
+pub mod collector;
 pub mod fetcher;
 pub mod parser;
 pub mod repository;
 
+pub use canonical::*;
+pub use collector::*;
 pub use fetcher::*;
 pub use parser::*;
 pub use repository::*;
@@ -1,16 +1,17 @@
 use super::fetcher::HttpFetcher;
+use crate::domain::infra::http_client::ResilientHttpClient;
 use anyhow::Result;
 use headless_chrome::{Browser, LaunchOptions};
 use readability::extractor;
 use reqwest::Url;
-use scraper::{Html, Selector};
+use scraper::{node::Node, ElementRef, Html, Selector};
 use adco_shared::source::{Source, SourceType};
 use pdf_extract;
 use std::io::Cursor;
 use tracing::info;
 
 enum ContentType {
-    StaticPage { document: Html },
+    StaticPage { html: String },
     DynamicPage,
     PdfFile { url: String, title: Option<String> },
 }
@@ -28,22 +29,21 @@ impl HtmlParser {
     }
 
     async fn fetch_pdf_bytes(&self, url: &str) -> Result<Vec<u8>, anyhow::Error> {
-        let client = reqwest::Client::new();
-        let response = client.get(url).send().await?;
-        if response.status() != 200 {
-            return Err(anyhow::anyhow!("Failed to fetch PDF: {}", response.status()));
+        let client = ResilientHttpClient::new();
+        let response = client.get(url).await?;
+        if response.status != 200 {
+            return Err(anyhow::anyhow!("Failed to fetch PDF: {}", response.status));
         }
-        let bytes = response.bytes().await?;
-        Ok(bytes.to_vec())
+        Ok(response.body)
     }
 
     pub async fn scrap_source_from_url(&self, url: &str, title: Option<&str>) -> Result<Source, anyhow::Error> {
         match self.detect_content_type(url, title).await? {
-            ContentType::StaticPage { document } => {
+            ContentType::StaticPage { html } => {
                 info!("Static page detected");
-                let html_or_text = document.html();
+                let html_or_text = html;
                 let document = extractor::extract(&mut html_or_text.as_bytes(), &Url::parse(url)?)?;
-                let text = document.text;
+                let text = readable_text(&document);
                 return Ok(Source::new(url.to_string(), title.unwrap_or(&document.title).to_string(), SourceType::WebPage, text));
             }
             ContentType::DynamicPage => {
@@ -54,7 +54,7 @@ impl HtmlParser {
                     Ok(text) => {
                         let html_or_text = text;
                         let document = extractor::extract(&mut html_or_text.as_bytes(), &Url::parse(url)?)?;
-                        let text = document.text;
+                        let text = readable_text(&document);
                         info!("document title: {}", document.title);
                         info!("document text length: {}", text.len());
                         return Ok(Source::new(url.to_string(), title.unwrap_or(&document.title).to_string(), SourceType::WebPage, text));
@@ -74,7 +74,8 @@ impl HtmlParser {
         info!("PDF detected, parsing...");
         let pdf_bytes = self.fetch_pdf_bytes(url).await?;
         let cursor = Cursor::new(pdf_bytes);
-        let text = pdf_extract::extract_text_from_mem(cursor.get_ref())?;
+        // Pages are separated by form feeds, the chunker keeps track of them
+        let text = pdf_extract::extract_text_from_mem_by_pages(cursor.get_ref())?.join("\u{c}");
 
         // Extract title from URL or use filename
         let pdf_title = url.split('/').last()
@@ -82,7 +83,7 @@ impl HtmlParser {
             .replace(".pdf", "");
 
         info!("PDF parsed, text length: {}", text.len());
-        Ok(Source::new(url.to_string(), title.unwrap_or(&pdf_title).to_string(), SourceType::WebPage, text))
+        Ok(Source::new(url.to_string(), title.unwrap_or(&pdf_title).to_string(), SourceType::PDF, text))
     }
 
     // 1. Подсчет символов текста - если в <body> меньше 500 символов → DynamicPage
@@ -121,7 +122,7 @@ impl HtmlParser {
             return Ok(ContentType::DynamicPage);
         }
 
-        Ok(ContentType::StaticPage { document })
+        Ok(ContentType::StaticPage { html: document.html() })
     }
 
     async fn crawl_text_from_url(
@@ -148,3 +149,70 @@ impl HtmlParser {
         Ok(document.html())
     }
 }
+
+/// Text of the readable part of a page as markdown, falling back to the plain text.
+fn readable_text(document: &extractor::Product) -> String {
+    let markdown = html_to_markdown(&document.content);
+    if markdown.is_empty() {
+        document.text.clone()
+    } else {
+        markdown
+    }
+}
+
+/// Keeps the structure the chunker needs: headings become `#` lines, paragraphs, list
+/// items and other blocks are separated by blank lines, inline markup is dropped.
+pub fn html_to_markdown(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut blocks = Vec::new();
+    let mut current = String::new();
+    write_markdown(fragment.root_element(), &mut blocks, &mut current);
+    flush_block(&mut blocks, &mut current, "");
+    blocks.join("\n\n")
+}
+
+fn write_markdown(element: ElementRef, blocks: &mut Vec<String>, current: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => current.push_str(text),
+            Node::Element(child_element) => {
+                let Some(child_ref) = ElementRef::wrap(child) else {
+                    continue;
+                };
+                let name = child_element.name();
+                match name {
+                    "script" | "style" | "noscript" | "template" => {}
+                    "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                        flush_block(blocks, current, "");
+                        write_markdown(child_ref, blocks, current);
+                        let level = name[1..].parse::<usize>().unwrap_or(1);
+                        flush_block(blocks, current, &format!("{} ", "#".repeat(level)));
+                    }
+                    "li" => {
+                        flush_block(blocks, current, "");
+                        write_markdown(child_ref, blocks, current);
+                        flush_block(blocks, current, "- ");
+                    }
+                    "br" => current.push(' '),
+                    "p" | "div" | "section" | "article" | "main" | "header" | "footer" | "aside"
+                    | "blockquote" | "pre" | "ul" | "ol" | "dl" | "dt" | "dd" | "table" | "tr"
+                    | "figure" | "figcaption" => {
+                        flush_block(blocks, current, "");
+                        write_markdown(child_ref, blocks, current);
+                        flush_block(blocks, current, "");
+                    }
+                    _ => write_markdown(child_ref, blocks, current),
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn flush_block(blocks: &mut Vec<String>, current: &mut String, prefix: &str) {
+    let text = current.split_whitespace().collect::<Vec<_>>().join(" ");
+    if !text.is_empty() {
+        blocks.push(format!("{}{}", prefix, text));
+    }
+    current.clear();
+}
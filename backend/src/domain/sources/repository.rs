@@ -1,4 +1,5 @@
-use adco_shared::source::Source;
+use crate::domain::content::EmbeddedChunk;
+use adco_shared::source::{Source, SourceChunk, SourceSummary};
 use uuid::Uuid;
 use thiserror::Error;
 
@@ -6,7 +7,58 @@ pub trait SourceRepository {
     async fn create_source(&self, source: &Source) -> Result<Uuid, SourceRepositoryError>;
     async fn get_source_by_id(&self, id: Uuid) -> Result<Option<Source>, SourceRepositoryError>;
     async fn update_source(&self, source: &Source) -> Result<(), SourceRepositoryError>;
+    /// Stores a fetched source unless one with the same canonical URL or the same text is
+    /// already known. A known source whose text changed is updated and loses its chunks.
+    async fn upsert_source(&self, source: &Source) -> Result<SourceUpsert, SourceRepositoryError>;
+    /// Recomputes `canonical_url` of every row, for rows stored before deduplication, and
+    /// merges rows with the same canonical URL into one: the oldest with chunks, otherwise
+    /// the oldest. The other rows are deleted with their chunks.
+    async fn canonicalize_sources(&self) -> Result<SourceCanonicalization, SourceRepositoryError>;
+    /// Deletes the source together with its embedded chunks.
     async fn delete_source(&self, id: Uuid) -> Result<(), SourceRepositoryError>;
+
+    async fn list_sources(&self, limit: i64, offset: i64) -> Result<Vec<SourceSummary>, SourceRepositoryError>;
+    async fn count_sources(&self) -> Result<i64, SourceRepositoryError>;
+    async fn get_source_chunks(&self, source_id: Uuid) -> Result<Vec<SourceChunk>, SourceRepositoryError>;
+    /// Removes the embedded chunks of a source, returns how many were deleted.
+    async fn delete_source_chunks(&self, source_id: Uuid) -> Result<u64, SourceRepositoryError>;
+    /// Saves the source and swaps its chunks for `chunks` in one transaction, so a failure
+    /// leaves the text, its hash and the chunks as they were. Returns how many were deleted.
+    async fn replace_source_chunks(&self, source: &Source, chunks: &[EmbeddedChunk]) -> Result<u64, SourceRepositoryError>;
+}
+
+/// What [`SourceRepository::upsert_source`] did with the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceUpsert {
+    Created(Uuid),
+    /// Already stored with the same text and chunks, they are still valid.
+    Unchanged(Uuid),
+    /// Already stored with a different text or without chunks, the old chunks were removed.
+    Updated(Uuid),
+}
+
+impl SourceUpsert {
+    pub fn id(&self) -> Uuid {
+        match self {
+            SourceUpsert::Created(id) | SourceUpsert::Unchanged(id) | SourceUpsert::Updated(id) => *id,
+        }
+    }
+
+    /// Whether the source has to be chunked and embedded.
+    pub fn needs_indexing(&self) -> bool {
+        !matches!(self, SourceUpsert::Unchanged(_))
+    }
+}
+
+/// What [`SourceRepository::canonicalize_sources`] changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceCanonicalization {
+    /// Rows whose canonical URL was set or changed.
+    pub updated: usize,
+    /// Duplicate rows merged into another one and deleted.
+    pub merged: usize,
+    /// Chunks of the deleted rows.
+    pub deleted_chunks: u64,
 }
 
 #[derive(Error, Debug)]
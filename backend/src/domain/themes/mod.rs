@@ -0,0 +1,5 @@
+pub mod repository;
+pub mod selection;
+
+pub use repository::*;
+pub use selection::*;
@@ -0,0 +1,27 @@
+use adco_shared::theme::TopicTheme;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub trait ThemeRepository {
+    /// All themes ordered by category and name.
+    async fn list_themes(&self) -> Result<Vec<TopicTheme>, ThemeRepositoryError>;
+    async fn get_enabled_themes(&self) -> Result<Vec<TopicTheme>, ThemeRepositoryError>;
+    async fn get_theme_by_id(&self, id: Uuid) -> Result<Option<TopicTheme>, ThemeRepositoryError>;
+    async fn create_theme(&self, theme: &TopicTheme) -> Result<Uuid, ThemeRepositoryError>;
+    async fn update_theme(&self, theme: &TopicTheme) -> Result<(), ThemeRepositoryError>;
+    /// Starts the cooldown of the theme called `name`, unknown names are ignored.
+    async fn mark_theme_used(
+        &self,
+        name: &str,
+        at: DateTime<Utc>,
+    ) -> Result<(), ThemeRepositoryError>;
+}
+
+#[derive(Error, Debug)]
+pub enum ThemeRepositoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error("Theme not found: {id}")]
+    NotFound { id: Uuid },
+}
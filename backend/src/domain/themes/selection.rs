@@ -0,0 +1,29 @@
+use adco_shared::theme::TopicTheme;
+use chrono::{DateTime, Utc};
+use rand::seq::IndexedRandom;
+use rand::Rng;
+
+/// Picks an enabled theme with probability proportional to its weight, skipping the
+/// themes that are still cooling down. When every candidate rests, the one that
+/// becomes available first is returned so the pipeline never stalls.
+pub fn pick_theme<'a, R: Rng + ?Sized>(
+    themes: &'a [TopicTheme],
+    now: DateTime<Utc>,
+    rng: &mut R,
+) -> Option<&'a TopicTheme> {
+    let candidates: Vec<&TopicTheme> = themes
+        .iter()
+        .filter(|theme| theme.enabled && theme.weight > 0.0)
+        .collect();
+    let rested: Vec<&TopicTheme> = candidates
+        .iter()
+        .copied()
+        .filter(|theme| !theme.is_cooling_down(now))
+        .collect();
+    match rested.choose_weighted(rng, |theme| theme.weight) {
+        Ok(theme) => Some(*theme),
+        Err(_) => candidates
+            .into_iter()
+            .min_by_key(|theme| theme.available_at()),
+    }
+}
@@ -0,0 +1,7 @@
+pub mod pricing;
+pub mod repository;
+pub mod scope;
+
+pub use pricing::*;
+pub use repository::*;
+pub use scope::*;
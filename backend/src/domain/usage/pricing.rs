@@ -0,0 +1,69 @@
+/// USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+}
+
+impl ModelPrice {
+    pub const fn new(input_per_mtok: f64, output_per_mtok: f64) -> Self {
+        Self {
+            input_per_mtok,
+            output_per_mtok,
+        }
+    }
+
+    pub fn cost_usd(&self, input_tokens: u64, output_tokens: u64) -> f64 {
+        (input_tokens as f64 * self.input_per_mtok + output_tokens as f64 * self.output_per_mtok)
+            / 1_000_000.0
+    }
+}
+
+/// List prices by model name prefix; the longest matching prefix wins.
+const PRICES: &[(&str, ModelPrice)] = &[
+    ("gpt-5", ModelPrice::new(1.25, 10.0)),
+    ("gpt-5-mini", ModelPrice::new(0.25, 2.0)),
+    ("gpt-5-nano", ModelPrice::new(0.05, 0.4)),
+    ("gpt-4.1", ModelPrice::new(2.0, 8.0)),
+    ("gpt-4.1-mini", ModelPrice::new(0.4, 1.6)),
+    ("gpt-4o", ModelPrice::new(2.5, 10.0)),
+    ("gpt-4o-mini", ModelPrice::new(0.15, 0.6)),
+    ("claude-opus-4", ModelPrice::new(15.0, 75.0)),
+    ("claude-sonnet-4", ModelPrice::new(3.0, 15.0)),
+    ("claude-3-7-sonnet", ModelPrice::new(3.0, 15.0)),
+    ("claude-3-5-haiku", ModelPrice::new(0.8, 4.0)),
+    ("text-embedding-3-small", ModelPrice::new(0.02, 0.0)),
+    ("text-embedding-3-large", ModelPrice::new(0.13, 0.0)),
+];
+
+/// Price of `model`. `ADCO_LLM_PRICE_<MODEL>=<input>,<output>` (model upper-cased, other
+/// characters replaced by `_`) overrides the built-in table, e.g. for local models.
+pub fn price_for(model: &str) -> Option<ModelPrice> {
+    let env_name = format!(
+        "ADCO_LLM_PRICE_{}",
+        model
+            .to_uppercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>()
+    );
+    if let Ok(value) = std::env::var(env_name) {
+        if let Some((input, output)) = value.split_once(',') {
+            if let (Ok(input), Ok(output)) = (input.trim().parse(), output.trim().parse()) {
+                return Some(ModelPrice::new(input, output));
+            }
+        }
+    }
+    PRICES
+        .iter()
+        .filter(|(prefix, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, price)| *price)
+}
+
+/// Cost of a call in USD; unknown models cost nothing.
+pub fn cost_usd(model: &str, input_tokens: u64, output_tokens: u64) -> f64 {
+    price_for(model)
+        .map(|price| price.cost_usd(input_tokens, output_tokens))
+        .unwrap_or(0.0)
+}
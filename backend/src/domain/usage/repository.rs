@@ -0,0 +1,23 @@
+use adco_shared::usage::{CostSummary, DailyCost, LlmCall};
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub trait UsageRepository {
+    async fn save_calls(&self, calls: &[LlmCall]) -> Result<(), UsageRepositoryError>;
+    async fn get_run_calls(&self, run_id: Uuid) -> Result<Vec<LlmCall>, UsageRepositoryError>;
+    /// Totals of all runs that produced the post.
+    async fn get_post_cost(&self, post_id: Uuid) -> Result<CostSummary, UsageRepositoryError>;
+    /// Totals per UTC day in `[from, to)`.
+    async fn get_daily_costs(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<DailyCost>, UsageRepositoryError>;
+}
+
+#[derive(Error, Debug)]
+pub enum UsageRepositoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
@@ -0,0 +1,44 @@
+use adco_shared::usage::LlmCall;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+tokio::task_local! {
+    static USAGE_SCOPE: UsageScope;
+}
+
+/// Collects the calls made while a future runs, so they can be attributed
+/// to the pipeline stage that made them.
+#[derive(Debug, Clone, Default)]
+pub struct UsageScope {
+    calls: Arc<Mutex<Vec<LlmCall>>>,
+}
+
+impl UsageScope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn run<F: Future>(&self, future: F) -> F::Output {
+        USAGE_SCOPE.scope(self.clone(), future).await
+    }
+
+    pub fn take(&self) -> Vec<LlmCall> {
+        std::mem::take(&mut *self.calls.lock().unwrap())
+    }
+}
+
+/// Logs the call and adds it to the current scope, if any.
+pub fn record_call(call: LlmCall) {
+    info!(
+        "{} {} call to {}: {} in / {} out tokens, {} ms, ${:.6}",
+        call.agent,
+        call.kind,
+        call.model,
+        call.input_tokens,
+        call.output_tokens,
+        call.latency_ms,
+        call.cost_usd
+    );
+    let _ = USAGE_SCOPE.try_with(|scope| scope.calls.lock().unwrap().push(call));
+}
@@ -1,23 +1,62 @@
+mod api;
 mod appstate;
 mod domain;
 
 use appstate::{AppState, APP_STATE};
 use axum::{routing::get, Router};
+use domain::feeds::FeedPoller;
+use domain::infra::postgres::sources_repo::SourcesRepositoryImpl;
+use domain::infra::postgres::usage_repo::UsageRepositoryPostgres;
 use domain::orchestrator::Orchestrator;
+use domain::publishing::ApprovalBot;
+use domain::scheduler::{with_pipeline_lock, Scheduler, SchedulerConfig};
+use domain::sources::repository::SourceRepository;
+use domain::usage::UsageRepository;
 use tracing::info;
+use adco_shared::pipeline::{parse_topic_list, RunRequest};
+use uuid::Uuid;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
 
     AppState::initialize()?;
 
-    if APP_STATE.is_interactive_mode {
+    let args: Vec<String> = std::env::args().collect();
+    if let (Some("resume"), Some(run_id)) = (args.get(1).map(String::as_str), args.get(2)) {
+        let run_id = Uuid::parse_str(run_id)?;
+        locked_run(|| async {
+            info!("Resuming pipeline run {}", run_id);
+            Orchestrator::from_env().await?.resume(run_id).await
+        })
+        .await?;
+    } else if args.get(1).map(String::as_str) == Some("run") {
+        let requests = run_requests_from_args(&args[2..])?;
+        locked_run(|| async {
+            Orchestrator::from_env().await?.orchestrate_batch(&requests).await?;
+            Ok(())
+        })
+        .await?;
+    } else if args.get(1).map(String::as_str) == Some("feeds") {
+        poll_feeds().await?;
+    } else if args.get(1).map(String::as_str) == Some("costs") {
+        print_costs(args.get(2).map(String::as_str), args.get(3).map(String::as_str)).await?;
+    } else if args.get(1).map(String::as_str) == Some("dedup") {
+        dedup_sources().await?;
+    } else if APP_STATE.is_interactive_mode {
         info!("Interactive mode");
-        info!("Orchestrator starting");
-        let orchestrator = Orchestrator {};
-        orchestrator.orchestrate().await?;
+        locked_run(|| async {
+            info!("Orchestrator starting");
+            Orchestrator::from_env().await?.orchestrate().await?;
+            Ok(())
+        })
+        .await?;
     } else {
         info!("Non-interactive mode");
+        let pg_pool = APP_STATE.get_pg_pool().await;
+        let _scheduler = Scheduler::new(&pg_pool, SchedulerConfig::from_env()).start().await?;
+        if ApprovalBot::is_configured() {
+            tokio::spawn(ApprovalBot::from_env(&pg_pool)?.run());
+        }
         let server_handle = tokio::spawn(async {
             match start_server().await {
                 Ok(_) => info!("never reached"),
@@ -33,8 +72,113 @@ async fn main() -> anyhow::Result<()> {
     return Ok(());
 }
 
+/// Runs the pipeline from the command line under the same lock as scheduled and API runs.
+async fn locked_run<F, Fut>(run: F) -> anyhow::Result<()>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<()>>,
+{
+    let pg_pool = APP_STATE.get_pg_pool().await;
+    if with_pipeline_lock(&pg_pool, run).await?.is_none() {
+        anyhow::bail!("Another pipeline run is in progress, try again when it finishes");
+    }
+    Ok(())
+}
+
+const RUN_USAGE: &str = "Usage: run [--topic <topic>]... [--theme <theme>] [--topics-file <path>]";
+
+/// `run` without arguments writes about a random theme; every `--topic` and every line
+/// of `--topics-file` becomes a separate run, executed one after another.
+fn run_requests_from_args(args: &[String]) -> anyhow::Result<Vec<RunRequest>> {
+    let mut topics = Vec::new();
+    let mut theme = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(value) = args.next() else {
+            anyhow::bail!("{} needs a value. {}", arg, RUN_USAGE);
+        };
+        match arg.as_str() {
+            "--topic" => topics.push(value.clone()),
+            "--theme" => theme = Some(value.clone()),
+            "--topics-file" => topics.extend(parse_topic_list(&std::fs::read_to_string(value)?)),
+            _ => anyhow::bail!("Unknown argument {}. {}", arg, RUN_USAGE),
+        }
+    }
+    let requests = match (topics.is_empty(), theme) {
+        (true, theme) => vec![RunRequest { topic: None, theme }],
+        (false, None) => topics.iter().map(|topic| RunRequest::topic(topic)).collect(),
+        (false, Some(_)) => anyhow::bail!("Pass either topics or a theme, not both"),
+    };
+    for request in &requests {
+        request.validate().map_err(anyhow::Error::msg)?;
+    }
+    Ok(requests)
+}
+
+/// `feeds` - polls the feeds whose interval has passed, as the scheduler does.
+async fn poll_feeds() -> anyhow::Result<()> {
+    let pg_pool = APP_STATE.get_pg_pool().await;
+    for report in FeedPoller::new(&pg_pool).poll_due(chrono::Utc::now()).await? {
+        println!(
+            "Feed {}: {} new items, {} stored, {} failed{}",
+            report.feed_id,
+            report.new_items,
+            report.stored,
+            report.failed,
+            if report.not_modified { " (not modified)" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+/// `dedup` - canonicalizes the URLs of sources stored before deduplication and merges
+/// the duplicates, run once after `09_source_dedup.sql`.
+async fn dedup_sources() -> anyhow::Result<()> {
+    let sources_repo = SourcesRepositoryImpl::new(&*APP_STATE.get_pg_pool().await);
+    let report = sources_repo.canonicalize_sources().await?;
+    println!(
+        "Sources: {} canonical URLs updated, {} duplicates merged, {} chunks deleted",
+        report.updated, report.merged, report.deleted_chunks
+    );
+    Ok(())
+}
+
+/// `costs post <post_id>` - totals of one post, `costs daily [days]` - totals per day (default 30).
+async fn print_costs(scope: Option<&str>, arg: Option<&str>) -> anyhow::Result<()> {
+    let usage_repo = UsageRepositoryPostgres::new(&*APP_STATE.get_pg_pool().await);
+    match (scope, arg) {
+        (Some("post"), Some(post_id)) => {
+            let cost = usage_repo.get_post_cost(Uuid::parse_str(post_id)?).await?;
+            println!(
+                "Post {}: {} calls, {} in / {} out tokens, ${:.4}",
+                post_id, cost.calls, cost.input_tokens, cost.output_tokens, cost.cost_usd
+            );
+        }
+        (Some("daily"), days) => {
+            let days = days.map(str::parse::<i64>).transpose()?.unwrap_or(30);
+            let to = chrono::Utc::now();
+            let from = to - chrono::Duration::days(days);
+            for day in usage_repo.get_daily_costs(from, to).await? {
+                println!(
+                    "{}: {} calls, {} in / {} out tokens, ${:.4}",
+                    day.day,
+                    day.summary.calls,
+                    day.summary.input_tokens,
+                    day.summary.output_tokens,
+                    day.summary.cost_usd
+                );
+            }
+        }
+        _ => println!("Usage: costs post <post_id> | costs daily [days]"),
+    }
+    Ok(())
+}
+
 async fn start_server() -> anyhow::Result<()> {
-    let app = Router::new().route("/", get(|| async { "Hello, World!" }));
+    let pg_pool = APP_STATE.get_pg_pool().await;
+    let app = Router::new()
+        .route("/", get(|| async { "Hello, World!" }))
+        .merge(api::router(&pg_pool));
     let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await.unwrap();
     axum::serve(listener, app.into_make_service())
         .await?;
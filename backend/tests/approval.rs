@@ -0,0 +1,39 @@
+use adco_backend::domain::publishing::{next_publish_slot, ApprovalAction};
+use chrono::{NaiveTime, TimeZone, Utc};
+use uuid::Uuid;
+
+#[test]
+fn callback_data_round_trips_and_fits_telegram_limit() {
+    let post_id = Uuid::new_v4();
+    for action in ApprovalAction::ALL {
+        let data = action.callback_data(post_id);
+        assert!(data.len() <= 64, "{} is longer than 64 bytes", data);
+        assert_eq!(
+            ApprovalAction::parse_callback_data(&data),
+            Some((action, post_id))
+        );
+    }
+    assert_eq!(
+        ApprovalAction::parse_callback_data("publish:not-a-uuid"),
+        None
+    );
+    assert_eq!(
+        ApprovalAction::parse_callback_data(&format!("delete:{}", post_id)),
+        None
+    );
+}
+
+#[test]
+fn schedule_picks_the_next_publish_slot() {
+    let time = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+    let morning = Utc.with_ymd_and_hms(2025, 9, 1, 9, 30, 0).unwrap();
+    assert_eq!(
+        next_publish_slot(morning, time),
+        Utc.with_ymd_and_hms(2025, 9, 1, 18, 0, 0).unwrap()
+    );
+    let evening = Utc.with_ymd_and_hms(2025, 9, 1, 18, 0, 0).unwrap();
+    assert_eq!(
+        next_publish_slot(evening, time),
+        Utc.with_ymd_and_hms(2025, 9, 2, 18, 0, 0).unwrap()
+    );
+}
@@ -0,0 +1,198 @@
+use adco_backend::domain::content::{Chunk, ChunkingStrategy, TextChunker, TokenCounter};
+use adco_backend::domain::sources::html_to_markdown;
+use adco_shared::source::{Source, SourceType};
+use std::sync::Arc;
+
+/// Every word is a token, so sizes in the tests are easy to follow.
+struct WordCounter;
+
+impl TokenCounter for WordCounter {
+    fn count_tokens(&self, text: &str) -> usize {
+        text.split_whitespace().count()
+    }
+}
+
+fn chunker(chunk_size: usize, overlap_size: usize) -> TextChunker {
+    TextChunker::new(chunk_size, overlap_size).with_counter(Arc::new(WordCounter))
+}
+
+fn source(source_type: SourceType, text: &str) -> Source {
+    Source::new(
+        "https://example.com/chunking".to_string(),
+        "Chunking".to_string(),
+        source_type,
+        text.to_string(),
+    )
+}
+
+fn sentence(n: usize, words: usize) -> String {
+    let body = vec!["word"; words - 1].join(" ");
+    format!("Sentence{} {}.", n, body)
+}
+
+/// The offsets point at the text the chunk was made of.
+fn assert_offsets(source: &Source, chunks: &[Chunk]) {
+    for (index, chunk) in chunks.iter().enumerate() {
+        assert_eq!(chunk.chunk_index, index);
+        let span: String = source
+            .raw_text
+            .chars()
+            .skip(chunk.char_start)
+            .take(chunk.char_end - chunk.char_start)
+            .collect();
+        assert_eq!(
+            span.split_whitespace().collect::<Vec<_>>().join(" "),
+            chunk.text
+        );
+    }
+}
+
+#[test]
+fn strategies_are_chosen_per_source_type() {
+    let chunker = TextChunker::default();
+    assert_eq!(
+        chunker.strategy_for(&SourceType::WebPage),
+        ChunkingStrategy::Sections
+    );
+    assert_eq!(
+        chunker.strategy_for(&SourceType::PDF),
+        ChunkingStrategy::Pages
+    );
+    let chunker = chunker.with_strategy(SourceType::PDF, ChunkingStrategy::Words);
+    assert_eq!(
+        chunker.strategy_for(&SourceType::PDF),
+        ChunkingStrategy::Words
+    );
+}
+
+#[test]
+fn paragraphs_are_packed_and_long_ones_split_at_sentence_ends() {
+    let long_paragraph = (0..6).map(|n| sentence(n, 4)).collect::<Vec<_>>().join(" ");
+    let text = format!(
+        "Short intro here.\n\nSecond paragraph fits too.\n\n{}",
+        long_paragraph
+    );
+    let source = source(SourceType::API, &text);
+    let chunks = chunker(10, 4).chunk_text_from_source(&source);
+
+    assert_eq!(
+        chunks[0].text,
+        "Short intro here. Second paragraph fits too."
+    );
+    // The next chunk repeats up to 4 words from the end of the previous one
+    assert_eq!(
+        chunks[1].text,
+        "Second paragraph fits too. Sentence0 word word word."
+    );
+    for chunk in &chunks[2..] {
+        assert!(chunk.text.starts_with("Sentence"), "{}", chunk.text);
+        assert!(chunk.text.ends_with('.'), "{}", chunk.text);
+        assert!(chunk.text.split_whitespace().count() <= 10);
+    }
+    assert!(chunks[2].text.starts_with("Sentence0 "));
+    assert!(chunks
+        .last()
+        .unwrap()
+        .text
+        .ends_with("Sentence5 word word word."));
+    assert_offsets(&source, &chunks);
+}
+
+#[test]
+fn markdown_headings_start_chunks_and_name_their_section() {
+    let text = "Intro без заголовка.\n\n# Sleep\n\nWe dream in REM sleep.\n\nAnd remember less.\n\n## Memory\nConsolidation happens at night.";
+    let source = source(SourceType::WebPage, text);
+    let chunks = chunker(50, 10).chunk_text_from_source(&source);
+
+    let sections: Vec<Option<&str>> = chunks.iter().map(|c| c.section.as_deref()).collect();
+    assert_eq!(sections, [None, Some("Sleep"), Some("Memory")]);
+    assert_eq!(
+        chunks[1].text,
+        "# Sleep We dream in REM sleep. And remember less."
+    );
+    assert_eq!(chunks[2].text, "## Memory Consolidation happens at night.");
+    assert_eq!(chunks[0].char_start, 0);
+    assert_eq!(chunks[2].char_end, text.chars().count());
+    assert_offsets(&source, &chunks);
+}
+
+#[test]
+fn pdf_chunks_know_their_page_and_section() {
+    let text = "Title of the paper\nAbstract\nWe study sleep.\n\u{c}1 Introduction\nSleep is old.\n\n2.1. Data collection\nWe slept.\u{c}References\n[1] Someone, 2020.";
+    let source = source(SourceType::PDF, text);
+    let chunks = chunker(50, 10).chunk_text_from_source(&source);
+
+    let positions: Vec<(Option<usize>, Option<&str>)> = chunks
+        .iter()
+        .map(|c| (c.page, c.section.as_deref()))
+        .collect();
+    assert_eq!(
+        positions,
+        [
+            (Some(1), None),
+            (Some(1), Some("Abstract")),
+            (Some(2), Some("1 Introduction")),
+            (Some(2), Some("2.1. Data collection")),
+            (Some(3), Some("References")),
+        ]
+    );
+    assert_offsets(&source, &chunks);
+}
+
+#[test]
+fn word_windows_overlap_and_stop_at_the_end() {
+    let text = (0..25)
+        .map(|n| format!("w{}", n))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let source = source(SourceType::API, &text);
+    let chunks = chunker(10, 3)
+        .with_strategy(SourceType::API, ChunkingStrategy::Words)
+        .chunk_text_from_source(&source);
+
+    let firsts: Vec<&str> = chunks
+        .iter()
+        .map(|c| c.text.split_whitespace().next().unwrap())
+        .collect();
+    assert_eq!(firsts, ["w0", "w7", "w14", "w21"]);
+    assert!(chunks[2].text.ends_with("w22 w23"));
+    assert_eq!(chunks[3].text, "w21 w22 w23 w24");
+    assert_offsets(&source, &chunks);
+    assert!(TextChunker::default()
+        .chunk_text_from_source(&source_with_text(""))
+        .is_empty());
+}
+
+fn source_with_text(text: &str) -> Source {
+    source(SourceType::WebPage, text)
+}
+
+#[test]
+fn html_keeps_headings_paragraphs_and_list_items() {
+    let html = r#"<div><h1>Why we <em>dream</em></h1><p>Dreams happen
+        in <a href="/rem">REM</a> sleep.</p><script>track()</script>
+        <h3>Theories</h3><ul><li>Memory</li><li>Threat <b>simulation</b></li></ul></div>"#;
+    assert_eq!(
+        html_to_markdown(html),
+        "# Why we dream\n\nDreams happen in REM sleep.\n\n### Theories\n\n- Memory\n\n- Threat simulation"
+    );
+}
+
+#[test]
+fn overlapping_windows_over_non_ascii_text_keep_their_offsets() {
+    let text = (0..40)
+        .map(|n| format!("слово{}", n))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let source = source(SourceType::RSS, &text);
+    let chunks = chunker(10, 4)
+        .with_strategy(SourceType::RSS, ChunkingStrategy::Words)
+        .chunk_text_from_source(&source);
+
+    assert!(chunks.len() > 3);
+    assert!(chunks
+        .windows(2)
+        .all(|pair| pair[1].char_start < pair[0].char_end));
+    assert_eq!(chunks.last().unwrap().char_end, text.chars().count());
+    assert_offsets(&source, &chunks);
+}
@@ -0,0 +1,61 @@
+//! Helpers for the tests that run against the local Postgres (`ADCO_TEST_DATABASE_URL`).
+// Every test crate compiles this module and uses only some of it
+#![allow(dead_code)]
+
+use adco_backend::domain::content::Chunk;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub fn database_url() -> String {
+    std::env::var("ADCO_TEST_DATABASE_URL")
+        .or_else(|_| std::env::var("DATABASE_URL"))
+        .expect("ADCO_TEST_DATABASE_URL not set")
+}
+
+pub async fn test_pool() -> PgPool {
+    PgPool::connect(&database_url()).await.unwrap()
+}
+
+/// A word no other document contains, so full-text search finds only this test's chunks.
+pub fn marker() -> String {
+    format!("m{}", Uuid::new_v4().simple())
+}
+
+pub fn chunk(source_id: &str, title: &str, text: &str) -> Chunk {
+    Chunk {
+        source_id: source_id.to_string(),
+        source_url: format!("https://example.com/{}", source_id),
+        source_title: title.to_string(),
+        text: text.to_string(),
+        chunk_index: 0,
+        char_start: 0,
+        char_end: text.chars().count(),
+        section: None,
+        page: None,
+    }
+}
+
+/// Stores chunks as documents without embeddings and returns their ids.
+pub async fn insert_chunks(pg_pool: &PgPool, chunks: &[Chunk]) -> Vec<String> {
+    let mut ids = Vec::new();
+    for chunk in chunks {
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO documents (document, embedded_text) VALUES ($1, $2) RETURNING id",
+        )
+        .bind(serde_json::to_value(chunk).unwrap())
+        .bind(&chunk.text)
+        .fetch_one(pg_pool)
+        .await
+        .unwrap();
+        ids.push(id.to_string());
+    }
+    ids
+}
+
+pub async fn delete_documents(pg_pool: &PgPool, ids: &[String]) {
+    sqlx::query("DELETE FROM documents WHERE id::TEXT = ANY($1)")
+        .bind(ids)
+        .execute(pg_pool)
+        .await
+        .unwrap();
+}
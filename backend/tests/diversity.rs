@@ -0,0 +1,121 @@
+use adco_backend::domain::content::{
+    select_diverse, Chunk, DiversityConfig, EmbeddingsRepository, FakeEmbeddingModel, HybridWeights,
+};
+use uuid::Uuid;
+
+mod common;
+
+fn unit(x: f32, y: f32) -> Vec<f32> {
+    let norm = (x * x + y * y).sqrt();
+    vec![x / norm, y / norm]
+}
+
+#[test]
+fn mmr_prefers_a_different_chunk_over_a_near_duplicate() {
+    // Two near copies of the best chunk and a less relevant one about something else
+    let relevance = [1.0, 0.95, 0.6];
+    let embeddings = [unit(1.0, 0.0), unit(1.0, 0.05), unit(0.0, 1.0)];
+    let sources = ["a", "b", "c"];
+
+    let (by_relevance, _) = select_diverse(
+        &relevance,
+        &embeddings,
+        &sources,
+        2,
+        DiversityConfig::disabled(),
+    );
+    assert_eq!(by_relevance, [0, 1]);
+
+    let config = DiversityConfig {
+        mmr_lambda: 0.5,
+        max_chunks_per_source: 0,
+    };
+    let (selected, stats) = select_diverse(&relevance, &embeddings, &sources, 2, config);
+    assert_eq!(selected, [0, 2]);
+    assert_eq!(stats.mean_pairwise_similarity, 0.0);
+}
+
+#[test]
+fn sources_are_capped_and_reported() {
+    let relevance = [1.0, 0.9, 0.8, 0.7, 0.2];
+    let embeddings: Vec<Vec<f32>> = (0..5).map(|i| unit(1.0, i as f32)).collect();
+    let sources = ["pdf", "pdf", "pdf", "pdf", "blog"];
+    let config = DiversityConfig {
+        mmr_lambda: 1.0,
+        max_chunks_per_source: 2,
+    };
+
+    let (selected, stats) = select_diverse(&relevance, &embeddings, &sources, 4, config);
+    assert_eq!(selected, [0, 1, 4]);
+    assert_eq!(stats.candidates, 5);
+    assert_eq!(stats.selected, 3);
+    assert_eq!(stats.distinct_sources, 2);
+    assert_eq!(stats.max_chunks_from_one_source, 2);
+    assert_eq!(stats.skipped_by_source_limit, 2);
+
+    let (selected, stats) = select_diverse(
+        &relevance,
+        &embeddings,
+        &sources,
+        4,
+        DiversityConfig::disabled(),
+    );
+    assert_eq!(selected, [0, 1, 2, 3]);
+    assert_eq!(stats.distinct_sources, 1);
+    assert_eq!(stats.skipped_by_source_limit, 0);
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn retrieval_spreads_chunks_over_sources() {
+    let pg_pool = common::test_pool().await;
+    let marker = common::marker();
+    let long_pdf = Uuid::new_v4().to_string();
+    let article = Uuid::new_v4().to_string();
+    // The PDF mentions the term more often, so it wins every full-text rank
+    let mut documents: Vec<(String, String)> = (0..5)
+        .map(|n| {
+            (
+                long_pdf.clone(),
+                format!("{} {} page {} of the long paper", marker, marker, n),
+            )
+        })
+        .collect();
+    documents.push((article.clone(), format!("{} in a short article", marker)));
+
+    let chunks: Vec<Chunk> = documents
+        .iter()
+        .enumerate()
+        .map(|(index, (source_id, text))| Chunk {
+            chunk_index: index,
+            ..common::chunk(source_id, "Diversity", text)
+        })
+        .collect();
+    let ids = common::insert_chunks(&pg_pool, &chunks).await;
+
+    let repo = EmbeddingsRepository::with_model(&pg_pool, FakeEmbeddingModel::new(64));
+    let text_only = HybridWeights {
+        vector: 0.0,
+        ..HybridWeights::default()
+    };
+    let diversity = DiversityConfig {
+        mmr_lambda: 0.7,
+        max_chunks_per_source: 2,
+    };
+    let retrieved = repo
+        .retrieve_chunks(&marker, &marker, 3, text_only, diversity)
+        .await
+        .unwrap();
+
+    assert_eq!(retrieved.chunks.len(), 3);
+    assert!(retrieved
+        .chunks
+        .iter()
+        .any(|retrieved| retrieved.chunk.source_id == article));
+    assert_eq!(retrieved.diversity.candidates, 6);
+    assert_eq!(retrieved.diversity.distinct_sources, 2);
+    assert_eq!(retrieved.diversity.max_chunks_from_one_source, 2);
+    assert_eq!(retrieved.diversity.skipped_by_source_limit, 3);
+
+    common::delete_documents(&pg_pool, &ids).await;
+}
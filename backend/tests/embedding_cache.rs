@@ -0,0 +1,109 @@
+use adco_backend::domain::content::{
+    embedding_cache_key, embedding_cache_stats, CachedEmbeddingModel, FakeEmbeddingModel,
+    NamedEmbeddingModel,
+};
+use adco_backend::domain::usage::UsageScope;
+use rig::embeddings::{Embedding, EmbeddingError, EmbeddingModel};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+mod common;
+
+/// Fake model that counts the texts it was asked to embed.
+#[derive(Clone)]
+struct CountingModel {
+    inner: FakeEmbeddingModel,
+    embedded: Arc<AtomicUsize>,
+}
+
+impl NamedEmbeddingModel for CountingModel {
+    fn model_name(&self) -> &str {
+        "counting-embedding"
+    }
+}
+
+impl EmbeddingModel for CountingModel {
+    const MAX_DOCUMENTS: usize = 16;
+
+    fn ndims(&self) -> usize {
+        self.inner.ndims()
+    }
+
+    async fn embed_texts(
+        &self,
+        texts: impl IntoIterator<Item = String> + Send,
+    ) -> Result<Vec<Embedding>, EmbeddingError> {
+        let texts: Vec<String> = texts.into_iter().collect();
+        self.embedded.fetch_add(texts.len(), Ordering::Relaxed);
+        self.inner.embed_texts(texts).await
+    }
+}
+
+#[test]
+fn cache_key_depends_on_model_dimensions_and_text() {
+    let key = embedding_cache_key("text-embedding-3-small", 1536, "sleep");
+    assert_eq!(key.len(), 64);
+    assert_eq!(
+        key,
+        embedding_cache_key("text-embedding-3-small", 1536, "sleep")
+    );
+    assert_ne!(
+        key,
+        embedding_cache_key("text-embedding-3-large", 1536, "sleep")
+    );
+    assert_ne!(
+        key,
+        embedding_cache_key("text-embedding-3-small", 512, "sleep")
+    );
+    assert_ne!(
+        key,
+        embedding_cache_key("text-embedding-3-small", 1536, "sleep ")
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn known_texts_are_not_embedded_again() {
+    let pg_pool = common::test_pool().await;
+    let embedded = Arc::new(AtomicUsize::new(0));
+    let model = CachedEmbeddingModel::new(
+        &pg_pool,
+        CountingModel {
+            inner: FakeEmbeddingModel::new(64),
+            embedded: embedded.clone(),
+        },
+    );
+    let marker = Uuid::new_v4();
+    let first = format!("memory consolidation {}", marker);
+    let second = format!("hawking radiation {}", marker);
+    let before = embedding_cache_stats();
+
+    let scope = UsageScope::new();
+    let vectors = scope
+        .run(model.embed_texts(vec![first.clone(), first.clone()]))
+        .await
+        .unwrap();
+    assert_eq!(embedded.load(Ordering::Relaxed), 1);
+    assert_eq!(vectors[0].vec, vectors[1].vec);
+    assert_eq!(vectors[0].document, first);
+
+    let vectors_again = scope
+        .run(model.embed_texts(vec![second.clone(), first.clone()]))
+        .await
+        .unwrap();
+    assert_eq!(embedded.load(Ordering::Relaxed), 2);
+    assert_eq!(vectors_again[1].vec, vectors[0].vec);
+    assert_eq!(vectors_again[0].document, second);
+
+    // Only the two calls that reached the model are paid for
+    assert_eq!(scope.take().len(), 2);
+    let after = embedding_cache_stats();
+    assert!(after.hits - before.hits >= 2);
+    assert!(after.misses - before.misses >= 2);
+
+    sqlx::query("DELETE FROM embedding_cache WHERE model = 'counting-embedding'")
+        .execute(&pg_pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,218 @@
+use adco_backend::api;
+use adco_backend::domain::content::FakeEmbeddingModel;
+use adco_backend::domain::feeds::{parse_feed, FeedFetch, FeedFetcher, FeedPoller, FeedRepository};
+use adco_backend::domain::infra::postgres::{
+    feeds_repo::FeedRepositoryPostgres, sources_repo::SourcesRepositoryImpl,
+};
+use adco_backend::domain::sources::SourceRepository;
+use adco_shared::feed::Feed;
+use adco_shared::source::SourceType;
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, HeaderMap, Method, Request, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+mod common;
+
+const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>Quanta Magazine</title>
+    <link>https://www.quantamagazine.org</link>
+    <item>
+      <title>Why Do We Dream?</title>
+      <link>https://example.org/dreams</link>
+      <guid isPermaLink="false">dreams-1</guid>
+      <pubDate>Tue, 14 Oct 2025 09:30:00 GMT</pubDate>
+      <description><![CDATA[<p>Sleep <b>replays</b> the day.</p>]]></description>
+      <content:encoded><![CDATA[<p>Full text about <i>dreams</i>.</p><p>Second paragraph.</p>]]></content:encoded>
+    </item>
+    <item>
+      <title>No guid</title>
+      <link>https://example.org/no-guid</link>
+      <description>Plain &amp; simple</description>
+    </item>
+    <item>
+      <title>No link</title>
+    </item>
+  </channel>
+</rss>"#;
+
+const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="text">arXiv q-bio.NC</title>
+  <entry>
+    <id>urn:uuid:1225c695</id>
+    <title type="html">Memory &lt;em&gt;consolidation&lt;/em&gt;</title>
+    <link rel="self" href="https://example.org/self"/>
+    <link href="https://example.org/memory"/>
+    <updated>2025-10-01T12:00:00Z</updated>
+    <summary>Replay during sleep.</summary>
+  </entry>
+</feed>"#;
+
+/// Serves `RSS` with an ETag and answers 304 when the client already has it.
+async fn serve_feed() -> String {
+    async fn feed(headers: HeaderMap) -> impl IntoResponse {
+        if headers
+            .get(header::IF_NONE_MATCH)
+            .and_then(|v| v.to_str().ok())
+            == Some("\"v1\"")
+        {
+            return StatusCode::NOT_MODIFIED.into_response();
+        }
+        (
+            [
+                (header::ETAG, "\"v1\""),
+                (header::LAST_MODIFIED, "Tue, 14 Oct 2025 09:30:00 GMT"),
+            ],
+            RSS,
+        )
+            .into_response()
+    }
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, Router::new().route("/feed.xml", get(feed)))
+            .await
+            .unwrap();
+    });
+    format!("http://{}/feed.xml", address)
+}
+
+#[test]
+fn rss_items_are_parsed_with_fallbacks() {
+    let feed = parse_feed(RSS).unwrap();
+    assert_eq!(feed.title.as_deref(), Some("Quanta Magazine"));
+    assert_eq!(feed.items.len(), 2);
+
+    let dreams = &feed.items[0];
+    assert_eq!(dreams.guid, "dreams-1");
+    assert_eq!(dreams.url, "https://example.org/dreams");
+    assert_eq!(
+        dreams.published_at.unwrap().to_rfc3339(),
+        "2025-10-14T09:30:00+00:00"
+    );
+    assert_eq!(
+        dreams.summary.as_deref(),
+        Some("Full text about dreams. Second paragraph.")
+    );
+
+    let no_guid = &feed.items[1];
+    assert_eq!(no_guid.guid, "https://example.org/no-guid");
+    assert_eq!(no_guid.summary.as_deref(), Some("Plain & simple"));
+    assert_eq!(no_guid.published_at, None);
+}
+
+#[test]
+fn atom_entries_use_the_alternate_link() {
+    let feed = parse_feed(ATOM).unwrap();
+    assert_eq!(feed.title.as_deref(), Some("arXiv q-bio.NC"));
+    let entry = &feed.items[0];
+    assert_eq!(entry.guid, "urn:uuid:1225c695");
+    assert_eq!(entry.url, "https://example.org/memory");
+    assert_eq!(entry.title.as_deref(), Some("Memory consolidation"));
+    assert_eq!(entry.summary.as_deref(), Some("Replay during sleep."));
+    assert!(entry.published_at.is_some());
+
+    assert!(parse_feed("<html><body>not a feed</body></html>").is_err());
+}
+
+#[test]
+fn feeds_are_due_after_their_interval() {
+    let now = Utc::now();
+    let mut feed = Feed::new("https://example.org/feed.xml");
+    assert!(feed.is_due(now));
+    feed.last_polled_at = Some(now - Duration::minutes(30));
+    assert!(!feed.is_due(now));
+    feed.last_polled_at = Some(now - Duration::minutes(60));
+    assert!(feed.is_due(now));
+    feed.enabled = false;
+    assert!(!feed.is_due(now));
+}
+
+#[tokio::test]
+async fn fetcher_sends_validators_back_and_gets_not_modified() {
+    let mut feed = Feed::new(&serve_feed().await);
+    let fetcher = FeedFetcher::new();
+
+    let FeedFetch::Fetched {
+        body,
+        etag,
+        last_modified,
+    } = fetcher.fetch(&feed).await.unwrap()
+    else {
+        panic!("first fetch must return the feed");
+    };
+    assert!(body.contains("Why Do We Dream?"));
+    assert_eq!(etag.as_deref(), Some("\"v1\""));
+    assert!(last_modified.is_some());
+
+    feed.etag = etag;
+    assert_eq!(fetcher.fetch(&feed).await.unwrap(), FeedFetch::NotModified);
+}
+
+#[tokio::test]
+async fn invalid_feeds_are_rejected_before_touching_the_database() {
+    let app = api::router(&PgPool::connect_lazy("postgres://postgres@localhost/adco").unwrap());
+    for body in [
+        json!({"url": "not a url"}),
+        json!({"url": "https://example.org/feed.xml", "poll_interval_minutes": 0}),
+    ] {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/api/feeds")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    }
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn poller_stores_new_items_once() {
+    let pg_pool = common::test_pool().await;
+    let feed_repo = FeedRepositoryPostgres::new(&pg_pool);
+    let mut feed = Feed::new(&serve_feed().await);
+    feed.id = Some(feed_repo.create_feed(&feed).await.unwrap());
+    let poller = FeedPoller::with_model(&pg_pool, FakeEmbeddingModel::new(1536));
+
+    // The example.org pages can't be scraped, the text from the feed is stored instead
+    let report = poller.poll_feed(&mut feed).await.unwrap();
+    assert_eq!((report.new_items, report.stored, report.failed), (2, 2, 0));
+    let stored = feed_repo
+        .get_feed_by_id(feed.id.unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.title.as_deref(), Some("Quanta Magazine"));
+    assert_eq!(stored.etag.as_deref(), Some("\"v1\""));
+    assert!(stored.last_polled_at.is_some());
+
+    let report = poller.poll_feed(&mut feed).await.unwrap();
+    assert!(report.not_modified);
+    feed.etag = None;
+    feed.last_modified = None;
+    let report = poller.poll_feed(&mut feed).await.unwrap();
+    assert_eq!((report.new_items, report.stored), (0, 0));
+
+    let sources_repo = SourcesRepositoryImpl::new(&pg_pool);
+    let sources = sources_repo.list_sources(100, 0).await.unwrap();
+    let dreams = sources
+        .iter()
+        .find(|s| s.url == "https://example.org/dreams")
+        .unwrap();
+    assert_eq!(dreams.source_type, SourceType::RSS);
+    assert!(dreams.chunk_count > 0);
+    feed_repo.delete_feed(feed.id.unwrap()).await.unwrap();
+}
@@ -0,0 +1,97 @@
+use adco_backend::domain::publishing::{sanitize_telegram_html, SanitizerChange};
+
+#[test]
+fn valid_telegram_html_is_left_alone() {
+    let post = "Powered by ADCO\n\n<b>Сон</b> и <i>память</i> &amp; &lt;код&gt; &#128512;\n\n<a href=\"https://example.org\">link</a>";
+    let sanitized = sanitize_telegram_html(post);
+    assert_eq!(sanitized.html, post);
+    assert!(!sanitized.is_changed());
+}
+
+#[test]
+fn unsupported_tags_are_converted_or_stripped() {
+    let sanitized = sanitize_telegram_html(
+        "<h2>Title</h2><p>First <strong>bold</strong><br>line</p><p><span style=\"x\">Second</span></p><ul><li>one</li><li>two</li></ul>",
+    );
+    assert_eq!(
+        sanitized.html,
+        "<b>Title</b>\nFirst <b>bold</b>\nline\n\nSecond\n\n• one\n• two"
+    );
+    assert!(sanitized.changes.contains(&SanitizerChange::ConvertedTag {
+        from: "strong".to_string(),
+        to: "b".to_string()
+    }));
+    assert!(sanitized
+        .changes
+        .contains(&SanitizerChange::StrippedTag("br".to_string())));
+    assert!(sanitized
+        .changes
+        .contains(&SanitizerChange::StrippedTag("span".to_string())));
+}
+
+#[test]
+fn stray_characters_and_unknown_entities_are_escaped() {
+    let sanitized = sanitize_telegram_html("R&D: a < b > c&nbsp;d &foo; <3");
+    assert_eq!(
+        sanitized.html,
+        "R&amp;D: a &lt; b &gt; c\u{a0}d &amp;foo; &lt;3"
+    );
+    assert!(sanitized
+        .changes
+        .contains(&SanitizerChange::EscapedCharacter('&')));
+    assert!(sanitized
+        .changes
+        .contains(&SanitizerChange::EscapedCharacter('<')));
+    assert!(sanitized
+        .changes
+        .contains(&SanitizerChange::ReplacedEntity("nbsp".to_string())));
+}
+
+#[test]
+fn tags_are_balanced() {
+    let sanitized = sanitize_telegram_html("<b>bold <i>both</b> italic</i> </u>tail <b>open");
+    assert_eq!(
+        sanitized.html,
+        "<b>bold <i>both</i></b><i> italic</i> tail <b>open</b>"
+    );
+    assert!(sanitized
+        .changes
+        .contains(&SanitizerChange::ReorderedTag("i".to_string())));
+    assert!(sanitized
+        .changes
+        .contains(&SanitizerChange::DroppedClosingTag("u".to_string())));
+    assert!(sanitized
+        .changes
+        .contains(&SanitizerChange::ClosedTag("b".to_string())));
+}
+
+#[test]
+fn links_keep_only_href() {
+    let sanitized = sanitize_telegram_html(
+        "<a class=\"x\" href='https://example.org/?q=\"1\"' target=_blank>ok</a> <a name=\"n\">anchor</a>",
+    );
+    assert_eq!(
+        sanitized.html,
+        "<a href=\"https://example.org/?q=&quot;1&quot;\">ok</a> anchor"
+    );
+}
+
+#[test]
+fn link_href_is_escaped_and_matched_by_whole_name() {
+    let sanitized = sanitize_telegram_html(
+        "<a data-href=\"https://evil.example\" title='href=x' HREF='https://example.org/?a=1&b=\"2\"&amp;c&nbsp;'>ok</a>",
+    );
+    assert_eq!(
+        sanitized.html,
+        "<a href=\"https://example.org/?a=1&amp;b=&quot;2&quot;&amp;c\u{a0}\">ok</a>"
+    );
+    assert!(sanitized
+        .changes
+        .contains(&SanitizerChange::EscapedCharacter('&')));
+    assert!(sanitized
+        .changes
+        .contains(&SanitizerChange::ReplacedEntity("nbsp".to_string())));
+
+    let sanitized = sanitize_telegram_html("<a data-href=\"https://evil.example\">no link</a>");
+    assert_eq!(sanitized.html, "no link");
+}
@@ -0,0 +1,159 @@
+use adco_backend::domain::content::{
+    full_text_query, reciprocal_rank_fusion, Chunk, EmbeddingsRepository, FakeEmbeddingModel,
+    FullTextQuery, HybridWeights, SourceFilter, RRF_K,
+};
+use uuid::Uuid;
+
+mod common;
+
+#[test]
+fn fusion_rewards_items_ranked_by_both_lists() {
+    let fused = reciprocal_rank_fusion(&[(1.0, vec!["a", "b", "c"]), (1.0, vec!["c", "d", "b"])]);
+    let order: Vec<&str> = fused.iter().map(|(item, _)| *item).collect();
+    // "c" is 3rd and 1st, "b" 2nd and 3rd: both beat the items found once
+    assert_eq!(order, ["c", "b", "a", "d"]);
+    assert!((fused[0].1 - (1.0 / (RRF_K + 3.0) + 1.0 / (RRF_K + 1.0))).abs() < 1e-12);
+    // On a tie the item seen first goes first
+    let tied = reciprocal_rank_fusion(&[(1.0, vec!["x"]), (1.0, vec!["y"])]);
+    assert_eq!(tied[0], ("x", 1.0 / (RRF_K + 1.0)));
+    assert_eq!(tied[1].1, tied[0].1);
+}
+
+#[test]
+fn fusion_weights_shift_the_order_and_zero_drops_a_list() {
+    let rankings = |vector: f64, text: f64| {
+        reciprocal_rank_fusion(&[(vector, vec!["semantic"]), (text, vec!["exact"])])
+            .into_iter()
+            .map(|(item, _)| item)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(rankings(1.0, 2.0), ["exact", "semantic"]);
+    assert_eq!(rankings(2.0, 1.0), ["semantic", "exact"]);
+    assert_eq!(rankings(1.0, 0.0), ["semantic"]);
+    assert_eq!(HybridWeights::vector_only().text, 0.0);
+}
+
+#[test]
+fn full_text_query_requires_identifiers_and_any_word() {
+    assert_eq!(
+        full_text_query("What's new in GPT-4o (arXiv:2401.12345)?").unwrap(),
+        FullTextQuery {
+            words: Some("'what' | 's' | 'new' | 'in' | 'arxiv'".to_string()),
+            identifiers: Some("'gpt-4o' & '2401.12345'".to_string()),
+        }
+    );
+    assert_eq!(
+        full_text_query("RAG, rag & 'RAG'").unwrap(),
+        FullTextQuery {
+            words: Some("'rag'".to_string()),
+            identifiers: None,
+        }
+    );
+    assert!(full_text_query(" ?! -- ").is_none());
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn full_text_search_finds_exact_terms() {
+    let pg_pool = common::test_pool().await;
+    let marker = common::marker();
+    let texts = [
+        format!(
+            "{} is mentioned once among many other words about sleep",
+            marker
+        ),
+        format!("{} {} twice, in a short chunk", marker, marker),
+        "Nothing to see here".to_string(),
+    ];
+    let chunks: Vec<Chunk> = texts
+        .iter()
+        .map(|text| common::chunk(&Uuid::new_v4().to_string(), "Hybrid", text))
+        .collect();
+    let ids = common::insert_chunks(&pg_pool, &chunks).await;
+
+    let repo = EmbeddingsRepository::with_model(&pg_pool, FakeEmbeddingModel::new(64));
+    let found = repo
+        .full_text_search(
+            &format!("where is {}?", marker),
+            10,
+            &SourceFilter::default(),
+        )
+        .await
+        .unwrap();
+    let found_ids: Vec<&str> = found.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(found_ids, [ids[1].as_str(), ids[0].as_str()]);
+    assert_eq!(found[0].1.text, texts[1]);
+
+    let text_only = HybridWeights {
+        vector: 0.0,
+        ..HybridWeights::default()
+    };
+    let results = repo
+        .search_chunks(&marker, 1, text_only, &SourceFilter::default())
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].1, ids[1]);
+    assert!((results[0].0 - 1.0 / (RRF_K + 1.0)).abs() < 1e-12);
+
+    common::delete_documents(&pg_pool, &ids).await;
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn full_text_search_stems_words_and_skips_stop_words() {
+    let pg_pool = common::test_pool().await;
+    let marker = common::marker();
+    let source_id = Uuid::new_v4().to_string();
+    let texts = [
+        format!("{} the dreaming brain", marker),
+        format!("{} sleepers dream", marker),
+    ];
+    let chunks: Vec<Chunk> = texts
+        .iter()
+        .map(|text| common::chunk(&source_id, "Stemming", text))
+        .collect();
+    let ids = common::insert_chunks(&pg_pool, &chunks).await;
+    let repo = EmbeddingsRepository::with_model(&pg_pool, FakeEmbeddingModel::new(64));
+    let found_ids = |found: Vec<(String, Chunk)>| {
+        let mut found: Vec<String> = found
+            .into_iter()
+            .map(|(id, _)| id)
+            .filter(|id| ids.contains(id))
+            .collect();
+        found.sort();
+        found
+    };
+    let mut both = ids.clone();
+    both.sort();
+
+    // "dreams" is stemmed, "the" matches nothing on its own
+    let found = repo
+        .full_text_search("the dreams", 1000, &SourceFilter::default())
+        .await
+        .unwrap();
+    assert_eq!(found_ids(found), both);
+    let found = repo
+        .full_text_search("the", 1000, &SourceFilter::default())
+        .await
+        .unwrap();
+    assert!(found_ids(found).is_empty());
+
+    // The identifier is required, the words only rank
+    let found = repo
+        .full_text_search(&format!("{} brain", marker), 10, &SourceFilter::default())
+        .await
+        .unwrap();
+    assert_eq!(found[0].0, ids[0]);
+    assert_eq!(found_ids(found), both);
+
+    // Chunks without a source row do not pass a source filter
+    let filter = SourceFilter {
+        source_id: Some(Uuid::new_v4()),
+        ..SourceFilter::default()
+    };
+    let found = repo.full_text_search(&marker, 10, &filter).await.unwrap();
+    assert!(found.is_empty());
+
+    common::delete_documents(&pg_pool, &ids).await;
+}
@@ -0,0 +1,68 @@
+use adco_backend::domain::publishing::{split_html_message, visible_len, TELEGRAM_MESSAGE_LIMIT};
+
+#[test]
+fn visible_length_ignores_tags_and_counts_entities_once() {
+    assert_eq!(visible_len("<b>Hi</b> &amp; <i>bye</i>"), 8);
+    assert_eq!(visible_len("Привет"), 6);
+    // Telegram counts UTF-16 code units, emoji outside the BMP take two
+    assert_eq!(visible_len("🧠"), 2);
+}
+
+#[test]
+fn short_posts_are_sent_as_is() {
+    let post = "Powered by ADCO\n\n<b>Short</b> post";
+    assert_eq!(
+        split_html_message(post, TELEGRAM_MESSAGE_LIMIT),
+        vec![post.to_string()]
+    );
+}
+
+#[test]
+fn long_posts_are_split_on_paragraphs() {
+    let paragraph = "word ".repeat(300).trim_end().to_string();
+    let post = vec![paragraph.clone(); 3].join("\n\n");
+    let messages = split_html_message(&post, TELEGRAM_MESSAGE_LIMIT);
+    assert_eq!(messages.len(), 2);
+    assert!(messages
+        .iter()
+        .all(|m| visible_len(m) <= TELEGRAM_MESSAGE_LIMIT));
+    assert!(messages[0].ends_with(&paragraph));
+    assert!(messages[1].starts_with(&paragraph));
+}
+
+#[test]
+fn tags_open_at_a_cut_are_closed_and_reopened() {
+    let post = "<b>one two <i>three four</i> five six</b>";
+    let messages = split_html_message(post, 10);
+    assert!(messages.len() > 1);
+    for message in &messages {
+        assert!(visible_len(message) <= 10, "{} is too long", message);
+        assert_eq!(
+            message.matches("<b>").count(),
+            message.matches("</b>").count()
+        );
+        assert_eq!(
+            message.matches("<i>").count(),
+            message.matches("</i>").count()
+        );
+    }
+    assert!(messages[0].starts_with("<b>"));
+    assert!(messages[1].starts_with("<b>"));
+    let text: Vec<String> = messages
+        .iter()
+        .map(|m| {
+            m.replace("<b>", "")
+                .replace("</b>", "")
+                .replace("<i>", "")
+                .replace("</i>", "")
+        })
+        .collect();
+    assert_eq!(text.join(" "), "one two three four five six");
+}
+
+#[test]
+fn huge_words_are_cut_outside_of_entities() {
+    let post = "&amp;".repeat(15);
+    let messages = split_html_message(&post, 10);
+    assert_eq!(messages, vec!["&amp;".repeat(10), "&amp;".repeat(5)]);
+}
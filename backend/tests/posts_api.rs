@@ -0,0 +1,195 @@
+use adco_backend::api;
+use adco_backend::domain::infra::postgres::posts_repo::PostsRepositoryPostgres;
+use adco_backend::domain::posts::{self, PostRepository, PostRepositoryError, PostTransitionError};
+use adco_shared::post::{Post, PostStatus};
+use axum::{
+    body::{to_bytes, Body},
+    http::{Method, Request, StatusCode},
+    Router,
+};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod common;
+
+fn draft() -> Post {
+    Post::new(
+        "Topic".to_string(),
+        "draft".to_string(),
+        "text".to_string(),
+        String::new(),
+    )
+}
+
+async fn call(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    let body = body.map(|b| Body::from(b.to_string())).unwrap_or_default();
+    let response = app
+        .clone()
+        .oneshot(request.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[test]
+fn lifecycle_allows_only_forward_transitions_and_back_to_draft() {
+    let now = Utc::now();
+    let mut post = draft();
+
+    let error = posts::schedule(&mut post, now - Duration::minutes(1), now).unwrap_err();
+    assert!(matches!(error, PostTransitionError::ScheduledInPast(_)));
+    assert_eq!(post.status, PostStatus::Draft);
+
+    posts::schedule(&mut post, now + Duration::hours(1), now).unwrap();
+    assert_eq!(post.status, PostStatus::Scheduled);
+    assert!(post.scheduled_at.is_some());
+    assert!(posts::reject(&mut post).is_err());
+
+    posts::unschedule(&mut post).unwrap();
+    assert_eq!(post.status, PostStatus::Draft);
+    assert_eq!(post.scheduled_at, None);
+
+    posts::reject(&mut post).unwrap();
+    assert!(posts::check_transition(&post, PostStatus::Published).is_err());
+
+    post.status = PostStatus::Publishing;
+    assert!(matches!(
+        posts::check_editable(&post),
+        Err(PostTransitionError::BeingPublished)
+    ));
+    post.status = PostStatus::Published;
+    assert!(matches!(
+        posts::check_editable(&post),
+        Err(PostTransitionError::AlreadyPublished)
+    ));
+    assert!(posts::unschedule(&mut post).is_err());
+}
+
+#[tokio::test]
+async fn invalid_requests_are_rejected_before_touching_the_database() {
+    let app = api::router(&PgPool::connect_lazy("postgres://postgres@localhost/adco").unwrap());
+
+    let (status, body) = call(&app, Method::GET, "/api/posts?status=archived", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Unknown post status 'archived'");
+
+    let (status, _) = call(&app, Method::GET, "/api/posts/not-a-uuid", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn posts_api_crud_and_transitions() {
+    let pg_pool = common::test_pool().await;
+    let app = api::router(&pg_pool);
+
+    let (status, post) = call(
+        &app,
+        Method::POST,
+        "/api/posts",
+        Some(json!({"topic": "API test", "post_text": "<b>hello</b>"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let uri = format!("/api/posts/{}", post["id"].as_str().unwrap());
+
+    let (status, page) = call(&app, Method::GET, "/api/posts?status=draft&limit=500", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["limit"], 100);
+    assert_eq!(page["items"][0]["id"], post["id"]);
+
+    let (status, post) = call(
+        &app,
+        Method::PATCH,
+        &uri,
+        Some(json!({"post_text": "edited"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(post["post_text"], "edited");
+    assert_eq!(post["topic"], "API test");
+    assert_eq!(post["html_changes"], json!([]));
+
+    let (status, post) = call(
+        &app,
+        Method::PATCH,
+        &uri,
+        Some(json!({"post_text": "<strong>edited</strong> <span>again</span>"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(post["post_text"], "<b>edited</b> again");
+    assert_eq!(
+        post["html_changes"],
+        json!(["converted <strong> to <b>", "stripped unsupported <span>"])
+    );
+
+    let past = json!({"scheduled_at": Utc::now() - Duration::hours(1)});
+    let (status, _) = call(&app, Method::POST, &format!("{}/schedule", uri), Some(past)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let future = json!({"scheduled_at": Utc::now() + Duration::hours(1)});
+    let (status, post) = call(
+        &app,
+        Method::POST,
+        &format!("{}/schedule", uri),
+        Some(future),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(post["status"], "Scheduled");
+
+    let (status, _) = call(&app, Method::POST, &format!("{}/reject", uri), None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, post) = call(&app, Method::POST, &format!("{}/unschedule", uri), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(post["status"], "Draft");
+
+    // Claimed by a publisher meanwhile: neither edits nor transitions go through
+    let id = Uuid::parse_str(post["id"].as_str().unwrap()).unwrap();
+    let repo = PostsRepositoryPostgres::new(&pg_pool);
+    let loaded = repo.get_post_by_id(id).await.unwrap().unwrap();
+    repo.transition_status(id, PostStatus::Draft, PostStatus::Publishing)
+        .await
+        .unwrap();
+    assert!(matches!(
+        repo.update_post_if_status(&loaded, PostStatus::Draft).await,
+        Err(PostRepositoryError::StatusChanged { .. })
+    ));
+    let (status, _) = call(&app, Method::PATCH, &uri, Some(json!({"topic": "late"}))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = call(&app, Method::POST, &format!("{}/reject", uri), None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = call(&app, Method::POST, &format!("{}/publish", uri), None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = call(&app, Method::DELETE, &uri, None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(matches!(
+        repo.delete_post_if_status(id, PostStatus::Draft).await,
+        Err(PostRepositoryError::StatusChanged { .. })
+    ));
+
+    repo.transition_status(id, PostStatus::Publishing, PostStatus::Draft)
+        .await
+        .unwrap();
+    let (status, _) = call(&app, Method::DELETE, &uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, body) = call(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body["error"].as_str().unwrap().contains("not found"));
+    let (status, _) = call(&app, Method::DELETE, &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
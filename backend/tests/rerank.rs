@@ -0,0 +1,122 @@
+use adco_backend::domain::content::{
+    relative_scores, Chunk, DiversityConfig, EmbeddingsRepository, FakeEmbeddingModel,
+    HybridWeights, NoopReranker, RerankFuture, Reranker, RetrievedChunk,
+};
+use adco_backend::domain::llm::agents::LlmReranker;
+use adco_backend::domain::llm::{AgentFixtures, FixtureLlmProvider};
+use std::sync::Arc;
+use uuid::Uuid;
+
+mod common;
+
+fn chunk(text: &str) -> Chunk {
+    common::chunk(&Uuid::new_v4().to_string(), "Rerank", text)
+}
+
+fn candidates(scores: &[f64]) -> Vec<RetrievedChunk> {
+    scores
+        .iter()
+        .enumerate()
+        .map(|(index, score)| RetrievedChunk {
+            id: index.to_string(),
+            score: *score,
+            rerank_score: None,
+            chunk: chunk(&format!("passage {}", index)),
+        })
+        .collect()
+}
+
+fn llm_reranker(response: &str) -> LlmReranker {
+    let fixtures = AgentFixtures {
+        default: Some(response.to_string()),
+        ..Default::default()
+    };
+    LlmReranker::new(Arc::new(FixtureLlmProvider::new(
+        LlmReranker::NAME,
+        fixtures,
+    )))
+}
+
+#[tokio::test]
+async fn noop_keeps_the_search_order() {
+    let candidates = candidates(&[0.032, 0.016, 0.008]);
+    let scores = NoopReranker.rerank("sleep", &candidates).await.unwrap();
+    assert_eq!(scores, [1.0, 0.5, 0.25]);
+    assert_eq!(relative_scores(&[]), Vec::<f64>::new());
+}
+
+#[tokio::test]
+async fn llm_scores_are_mapped_to_passages_of_each_batch() {
+    let reranker = llm_reranker(
+        r#"```json
+{"scores": [{"passage": 2, "score": 9}, {"passage": 1, "score": 3}]}
+```"#,
+    )
+    .with_batch_size(2);
+    let candidates = candidates(&[0.03, 0.02, 0.01]);
+    let scores = reranker.rerank("sleep", &candidates).await.unwrap();
+    // The fixture answers every batch the same; the last batch has no passage 2
+    assert_eq!(scores, [0.3, 0.9, 0.3]);
+}
+
+#[tokio::test]
+async fn out_of_range_llm_scores_are_an_error() {
+    let reranker = llm_reranker(r#"{"scores": [{"passage": 1, "score": 12}]}"#);
+    let error = reranker
+        .rerank("sleep", &candidates(&[0.03]))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("expected 0 to 10"), "{}", error);
+}
+
+/// Prefers whatever search ranked last.
+struct ReverseReranker;
+
+impl Reranker for ReverseReranker {
+    fn name(&self) -> &str {
+        "reverse"
+    }
+
+    fn rerank<'a>(&'a self, _topic: &'a str, candidates: &'a [RetrievedChunk]) -> RerankFuture<'a> {
+        Box::pin(async move {
+            let count = candidates.len() as f64;
+            Ok((0..candidates.len())
+                .map(|index| (index + 1) as f64 / count)
+                .collect())
+        })
+    }
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn retrieval_keeps_search_and_rerank_scores() {
+    let pg_pool = common::test_pool().await;
+    let marker = common::marker();
+    let texts = [
+        format!("{} {} {} three times", marker, marker, marker),
+        format!("{} {} twice", marker, marker),
+        format!("{} once", marker),
+    ];
+    let chunks: Vec<Chunk> = texts.iter().map(|text| chunk(text)).collect();
+    let ids = common::insert_chunks(&pg_pool, &chunks).await;
+
+    let repo = EmbeddingsRepository::with_model(&pg_pool, FakeEmbeddingModel::new(64))
+        .with_reranker(Arc::new(ReverseReranker));
+    let text_only = HybridWeights {
+        vector: 0.0,
+        ..HybridWeights::default()
+    };
+    let retrieved = repo
+        .retrieve_chunks(&marker, "sleep", 2, text_only, DiversityConfig::disabled())
+        .await
+        .unwrap();
+
+    assert_eq!(retrieved.reranker, "reverse");
+    let order: Vec<&str> = retrieved.chunks.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(order, [ids[2].as_str(), ids[1].as_str()]);
+    // Search preferred the other way round, both scores are kept
+    assert!(retrieved.chunks[0].score < retrieved.chunks[1].score);
+    assert_eq!(retrieved.chunks[0].rerank_score, Some(1.0));
+
+    common::delete_documents(&pg_pool, &ids).await;
+}
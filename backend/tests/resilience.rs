@@ -0,0 +1,191 @@
+use adco_backend::domain::infra::http_client::ResilientHttpClient;
+use adco_backend::domain::infra::resilience::{
+    parse_retry_after, retry_decision_for_status, with_retry, RetryDecision, RetryPolicy,
+    TimeoutError,
+};
+use axum::body::{Body, Bytes};
+use axum::{routing::get, Router};
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug)]
+enum TestError {
+    Transient,
+    Fatal,
+    Timeout,
+}
+
+impl std::fmt::Display for TestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<TimeoutError> for TestError {
+    fn from(_: TimeoutError) -> Self {
+        TestError::Timeout
+    }
+}
+
+fn classify(error: &TestError) -> RetryDecision {
+    match error {
+        TestError::Transient | TestError::Timeout => RetryDecision::Retry { after: None },
+        TestError::Fatal => RetryDecision::Fail,
+    }
+}
+
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_retries: 3,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(5),
+        attempt_timeout: Duration::from_millis(50),
+        total_timeout: Duration::from_secs(5),
+    }
+}
+
+#[test]
+fn retry_after_accepts_seconds_and_http_dates() {
+    assert_eq!(parse_retry_after("20"), Some(Duration::from_secs(20)));
+    assert_eq!(
+        parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+        Some(Duration::ZERO)
+    );
+    assert_eq!(parse_retry_after("soon"), None);
+
+    assert_eq!(
+        retry_decision_for_status(429, Some(Duration::from_secs(3))),
+        RetryDecision::Retry {
+            after: Some(Duration::from_secs(3))
+        }
+    );
+    assert_eq!(
+        retry_decision_for_status(503, None),
+        RetryDecision::Retry { after: None }
+    );
+    assert_eq!(retry_decision_for_status(404, None), RetryDecision::Fail);
+}
+
+#[tokio::test]
+async fn transient_errors_are_retried_until_success() {
+    let calls = AtomicU32::new(0);
+    let result = with_retry(&fast_policy(), "test", classify, || async {
+        match calls.fetch_add(1, Ordering::SeqCst) {
+            0 | 1 => Err(TestError::Transient),
+            _ => Ok("done"),
+        }
+    })
+    .await;
+    assert_eq!(result.unwrap(), "done");
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn fatal_errors_and_exhausted_retries_fail() {
+    let calls = AtomicU32::new(0);
+    let result: Result<(), _> = with_retry(&fast_policy(), "test", classify, || async {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Err(TestError::Fatal)
+    })
+    .await;
+    assert!(matches!(result, Err(TestError::Fatal)));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let calls = AtomicU32::new(0);
+    let result: Result<(), _> = with_retry(&fast_policy(), "test", classify, || async {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Err(TestError::Transient)
+    })
+    .await;
+    assert!(matches!(result, Err(TestError::Transient)));
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn retry_after_is_honored_within_the_total_timeout() {
+    let calls = AtomicU32::new(0);
+    let started = std::time::Instant::now();
+    let rate_limited = |_: &TestError| RetryDecision::Retry {
+        after: Some(Duration::from_millis(100)),
+    };
+    let result = with_retry(&fast_policy(), "test", rate_limited, || async {
+        match calls.fetch_add(1, Ordering::SeqCst) {
+            0 => Err(TestError::Transient),
+            _ => Ok("done"),
+        }
+    })
+    .await;
+    assert_eq!(result.unwrap(), "done");
+    // Longer than max_delay: the server's delay is not cut short
+    assert!(started.elapsed() >= Duration::from_millis(100));
+
+    let calls = AtomicU32::new(0);
+    let started = std::time::Instant::now();
+    let too_late = |_: &TestError| RetryDecision::Retry {
+        after: Some(Duration::from_secs(60)),
+    };
+    let result: Result<(), _> = with_retry(&fast_policy(), "test", too_late, || async {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Err(TestError::Transient)
+    })
+    .await;
+    assert!(matches!(result, Err(TestError::Transient)));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn slow_attempts_time_out() {
+    let policy = RetryPolicy {
+        max_retries: 0,
+        ..fast_policy()
+    };
+    let result: Result<(), _> = with_retry(&policy, "test", classify, || async {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        Ok(())
+    })
+    .await;
+    assert!(matches!(result, Err(TestError::Timeout)));
+}
+
+#[tokio::test]
+async fn a_stalled_body_times_out_and_is_fetched_again() {
+    // The first response sends its headers and stalls in the middle of the body
+    let calls = Arc::new(AtomicU32::new(0));
+    let served = calls.clone();
+    let handler = move || {
+        let call = served.fetch_add(1, Ordering::SeqCst);
+        async move {
+            let (sender, receiver) = tokio::sync::mpsc::channel::<Result<Bytes, Infallible>>(2);
+            tokio::spawn(async move {
+                sender.send(Ok(Bytes::from("part one, "))).await.ok();
+                if call == 0 {
+                    tokio::time::sleep(Duration::from_secs(2)).await;
+                }
+                sender.send(Ok(Bytes::from("part two"))).await.ok();
+            });
+            Body::from_stream(tokio_stream::wrappers::ReceiverStream::new(receiver))
+        }
+    };
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, Router::new().route("/slow", get(handler)))
+            .await
+            .unwrap();
+    });
+
+    let policy = RetryPolicy {
+        attempt_timeout: Duration::from_millis(300),
+        ..fast_policy()
+    };
+    let response = ResilientHttpClient::with_policy(policy)
+        .get(&format!("http://{}/slow", address))
+        .await
+        .unwrap();
+    assert_eq!(response.status, 200);
+    assert_eq!(response.text(), "part one, part two");
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
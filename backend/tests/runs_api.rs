@@ -0,0 +1,283 @@
+use adco_backend::api;
+use adco_backend::domain::infra::postgres::{
+    pipeline_repo::PipelineRepositoryPostgres, usage_repo::UsageRepositoryPostgres,
+};
+use adco_backend::domain::pipeline::{events, PipelineRepository, PipelineTracker};
+use adco_shared::pipeline::{
+    parse_topic_list, PipelineEvent, PipelineRun, PipelineRunStatus, PipelineStageKind,
+    RunRequest,
+};
+use axum::{
+    body::{to_bytes, Body},
+    http::{Method, Request, StatusCode},
+    Router,
+};
+use serde_json::json;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use tracing_subscriber::layer::SubscriberExt;
+use uuid::Uuid;
+
+mod common;
+
+async fn call(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    body: Option<serde_json::Value>,
+) -> (StatusCode, String) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    let body = body.map(|b| Body::from(b.to_string())).unwrap_or_default();
+    let response = app
+        .clone()
+        .oneshot(request.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(bytes.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn run_events_reach_subscribers_until_closed() {
+    let run_id = Uuid::new_v4();
+    assert!(events::subscribe(run_id).is_none());
+
+    events::open(run_id);
+    let mut receiver = events::subscribe(run_id).unwrap();
+    let started = PipelineEvent::StageStarted {
+        stage: PipelineStageKind::Research,
+        attempt: 1,
+    };
+    events::publish(run_id, started.clone());
+    events::close(run_id);
+    events::publish(
+        run_id,
+        PipelineEvent::StageRestored {
+            stage: PipelineStageKind::Research,
+        },
+    );
+
+    assert_eq!(receiver.recv().await.unwrap(), started);
+    assert!(receiver.recv().await.is_err());
+    assert!(events::subscribe(run_id).is_none());
+}
+
+#[tokio::test]
+async fn log_lines_inside_a_run_scope_become_events() {
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(events::RunLogLayer));
+    let run_id = Uuid::new_v4();
+    events::open(run_id);
+    let mut receiver = events::subscribe(run_id).unwrap();
+
+    tracing::info!("outside of the run");
+    events::scope(run_id, async {
+        tracing::warn!(chunks = 3, "Retrieved chunks");
+    })
+    .await;
+    events::close(run_id);
+
+    assert_eq!(
+        receiver.recv().await.unwrap(),
+        PipelineEvent::Log {
+            level: "WARN".to_string(),
+            message: "Retrieved chunks chunks=3".to_string()
+        }
+    );
+    assert!(receiver.recv().await.is_err());
+}
+
+#[tokio::test]
+async fn run_request_needs_either_topic_or_theme() {
+    let app = api::router(&PgPool::connect_lazy("postgres://postgres@localhost/adco").unwrap());
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        "/api/runs",
+        Some(json!({"topic": "Why do we dream?", "theme": "sleep"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("either a topic or a theme"));
+
+    let (status, _) = call(&app, Method::POST, "/api/runs", Some(json!({"theme": " "}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn run_requests_are_validated_and_topic_lists_parsed() {
+    assert!(RunRequest::default().validate().is_ok());
+    assert_eq!(
+        RunRequest::topic("  Why do we dream? ").topic.as_deref(),
+        Some("Why do we dream?")
+    );
+    assert!(RunRequest::theme("").validate().is_err());
+    let both = RunRequest {
+        topic: Some("a".to_string()),
+        theme: Some("b".to_string()),
+    };
+    assert!(both.validate().is_err());
+
+    let topics = parse_topic_list("# sleep\nWhy do we dream?\n\n  How do we forget?  \n");
+    assert_eq!(topics, ["Why do we dream?", "How do we forget?"]);
+}
+
+#[tokio::test]
+async fn batch_needs_one_to_twenty_valid_topics() {
+    let app = api::router(&PgPool::connect_lazy("postgres://postgres@localhost/adco").unwrap());
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/api/runs/batch",
+        Some(json!({"topics": []})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let topics: Vec<String> = (0..21).map(|i| format!("topic {}", i)).collect();
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/api/runs/batch",
+        Some(json!({ "topics": topics })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        "/api/runs/batch",
+        Some(json!({"topics": ["ok", " "]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("must not be empty"));
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn tracker_publishes_stage_transitions() {
+    let pg_pool = common::test_pool().await;
+    let mut tracker = PipelineTracker::start(
+        PipelineRepositoryPostgres::new(&pg_pool),
+        UsageRepositoryPostgres::new(&pg_pool),
+    )
+    .await
+    .unwrap();
+    let mut receiver = events::subscribe(tracker.run_id()).unwrap();
+    let repository = PipelineRepositoryPostgres::new(&pg_pool);
+    let status = |run: Option<PipelineRun>| run.unwrap().status;
+    assert_eq!(
+        status(repository.get_run_by_id(tracker.run_id()).await.unwrap()),
+        PipelineRunStatus::Queued
+    );
+
+    tracker
+        .stage(PipelineStageKind::Research, &(), || async {
+            Ok("research".to_string())
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        status(repository.get_run_by_id(tracker.run_id()).await.unwrap()),
+        PipelineRunStatus::Running
+    );
+    tracker.complete().await.unwrap();
+
+    let mut received = Vec::new();
+    while let Ok(event) = receiver.recv().await {
+        received.push(event.name());
+    }
+    assert_eq!(
+        received,
+        ["stage_started", "stage_completed", "run_finished"]
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn finished_run_streams_snapshot_and_end() {
+    let pg_pool = common::test_pool().await;
+    let mut run = PipelineRun::new();
+    run.status = PipelineRunStatus::Failed;
+    run.error = Some("research: boom".to_string());
+    let run_id = PipelineRepositoryPostgres::new(&pg_pool)
+        .create_run(&run)
+        .await
+        .unwrap();
+    let app = api::router(&pg_pool);
+
+    let (status, body) = call(&app, Method::GET, &format!("/api/runs/{}", run_id), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let details: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(details["run"]["error"], "research: boom");
+
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        &format!("/api/runs/{}/events", run_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.starts_with("event: run\n"));
+    assert!(body.contains("event: run_finished\n"));
+    assert!(body.contains(r#""status":"Failed""#));
+
+    let (status, _) = call(
+        &app,
+        Method::GET,
+        &format!("/api/runs/{}/events", Uuid::new_v4()),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn errors_between_stages_fail_the_run() {
+    let pg_pool = common::test_pool().await;
+    let mut tracker = PipelineTracker::start(
+        PipelineRepositoryPostgres::new(&pg_pool),
+        UsageRepositoryPostgres::new(&pg_pool),
+    )
+    .await
+    .unwrap();
+    let run_id = tracker.run_id();
+    let mut receiver = events::subscribe(run_id).unwrap();
+
+    tracker
+        .stage(PipelineStageKind::Research, &(), || async {
+            Ok("research".to_string())
+        })
+        .await
+        .unwrap();
+    tracker.fail(&anyhow::anyhow!("post not found")).await;
+    // Already failed, nothing more is published
+    tracker.fail(&anyhow::anyhow!("again")).await;
+
+    let mut received = Vec::new();
+    while let Ok(event) = receiver.recv().await {
+        received.push(event);
+    }
+    assert_eq!(received.len(), 3);
+    assert!(matches!(
+        received.last(),
+        Some(PipelineEvent::RunFinished { status: PipelineRunStatus::Failed, error: Some(error), .. })
+            if error == "post not found"
+    ));
+    let run = PipelineRepositoryPostgres::new(&pg_pool)
+        .get_run_by_id(run_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(run.status, PipelineRunStatus::Failed);
+    assert_eq!(run.error.as_deref(), Some("post not found"));
+    assert!(run.finished_at.is_some());
+}
@@ -0,0 +1,108 @@
+use adco_backend::domain::{
+    infra::postgres::{advisory_lock::AdvisoryLock, posts_repo::PostsRepositoryPostgres},
+    posts::{PostRepository, PostRepositoryError},
+    scheduler::{Scheduler, SchedulerConfig},
+};
+use adco_shared::post::{Post, PostStatus};
+use sqlx::PgPool;
+
+mod common;
+
+fn lazy_pool() -> PgPool {
+    PgPool::connect_lazy("postgres://postgres@localhost/adco").unwrap()
+}
+
+#[tokio::test]
+async fn scheduler_accepts_default_crons_and_rejects_invalid_ones() {
+    let mut scheduler = Scheduler::new(&lazy_pool(), SchedulerConfig::from_env())
+        .start()
+        .await
+        .unwrap();
+    scheduler.shutdown().await.unwrap();
+
+    let config = SchedulerConfig {
+        pipeline_cron: Some("every morning".to_string()),
+        publish_cron: None,
+        feeds_cron: None,
+    };
+    assert!(Scheduler::new(&lazy_pool(), config).start().await.is_err());
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn advisory_lock_is_exclusive_until_released() {
+    let pg_pool = common::test_pool().await;
+    let key = 0x4144_434F_FFFF;
+
+    let lock = AdvisoryLock::try_acquire(&pg_pool, key)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(AdvisoryLock::try_acquire(&pg_pool, key)
+        .await
+        .unwrap()
+        .is_none());
+    lock.release().await.unwrap();
+
+    let lock = AdvisoryLock::try_acquire(&pg_pool, key)
+        .await
+        .unwrap()
+        .unwrap();
+    drop(lock);
+    let lock = AdvisoryLock::try_acquire(&pg_pool, key).await.unwrap();
+    assert!(lock.is_some());
+    lock.unwrap().release().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn scheduled_posts_are_claimed_once_and_unscheduled_after_repeated_failures() {
+    let repo = PostsRepositoryPostgres::new(&common::test_pool().await);
+    let mut post = Post::new(
+        "Claim test".to_string(),
+        String::new(),
+        "text".to_string(),
+        String::new(),
+    );
+    post.status = PostStatus::Scheduled;
+    post.scheduled_at = Some(chrono::Utc::now());
+    let id = repo.create_post(&post).await.unwrap();
+
+    let claim = || repo.transition_status(id, PostStatus::Scheduled, PostStatus::Publishing);
+    claim().await.unwrap();
+    assert!(matches!(
+        claim().await,
+        Err(PostRepositoryError::StatusChanged { .. })
+    ));
+
+    let failed = repo
+        .record_publish_failure(id, "Bad Gateway", PostStatus::Scheduled, 2)
+        .await
+        .unwrap();
+    assert_eq!(failed.status, PostStatus::Scheduled);
+    assert_eq!(failed.publish_attempts, 1);
+    assert_eq!(failed.publish_error.as_deref(), Some("Bad Gateway"));
+    assert!(failed.scheduled_at.is_some());
+
+    claim().await.unwrap();
+    let failed = repo
+        .record_publish_failure(id, "Bad Gateway", PostStatus::Scheduled, 2)
+        .await
+        .unwrap();
+    assert_eq!(failed.status, PostStatus::Draft);
+    assert_eq!(failed.publish_attempts, 2);
+    assert_eq!(failed.scheduled_at, None);
+
+    repo.transition_status(id, PostStatus::Draft, PostStatus::Publishing)
+        .await
+        .unwrap();
+    repo.mark_published(id, &[10, 11], chrono::Utc::now())
+        .await
+        .unwrap();
+    let published = repo.get_post_by_id(id).await.unwrap().unwrap();
+    assert_eq!(published.status, PostStatus::Published);
+    assert_eq!(published.telegram_message_ids, [10, 11]);
+    assert_eq!(published.publish_error, None);
+
+    repo.delete_post(id).await.unwrap();
+}
@@ -0,0 +1,151 @@
+use adco_backend::api;
+use adco_backend::domain::content::{EmbeddingsRepository, FakeEmbeddingModel};
+use adco_backend::domain::infra::postgres::sources_repo::SourcesRepositoryImpl;
+use adco_backend::domain::sources::SourceRepository;
+use adco_shared::search::SearchQuery;
+use adco_shared::source::{Source, SourceType};
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use serde_json::Value;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod common;
+
+async fn get(uri: &str) -> (StatusCode, Value) {
+    let app = api::router(&PgPool::connect_lazy("postgres://postgres@localhost/adco").unwrap());
+    let response = app
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[test]
+fn search_top_k_defaults_and_is_capped() {
+    let mut query = SearchQuery::new("rust");
+    assert_eq!(query.top_k(), 10);
+    query.top_k = Some(1000);
+    assert_eq!(query.top_k(), 50);
+    query.top_k = Some(0);
+    assert_eq!(query.top_k(), 1);
+}
+
+#[tokio::test]
+async fn search_rejects_invalid_queries() {
+    let (status, body) = get("/api/search?query=%20").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Query must not be empty");
+
+    let (status, _) = get("/api/search?query=rust&source_type=Podcast").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = get(
+        "/api/search?query=rust&fetched_after=2025-02-01T00:00:00Z&fetched_before=2025-01-01T00:00:00Z",
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body["error"],
+        "fetched_after must not be later than fetched_before"
+    );
+
+    let (status, body) = get("/api/search?query=rust&text_weight=-1").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "text_weight must be a non-negative number");
+
+    let (status, body) = get("/api/search?query=rust&vector_weight=0&text_weight=0").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body["error"],
+        "vector_weight and text_weight must not both be 0"
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres with pgvector (ADCO_TEST_DATABASE_URL)"]
+async fn search_filters_by_source() {
+    let pg_pool = common::test_pool().await;
+    let sources_repo = SourcesRepositoryImpl::new(&pg_pool);
+    let repo = EmbeddingsRepository::with_model(&pg_pool, FakeEmbeddingModel::new(1536));
+
+    let mut ids = Vec::new();
+    for (title, source_type) in [("Paper", SourceType::PDF), ("Article", SourceType::WebPage)] {
+        let mut source = Source::new(
+            format!("https://example.com/{}", title),
+            title.to_string(),
+            source_type,
+            "borrow checker lifetimes".to_string(),
+        );
+        source.id = Some(sources_repo.create_source(&source).await.unwrap());
+        repo.index_source(&source).await.unwrap();
+        ids.push(source.id.unwrap());
+    }
+
+    let mut query = SearchQuery::new("borrow checker lifetimes");
+    query.source_type = Some(SourceType::PDF);
+    let results = repo.search(&query).await.unwrap();
+    assert!(!results.is_empty());
+    assert!(results
+        .iter()
+        .all(|r| r.source_type == Some(SourceType::PDF)));
+    assert_eq!(results[0].source_title, "Paper");
+    assert!(results[0].score > 0.0);
+
+    let mut query = SearchQuery::new("borrow checker lifetimes");
+    query.source_id = Some(ids[1]);
+    let results = repo.search(&query).await.unwrap();
+    assert!(results.iter().all(|r| r.source_id == Some(ids[1])));
+
+    for id in ids {
+        sources_repo.delete_source(id).await.unwrap();
+    }
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn full_text_search_keeps_the_source_filters() {
+    let pg_pool = common::test_pool().await;
+    let sources_repo = SourcesRepositoryImpl::new(&pg_pool);
+    let repo = EmbeddingsRepository::with_model(&pg_pool, FakeEmbeddingModel::new(64));
+    let marker = format!("m{}", Uuid::new_v4().simple());
+
+    let mut ids = Vec::new();
+    for (title, source_type) in [("Paper", SourceType::PDF), ("Article", SourceType::WebPage)] {
+        let mut source = Source::new(
+            format!("https://example.com/{}/{}", title, marker),
+            title.to_string(),
+            source_type,
+            format!("{} borrow checker lifetimes", marker),
+        );
+        source.id = Some(sources_repo.create_source(&source).await.unwrap());
+        repo.index_source(&source).await.unwrap();
+        ids.push(source.id.unwrap());
+    }
+
+    let mut query = SearchQuery::new(&format!("{} lifetimes", marker));
+    query.vector_weight = Some(0.0);
+    let results = repo.search(&query).await.unwrap();
+    assert_eq!(results.len(), 2);
+
+    query.source_type = Some(SourceType::WebPage);
+    let results = repo.search(&query).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].source_id, Some(ids[1]));
+    assert_eq!(results[0].source_title, "Article");
+    assert_eq!(results[0].source_type, Some(SourceType::WebPage));
+    assert!(results[0].fetched_at.is_some());
+    assert!(results[0].score > 0.0);
+
+    for id in ids {
+        sources_repo.delete_source(id).await.unwrap();
+    }
+}
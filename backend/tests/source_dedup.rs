@@ -0,0 +1,203 @@
+use adco_backend::domain::infra::postgres::sources_repo::SourcesRepositoryImpl;
+use adco_backend::domain::sources::{canonical_url, content_hash, SourceRepository, SourceUpsert};
+use adco_shared::source::{Source, SourceType};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+#[test]
+fn tracking_params_fragments_and_scheme_do_not_change_the_canonical_url() {
+    let canonical = "https://example.com/post?id=7&page=2";
+    for url in [
+        "https://example.com/post?id=7&page=2",
+        "http://www.example.com/post/?page=2&id=7",
+        "https://example.com/post?utm_source=x&id=7&utm_medium=rss&page=2&fbclid=abc#comments",
+        "  https://EXAMPLE.com/post?page=2&gclid=1&id=7  ",
+    ] {
+        assert_eq!(canonical_url(url), canonical, "{}", url);
+    }
+    assert_eq!(canonical_url("https://example.com/"), "https://example.com");
+    assert_eq!(
+        canonical_url("http://localhost:8080/a"),
+        "https://localhost:8080/a"
+    );
+    assert_eq!(canonical_url("not a url"), "not a url");
+}
+
+#[test]
+fn every_arxiv_link_to_a_paper_has_one_canonical_url() {
+    for url in [
+        "https://arxiv.org/abs/2101.00001",
+        "https://arxiv.org/abs/2101.00001v3",
+        "http://arxiv.org/pdf/2101.00001v1.pdf",
+        "https://arxiv.org/pdf/2101.00001",
+        "https://export.arxiv.org/abs/2101.00001v2?context=cs",
+        "https://www.arxiv.org/abs/2101.00001/",
+    ] {
+        assert_eq!(
+            canonical_url(url),
+            "https://arxiv.org/abs/2101.00001",
+            "{}",
+            url
+        );
+    }
+    assert_eq!(
+        canonical_url("https://arxiv.org/abs/math/0601001v2"),
+        "https://arxiv.org/abs/math/0601001"
+    );
+    assert_eq!(
+        canonical_url("https://arxiv.org/list/cs.AI/recent"),
+        "https://arxiv.org/list/cs.AI/recent"
+    );
+}
+
+#[test]
+fn content_hash_is_hex_sha256() {
+    assert_eq!(
+        content_hash(""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_ne!(content_hash("one"), content_hash("one "));
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn known_sources_are_reused_and_changed_ones_lose_their_chunks() {
+    let pg_pool = common::test_pool().await;
+    let repo = SourcesRepositoryImpl::new(&pg_pool);
+    let marker = Uuid::new_v4();
+    let source = |url: String, text: &str| {
+        Source::new(
+            url,
+            "Dedup".to_string(),
+            SourceType::WebPage,
+            format!("{} {}", text, marker),
+        )
+    };
+    let url = format!("https://example.com/dedup/{}", marker);
+
+    let created = repo
+        .upsert_source(&source(url.clone(), "first"))
+        .await
+        .unwrap();
+    assert!(matches!(created, SourceUpsert::Created(_)));
+    let id = created.id();
+    sqlx::query("INSERT INTO documents (document, embedded_text) VALUES ($1, 'first')")
+        .bind(json!({"source_id": id.to_string(), "text": "first"}))
+        .execute(&pg_pool)
+        .await
+        .unwrap();
+
+    let tracked = format!(
+        "http://www.example.com/dedup/{}/?utm_source=rss#top",
+        marker
+    );
+    let unchanged = repo.upsert_source(&source(tracked, "first")).await.unwrap();
+    assert_eq!(unchanged, SourceUpsert::Unchanged(id));
+    assert!(!unchanged.needs_indexing());
+    let mirror = format!("https://mirror.example.org/{}", marker);
+    assert_eq!(
+        repo.upsert_source(&source(mirror, "first")).await.unwrap(),
+        SourceUpsert::Unchanged(id)
+    );
+    assert_eq!(repo.get_source_chunks(id).await.unwrap().len(), 1);
+
+    let updated = repo.upsert_source(&source(url.clone(), "second")).await.unwrap();
+    assert_eq!(updated, SourceUpsert::Updated(id));
+    assert!(updated.needs_indexing());
+    assert!(repo.get_source_chunks(id).await.unwrap().is_empty());
+    let stored = repo.get_source_by_id(id).await.unwrap().unwrap();
+    assert!(stored.raw_text.starts_with("second"));
+
+    // Indexing of the new text failed: the same text again is not taken as indexed
+    assert_eq!(
+        repo.upsert_source(&source(url, "second")).await.unwrap(),
+        SourceUpsert::Updated(id)
+    );
+
+    repo.delete_source(id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn rows_stored_before_dedup_are_merged_by_canonical_url() {
+    let pg_pool = common::test_pool().await;
+    let repo = SourcesRepositoryImpl::new(&pg_pool);
+    let paper = format!("2101.{:05}", Uuid::new_v4().as_u128() % 100_000);
+    // As 09_source_dedup.sql left them: raw URLs, the older row without chunks
+    let mut ids = Vec::new();
+    for (url, age) in [
+        (format!("https://arxiv.org/pdf/{}v2.pdf", paper), 2),
+        (format!("http://arxiv.org/abs/{}", paper), 1),
+    ] {
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO sources (url, title, source_type, fetched_at, raw_text, canonical_url)
+            VALUES ($1, 'Old', 'Paper', NOW() - make_interval(days => $2), 'old', $1)
+            RETURNING id",
+        )
+        .bind(&url)
+        .bind(age)
+        .fetch_one(&pg_pool)
+        .await
+        .unwrap();
+        ids.push(id);
+    }
+    for id in [ids[1], ids[1], ids[0]] {
+        sqlx::query("INSERT INTO documents (document, embedded_text) VALUES ($1, 'old')")
+            .bind(json!({"source_id": id.to_string(), "text": "old"}))
+            .execute(&pg_pool)
+            .await
+            .unwrap();
+    }
+    // The older row has a chunk too, so it is kept
+    let report = repo.canonicalize_sources().await.unwrap();
+    assert!(report.merged >= 1);
+    assert!(report.deleted_chunks >= 2);
+
+    assert!(repo.get_source_by_id(ids[1]).await.unwrap().is_none());
+    assert!(repo.get_source_chunks(ids[1]).await.unwrap().is_empty());
+    assert_eq!(repo.get_source_chunks(ids[0]).await.unwrap().len(), 1);
+    let canonical: Option<String> =
+        sqlx::query_scalar("SELECT canonical_url FROM sources WHERE id = $1")
+            .bind(ids[0])
+            .fetch_one(&pg_pool)
+            .await
+            .unwrap();
+    assert_eq!(canonical, Some(format!("https://arxiv.org/abs/{}", paper)));
+    assert_eq!(
+        repo.canonicalize_sources().await.unwrap(),
+        Default::default()
+    );
+
+    repo.delete_source(ids[0]).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn concurrent_upserts_of_a_new_url_share_one_row() {
+    let pg_pool = common::test_pool().await;
+    let repo = SourcesRepositoryImpl::new(&pg_pool);
+    let marker = Uuid::new_v4();
+    let url = format!("https://example.com/race/{}", marker);
+    let tasks: Vec<_> = (0..8)
+        .map(|n| {
+            let repo = SourcesRepositoryImpl::new(&pg_pool);
+            let source = Source::new(
+                url.clone(),
+                "Race".to_string(),
+                SourceType::WebPage,
+                format!("text {} {}", n, marker),
+            );
+            tokio::spawn(async move { repo.upsert_source(&source).await })
+        })
+        .collect();
+
+    let mut ids = Vec::new();
+    for task in tasks {
+        ids.push(task.await.unwrap().unwrap().id());
+    }
+    assert!(ids.iter().all(|id| *id == ids[0]), "{:?}", ids);
+
+    repo.delete_source(ids[0]).await.unwrap();
+}
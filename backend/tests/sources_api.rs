@@ -0,0 +1,184 @@
+use adco_backend::api;
+use adco_backend::domain::content::{Chunk, EmbeddedChunk};
+use adco_backend::domain::infra::postgres::sources_repo::SourcesRepositoryImpl;
+use adco_backend::domain::sources::SourceRepository;
+use adco_shared::source::{Source, SourceType};
+use axum::{
+    body::{to_bytes, Body},
+    http::{Method, Request, StatusCode},
+    Router,
+};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod common;
+
+async fn call(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    let body = body.map(|b| Body::from(b.to_string())).unwrap_or_default();
+    let response = app
+        .clone()
+        .oneshot(request.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[test]
+fn source_type_reads_back_what_is_stored() {
+    for source_type in [
+        SourceType::RSS,
+        SourceType::WebPage,
+        SourceType::API,
+        SourceType::PDF,
+    ] {
+        assert_eq!(SourceType::from(source_type.to_string()), source_type);
+    }
+    assert_eq!(
+        SourceType::from("web_page".to_string()),
+        SourceType::WebPage
+    );
+}
+
+#[tokio::test]
+async fn adding_a_source_requires_a_valid_url() {
+    let app = api::router(&PgPool::connect_lazy("postgres://postgres@localhost/adco").unwrap());
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        "/api/sources",
+        Some(json!({"url": "not a url"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Invalid URL 'not a url'");
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn sources_api_lists_chunks_and_deletes_them_with_the_source() {
+    let pg_pool = common::test_pool().await;
+    let app = api::router(&pg_pool);
+
+    // A fresh URL per run, so a run that failed halfway does not break the next one
+    let source = Source::new(
+        format!("https://example.com/sources-api/{}", Uuid::new_v4()),
+        "Sources API".to_string(),
+        SourceType::WebPage,
+        "one two three".to_string(),
+    );
+    let id = SourcesRepositoryImpl::new(&pg_pool)
+        .create_source(&source)
+        .await
+        .unwrap();
+    for text in ["one two", "two three"] {
+        sqlx::query("INSERT INTO documents (document, embedded_text) VALUES ($1, $2)")
+            .bind(json!({"source_id": id.to_string(), "text": text}))
+            .bind(text)
+            .execute(&pg_pool)
+            .await
+            .unwrap();
+    }
+    let uri = format!("/api/sources/{}", id);
+
+    let (status, page) = call(&app, Method::GET, "/api/sources?limit=100", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let summary = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["id"] == id.to_string())
+        .unwrap();
+    assert_eq!(summary["chunk_count"], 2);
+    assert_eq!(summary["text_length"], 13);
+    assert_eq!(summary["source_type"], "WebPage");
+
+    let (status, body) = call(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["raw_text"], "one two three");
+
+    let (status, chunks) = call(&app, Method::GET, &format!("{}/chunks", uri), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(chunks.as_array().unwrap().len(), 2);
+
+    let (status, _) = call(&app, Method::DELETE, &uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let documents: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM documents WHERE document->>'source_id' = $1")
+            .bind(id.to_string())
+            .fetch_one(&pg_pool)
+            .await
+            .unwrap();
+    assert_eq!(documents, 0);
+
+    let (status, _) = call(&app, Method::GET, &format!("{}/chunks", uri), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = call(&app, Method::DELETE, &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+fn embedded(source: &Source, text: &str) -> EmbeddedChunk {
+    EmbeddedChunk {
+        chunk: Chunk {
+            source_id: source.id.unwrap().to_string(),
+            source_url: source.url.clone(),
+            source_title: source.title.clone(),
+            text: text.to_string(),
+            chunk_index: 0,
+            char_start: 0,
+            char_end: text.chars().count(),
+            section: None,
+            page: None,
+        },
+        embedding: vec![0.5; 1536],
+    }
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn replacing_chunks_saves_the_text_and_the_chunks_together() {
+    let pg_pool = common::test_pool().await;
+    let repo = SourcesRepositoryImpl::new(&pg_pool);
+
+    let mut source = Source::new(
+        format!("https://example.com/replace-chunks/{}", Uuid::new_v4()),
+        "Replace chunks".to_string(),
+        SourceType::WebPage,
+        "old text".to_string(),
+    );
+    source.id = Some(repo.create_source(&source).await.unwrap());
+    repo.replace_source_chunks(&source, &[embedded(&source, "old text")])
+        .await
+        .unwrap();
+
+    source.raw_text = "new text, longer".to_string();
+    let chunks = [embedded(&source, "new text"), embedded(&source, "longer")];
+    let deleted = repo.replace_source_chunks(&source, &chunks).await.unwrap();
+    assert_eq!(deleted, 1);
+    let stored = repo
+        .get_source_by_id(source.id.unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.raw_text, "new text, longer");
+    let texts: Vec<String> = sqlx::query_scalar(
+        "SELECT embedded_text FROM documents WHERE document->>'source_id' = $1 ORDER BY embedded_text",
+    )
+    .bind(source.id.unwrap().to_string())
+    .fetch_all(&pg_pool)
+    .await
+    .unwrap();
+    assert_eq!(texts, ["longer", "new text"]);
+
+    repo.delete_source(source.id.unwrap()).await.unwrap();
+}
@@ -0,0 +1,198 @@
+use adco_backend::domain::content::{
+    SimilarTopic, TopicHistory, TopicHistoryFuture, TopicNoveltyConfig,
+};
+use adco_backend::domain::llm::{
+    agents::{TopicGenerator, TopicGeneratorResult},
+    complete_structured, extract_json, LlmFuture, LlmProvider, LlmRequest, LlmResponse, LlmUsage,
+    StructuredOutputError,
+};
+use adco_shared::theme::TopicTheme;
+use std::sync::{Arc, Mutex};
+
+/// Returns queued responses in order and remembers the prompts it got.
+struct ScriptedProvider {
+    responses: Mutex<Vec<String>>,
+    prompts: Mutex<Vec<String>>,
+}
+
+impl ScriptedProvider {
+    fn new(responses: &[&str]) -> Self {
+        Self {
+            responses: Mutex::new(responses.iter().rev().map(|r| r.to_string()).collect()),
+            prompts: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl LlmProvider for ScriptedProvider {
+    fn model(&self) -> &str {
+        "scripted"
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a> {
+        self.prompts.lock().unwrap().push(request.prompt.clone());
+        let content = self.responses.lock().unwrap().pop().unwrap_or_default();
+        Box::pin(async move {
+            Ok(LlmResponse {
+                content,
+                model: "scripted".to_string(),
+                usage: LlmUsage::default(),
+            })
+        })
+    }
+}
+
+/// One past post: "What are dreams for?", close only to topics about dreaming.
+struct DreamHistory;
+
+impl TopicHistory for DreamHistory {
+    fn nearest_topics<'a>(&'a self, text: &'a str, limit: usize) -> TopicHistoryFuture<'a> {
+        let similarity = if text.contains("dream") { 0.95 } else { 0.2 };
+        let past = SimilarTopic {
+            post_id: uuid::Uuid::nil(),
+            topic: "What are dreams for?".to_string(),
+            similarity,
+        };
+        Box::pin(async move { Ok(vec![past].into_iter().take(limit).collect()) })
+    }
+}
+
+const TOPIC_JSON: &str = r#"{"topic": "Why do we dream?", "full_search_query": "dreams rem sleep", "short_search_query": "dreams"}"#;
+
+#[test]
+fn extract_json_strips_fences_and_trailing_text() {
+    let fenced = format!("```json\n{}\n```\nHope this helps!", TOPIC_JSON);
+    assert_eq!(extract_json(&fenced), Some(TOPIC_JSON));
+
+    let with_braces_in_strings = r#"Sure: {"a": "} not the end {", "b": [1, 2]} done"#;
+    assert_eq!(
+        extract_json(with_braces_in_strings),
+        Some(r#"{"a": "} not the end {", "b": [1, 2]}"#)
+    );
+
+    assert_eq!(extract_json("no json here"), None);
+    assert_eq!(extract_json(r#"{"unterminated": true"#), None);
+}
+
+#[tokio::test]
+async fn structured_output_parses_fenced_response() {
+    let provider = ScriptedProvider::new(&[&format!("```json\n{}\n```", TOPIC_JSON)]);
+    let request = LlmRequest::new("topic_generator", "preamble", "prompt".to_string());
+    let result = complete_structured::<TopicGeneratorResult>(&provider, &request, 0)
+        .await
+        .unwrap();
+    assert_eq!(result.topic, "Why do we dream?");
+}
+
+#[tokio::test]
+async fn structured_output_sends_parse_error_back_for_repair() {
+    let provider = ScriptedProvider::new(&[
+        r#"{"topic": "Why do we dream?"}"#,
+        r#"{"topic": "", "full_search_query": "x", "short_search_query": "y"}"#,
+        TOPIC_JSON,
+    ]);
+    let request = LlmRequest::new("topic_generator", "preamble", "prompt".to_string());
+    let result = complete_structured::<TopicGeneratorResult>(&provider, &request, 2)
+        .await
+        .unwrap();
+    assert_eq!(result.short_search_query, "dreams");
+
+    let prompts = provider.prompts.lock().unwrap();
+    assert_eq!(prompts.len(), 3);
+    assert!(prompts[1].contains("missing field `full_search_query`"));
+    assert!(prompts[2].contains("topic is empty"));
+}
+
+#[tokio::test]
+async fn structured_output_gives_up_after_max_repairs() {
+    let provider = ScriptedProvider::new(&["not json", "still not json"]);
+    let request = LlmRequest::new("topic_generator", "preamble", "prompt".to_string());
+    let error = complete_structured::<TopicGeneratorResult>(&provider, &request, 1)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        StructuredOutputError::Invalid { attempts: 2, .. }
+    ));
+}
+
+#[tokio::test]
+async fn explicit_topic_is_kept_and_only_queries_are_generated() {
+    let provider = Arc::new(ScriptedProvider::new(&[TOPIC_JSON]));
+    let generator = TopicGenerator::new(provider.clone());
+    let result = generator
+        .search_queries_for_topic("How do octopuses taste with their arms?")
+        .await
+        .unwrap();
+    assert_eq!(result.topic, "How do octopuses taste with their arms?");
+    assert_eq!(result.full_search_query, "dreams rem sleep");
+    assert!(provider.prompts.lock().unwrap()[0].contains("octopuses"));
+}
+
+#[tokio::test]
+async fn explicit_theme_replaces_the_random_one() {
+    let provider = Arc::new(ScriptedProvider::new(&[TOPIC_JSON]));
+    let generator = TopicGenerator::new(provider.clone());
+    generator
+        .generate_topic(Some("marine biology"))
+        .await
+        .unwrap();
+    assert!(provider.prompts.lock().unwrap()[0].contains("blog post about marine biology."));
+}
+
+#[tokio::test]
+async fn random_theme_comes_from_the_configured_themes() {
+    let provider = Arc::new(ScriptedProvider::new(&[TOPIC_JSON]));
+    let mut resting = TopicTheme::new("black holes", "space");
+    resting.last_used_at = Some(chrono::Utc::now());
+    let generator = TopicGenerator::new(provider.clone())
+        .with_themes(vec![resting, TopicTheme::new("protein folding", "biology")]);
+    let result = generator.generate_topic(None).await.unwrap();
+    assert_eq!(result.theme.as_deref(), Some("protein folding"));
+    assert!(provider.prompts.lock().unwrap()[0].contains("blog post about protein folding."));
+
+    let error = TopicGenerator::new(provider)
+        .generate_topic(None)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("No enabled topic themes"));
+}
+
+#[tokio::test]
+async fn repeated_topics_are_regenerated_avoiding_past_ones() {
+    let provider = Arc::new(ScriptedProvider::new(&[
+        TOPIC_JSON,
+        r#"{"topic": "How do octopuses taste?", "full_search_query": "octopus", "short_search_query": "octopus"}"#,
+    ]));
+    let generator = TopicGenerator::new(provider.clone())
+        .with_history(Arc::new(DreamHistory), TopicNoveltyConfig::default());
+    let result = generator.generate_topic(Some("sleep")).await.unwrap();
+    assert_eq!(result.topic, "How do octopuses taste?");
+
+    let prompts = provider.prompts.lock().unwrap();
+    assert_eq!(prompts.len(), 2);
+    assert!(prompts[0].contains("avoiding topics: What are dreams for?."));
+    assert!(prompts[1].contains("What are dreams for?, Why do we dream?."));
+}
+
+#[tokio::test]
+async fn generation_fails_when_every_attempt_repeats_a_past_topic() {
+    let provider = Arc::new(ScriptedProvider::new(&[TOPIC_JSON, TOPIC_JSON]));
+    let novelty = TopicNoveltyConfig {
+        max_attempts: 2,
+        ..TopicNoveltyConfig::default()
+    };
+    let error = TopicGenerator::new(provider.clone())
+        .with_history(Arc::new(DreamHistory), novelty)
+        .generate_topic(Some("sleep"))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("repeats 'What are dreams for?'"));
+
+    let result = TopicGenerator::new(Arc::new(ScriptedProvider::new(&[TOPIC_JSON])))
+        .with_history(Arc::new(DreamHistory), TopicNoveltyConfig::disabled())
+        .generate_topic(Some("sleep"))
+        .await
+        .unwrap();
+    assert_eq!(result.topic, "Why do we dream?");
+}
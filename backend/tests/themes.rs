@@ -0,0 +1,129 @@
+use adco_backend::api;
+use adco_backend::domain::infra::postgres::themes_repo::ThemeRepositoryPostgres;
+use adco_backend::domain::themes::{pick_theme, ThemeRepository};
+use adco_shared::theme::TopicTheme;
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::json;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use tower::ServiceExt;
+
+mod common;
+
+fn theme(name: &str, weight: f64) -> TopicTheme {
+    let mut theme = TopicTheme::new(name, "physics");
+    theme.weight = weight;
+    theme
+}
+
+#[test]
+fn picks_follow_weights_and_skip_disabled_themes() {
+    let mut disabled = theme("relativity", 100.0);
+    disabled.enabled = false;
+    let themes = vec![
+        theme("quantum mechanics", 3.0),
+        theme("chaos theory", 1.0),
+        theme("thermodynamics", 0.0),
+        disabled,
+    ];
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut picks: HashMap<String, usize> = HashMap::new();
+    for _ in 0..4000 {
+        let picked = pick_theme(&themes, Utc::now(), &mut rng).unwrap();
+        *picks.entry(picked.name.clone()).or_default() += 1;
+    }
+    assert_eq!(picks.len(), 2);
+    let ratio = picks["quantum mechanics"] as f64 / picks["chaos theory"] as f64;
+    assert!((2.5..3.5).contains(&ratio), "ratio {}", ratio);
+}
+
+#[test]
+fn recently_used_themes_rest_until_their_cooldown_ends() {
+    let now = Utc::now();
+    let mut recent = theme("black holes", 1000.0);
+    recent.last_used_at = Some(now - Duration::days(1));
+    let mut rested = theme("dark matter", 1.0);
+    rested.last_used_at = Some(now - Duration::days(30));
+    let themes = vec![recent.clone(), rested.clone()];
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..50 {
+        assert_eq!(
+            pick_theme(&themes, now, &mut rng).unwrap().name,
+            "dark matter"
+        );
+    }
+
+    // When every theme rests, the one that is available soonest is used
+    let mut resting = rested;
+    resting.last_used_at = Some(now - Duration::days(2));
+    let themes = vec![resting, recent];
+    assert_eq!(
+        pick_theme(&themes, now, &mut rng).unwrap().name,
+        "dark matter"
+    );
+    assert!(pick_theme(&[], now, &mut rng).is_none());
+}
+
+#[test]
+fn cooldowns_past_the_last_date_rest_forever() {
+    let mut theme = theme("entropy", 1.0);
+    theme.last_used_at = Some(DateTime::<Utc>::MAX_UTC - Duration::days(1));
+    theme.cooldown_days = TopicTheme::MAX_COOLDOWN_DAYS;
+    assert_eq!(theme.available_at(), Some(DateTime::<Utc>::MAX_UTC));
+    assert!(theme.is_cooling_down(Utc::now()));
+}
+
+#[tokio::test]
+async fn cooldowns_over_ten_years_are_rejected() {
+    let app = api::router(&PgPool::connect_lazy("postgres://postgres@localhost/adco").unwrap());
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/api/themes")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"name": "entropy", "category": "physics", "cooldown_days": 3651}).to_string(),
+        ))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres (ADCO_TEST_DATABASE_URL)"]
+async fn themes_are_seeded_and_cooldowns_persisted() {
+    let pg_pool = common::test_pool().await;
+    let repo = ThemeRepositoryPostgres::new(&pg_pool);
+
+    let seeded = repo.list_themes().await.unwrap();
+    assert!(seeded
+        .iter()
+        .any(|t| t.name == "neuroplasticity" && t.category == "brain"));
+
+    let name = format!("test theme {}", uuid::Uuid::new_v4());
+    let mut theme = TopicTheme::new(&name, "engineering");
+    theme.id = Some(repo.create_theme(&theme).await.unwrap());
+    let used_at = Utc::now();
+    repo.mark_theme_used(&name, used_at).await.unwrap();
+    let stored = repo
+        .get_theme_by_id(theme.id.unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(stored.is_cooling_down(used_at + Duration::days(1)));
+
+    theme.enabled = false;
+    repo.update_theme(&theme).await.unwrap();
+    let enabled = repo.get_enabled_themes().await.unwrap();
+    assert!(enabled.iter().all(|t| t.name != name));
+    sqlx::query("DELETE FROM topic_themes WHERE id = $1")
+        .bind(theme.id)
+        .execute(&pg_pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,79 @@
+use adco_backend::domain::content::{
+    fit_to_budget, BpeTokenizer, ChunkingStrategy, EstimatedTokenCounter, TextChunker,
+    TokenCounter, MAX_EMBEDDING_INPUT_TOKENS,
+};
+use adco_shared::source::{Source, SourceType};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Bytes plus merges up to "hello" and " wor".
+fn tiny_tokenizer() -> BpeTokenizer {
+    BpeTokenizer::load(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tokenizer/tiny.tiktoken"),
+    )
+    .unwrap()
+}
+
+#[test]
+fn bpe_merges_by_rank_within_pretokenized_pieces() {
+    let tokenizer = tiny_tokenizer();
+    assert_eq!(tokenizer.count_tokens(""), 0);
+    assert_eq!(tokenizer.count_tokens("hello"), 1);
+    // " world" merges into " wor" + "ld"
+    assert_eq!(tokenizer.count_tokens("hello world"), 3);
+    // The second space is a piece of its own, the first one stays with the word
+    assert_eq!(tokenizer.count_tokens("hello  world"), 4);
+    // Digits go in pieces of three: "123", "45", "hello"
+    assert_eq!(tokenizer.count_tokens("12345hello"), 6);
+    // h, two bytes of é, "llo"
+    assert_eq!(tokenizer.count_tokens("héllo"), 4);
+}
+
+#[test]
+fn invalid_vocabulary_lines_are_reported() {
+    let error = BpeTokenizer::parse("aGU= 256\nnot-base64 x\n").unwrap_err();
+    assert!(error.to_string().contains("line 2"), "{}", error);
+}
+
+#[test]
+fn chunks_are_sized_in_tokens() {
+    let text = vec!["hello world"; 30].join(" ");
+    let source = Source::new(
+        "https://example.com/tokens".to_string(),
+        "Tokens".to_string(),
+        SourceType::API,
+        text,
+    );
+    let chunks = TextChunker::new(10, 3)
+        .with_counter(Arc::new(tiny_tokenizer()))
+        .with_strategy(SourceType::API, ChunkingStrategy::Words)
+        .chunk_text_from_source(&source);
+
+    let tokenizer = tiny_tokenizer();
+    for chunk in &chunks {
+        assert!(tokenizer.count_tokens(&chunk.text) <= 10, "{}", chunk.text);
+    }
+    // "hello" is 1 token and "world" 3 on their own, the last "hello" is the overlap
+    assert_eq!(chunks[0].text, "hello world hello world hello");
+    assert!(chunks[1].text.starts_with("hello world"));
+
+    assert_eq!(
+        TextChunker::new(usize::MAX, 0).chunk_size,
+        MAX_EMBEDDING_INPUT_TOKENS
+    );
+}
+
+#[test]
+fn context_keeps_the_best_ranked_texts_that_fit() {
+    let texts: Vec<String> = [40, 80, 30, 10]
+        .iter()
+        .map(|chars| "x".repeat(*chars))
+        .collect();
+    // 10, 20, 8 and 3 estimated tokens
+    assert_eq!(fit_to_budget(&EstimatedTokenCounter, &texts, 21), [0, 2, 3]);
+    assert_eq!(
+        fit_to_budget(&EstimatedTokenCounter, &texts, 100),
+        [0, 1, 2, 3]
+    );
+    assert!(fit_to_budget(&EstimatedTokenCounter, &texts, 2).is_empty());
+}
@@ -0,0 +1,55 @@
+use adco_backend::domain::content::{
+    cosine_similarity, FakeEmbeddingModel, PostTopicHistory, TopicHistory,
+};
+use adco_backend::domain::infra::postgres::posts_repo::PostsRepositoryPostgres;
+use adco_backend::domain::posts::PostRepository;
+use adco_shared::post::Post;
+use uuid::Uuid;
+
+mod common;
+
+#[test]
+fn cosine_similarity_ignores_length_and_handles_zero_vectors() {
+    assert!((cosine_similarity(&[1.0, 2.0], &[2.0, 4.0]) - 1.0).abs() < 1e-9);
+    assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]).abs() < 1e-9);
+    assert!((cosine_similarity(&[1.0, 0.0], &[-1.0, 0.0]) + 1.0).abs() < 1e-9);
+    assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    assert_eq!(cosine_similarity(&[], &[]), 0.0);
+}
+
+async fn needs_embedding(post_repo: &PostsRepositoryPostgres, post_id: Uuid) -> bool {
+    post_repo
+        .get_unembedded_post_topics("fake-embedding")
+        .await
+        .unwrap()
+        .iter()
+        .any(|t| t.post_id == post_id)
+}
+
+#[tokio::test]
+#[ignore = "requires local Postgres with pgvector (ADCO_TEST_DATABASE_URL)"]
+async fn post_topics_are_embedded_once_and_ranked_by_similarity() {
+    let pg_pool = common::test_pool().await;
+    let post_repo = PostsRepositoryPostgres::new(&pg_pool);
+    let marker = Uuid::new_v4().simple().to_string();
+    let topic = format!("Why do octopuses {} taste with their arms?", marker);
+    let post = Post::new(topic.clone(), String::new(), String::new(), String::new());
+    let post_id = post_repo.create_post(&post).await.unwrap();
+    assert!(needs_embedding(&post_repo, post_id).await);
+
+    let history = PostTopicHistory::new(&pg_pool, FakeEmbeddingModel::new(1536));
+    let nearest = history
+        .nearest_topics(&format!("How do octopuses {} taste?", marker), 1)
+        .await
+        .unwrap();
+    assert_eq!(nearest[0].topic, topic);
+    assert!(nearest[0].similarity > 0.5);
+    assert!(!needs_embedding(&post_repo, post_id).await);
+
+    // A new topic invalidates the stored embedding
+    let mut post = post_repo.get_post_by_id(post_id).await.unwrap().unwrap();
+    post.topic = "Black holes".to_string();
+    post_repo.update_post(&post).await.unwrap();
+    assert!(needs_embedding(&post_repo, post_id).await);
+    post_repo.delete_post(post_id).await.unwrap();
+}
@@ -0,0 +1,57 @@
+use adco_backend::domain::{
+    llm::{LlmFuture, LlmProvider, LlmRequest, LlmResponse, LlmUsage, MeteredLlmProvider},
+    usage::{cost_usd, price_for, UsageScope},
+};
+use adco_shared::usage::LlmCallKind;
+use std::sync::Arc;
+
+struct FixedUsageProvider;
+
+impl LlmProvider for FixedUsageProvider {
+    fn model(&self) -> &str {
+        "gpt-5-mini"
+    }
+
+    fn complete<'a>(&'a self, _request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(async {
+            Ok(LlmResponse {
+                content: "ok".to_string(),
+                model: "gpt-5-mini-2025-08-07".to_string(),
+                usage: LlmUsage {
+                    input_tokens: 1_000_000,
+                    output_tokens: 500_000,
+                },
+            })
+        })
+    }
+}
+
+#[test]
+fn longest_model_prefix_sets_the_price() {
+    assert_eq!(price_for("gpt-5-mini-2025-08-07"), price_for("gpt-5-mini"));
+    assert_ne!(price_for("gpt-5-mini"), price_for("gpt-5"));
+    assert_eq!(cost_usd("gpt-5", 1_000_000, 1_000_000), 11.25);
+    assert_eq!(cost_usd("my-local-model", 1_000_000, 1_000_000), 0.0);
+
+    std::env::set_var("ADCO_LLM_PRICE_MY_LOCAL_MODEL", "1, 2");
+    assert_eq!(cost_usd("my-local-model", 1_000_000, 1_000_000), 3.0);
+}
+
+#[tokio::test]
+async fn metered_calls_are_collected_by_the_surrounding_scope() {
+    let provider = MeteredLlmProvider::new(Arc::new(FixedUsageProvider));
+    let request = LlmRequest::new("stylizer", "preamble", "prompt".to_string());
+
+    let scope = UsageScope::new();
+    scope.run(provider.complete(&request)).await.unwrap();
+    // Calls outside of a scope are only logged
+    provider.complete(&request).await.unwrap();
+
+    let calls = scope.take();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].agent, "stylizer");
+    assert_eq!(calls[0].kind, LlmCallKind::Completion);
+    assert_eq!(calls[0].input_tokens, 1_000_000);
+    assert_eq!(calls[0].cost_usd, 0.25 + 1.0);
+    assert!(scope.take().is_empty());
+}
@@ -107,3 +107,14 @@ pub async fn get_all_posts_names() -> Result<Vec<Post>, ServerFnError> {
         .await?;
     Ok(posts)
 }
+
+use adco_shared::search::{SearchQuery, SearchResult};
+#[server]
+pub async fn search_knowledge_base(query: SearchQuery) -> Result<Vec<SearchResult>, ServerFnError> {
+    use adco_backend::api::search::search;
+    use adco_backend::appstate::APP_STATE;
+
+    let pg_pool = APP_STATE.get_pg_pool().await;
+    let results = search(&pg_pool, &query).await?;
+    Ok(results)
+}
@@ -0,0 +1,47 @@
+use chrono::offset::Utc;
+use chrono::{DateTime, Duration};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// An RSS or Atom feed polled for new sources.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Feed {
+    pub id: Option<Uuid>,
+    pub url: String,
+    pub title: Option<String>,
+    pub poll_interval_minutes: i32,
+    pub enabled: bool,
+    /// `ETag` of the last successful response, sent back as `If-None-Match`.
+    pub etag: Option<String>,
+    /// `Last-Modified` of the last successful response, sent back as `If-Modified-Since`.
+    pub last_modified: Option<String>,
+    pub last_polled_at: Option<DateTime<Utc>>,
+    /// Why the last poll failed, `None` after a successful one.
+    pub last_error: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl Feed {
+    pub fn new(url: &str) -> Self {
+        Self {
+            id: None,
+            url: url.to_string(),
+            title: None,
+            poll_interval_minutes: 60,
+            enabled: true,
+            etag: None,
+            last_modified: None,
+            last_polled_at: None,
+            last_error: None,
+            created_at: Some(chrono::Utc::now()),
+        }
+    }
+
+    /// Enabled and never polled, or polled at least one interval ago.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.enabled
+            && self.last_polled_at.is_none_or(|polled| {
+                polled + Duration::minutes(self.poll_interval_minutes.into()) <= now
+            })
+    }
+}
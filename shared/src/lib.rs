@@ -1,2 +1,7 @@
+pub mod feed;
+pub mod pipeline;
 pub mod post;
-pub mod source;
\ No newline at end of file
+pub mod search;
+pub mod source;
+pub mod theme;
+pub mod usage;
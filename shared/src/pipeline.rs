@@ -0,0 +1,288 @@
+use chrono::offset::Utc;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "backend")]
+use sqlx::Type;
+
+use uuid::Uuid;
+
+/// One execution of the generation pipeline (topic -> ... -> publishing).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(Type))]
+#[cfg_attr(feature = "backend", sqlx(type_name = "pipeline_run"))]
+pub struct PipelineRun {
+    pub id: Option<Uuid>,
+    pub status: PipelineRunStatus,
+    pub current_stage: Option<PipelineStageKind>,
+    pub post_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub meta: Option<serde_json::Value>,
+}
+
+impl PipelineRun {
+    pub fn new() -> Self {
+        let now = chrono::Utc::now();
+        Self {
+            id: None,
+            status: PipelineRunStatus::Queued,
+            current_stage: None,
+            post_id: None,
+            error: None,
+            created_at: Some(now),
+            updated_at: Some(now),
+            finished_at: None,
+            meta: None,
+        }
+    }
+}
+
+impl Default for PipelineRun {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What a run was asked to write about, kept in `PipelineRun::meta` so a resumed run
+/// follows the same request. Nothing set means a topic on a random theme.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RunRequest {
+    /// Write about exactly this topic.
+    pub topic: Option<String>,
+    /// Generate a topic within this theme.
+    pub theme: Option<String>,
+}
+
+impl RunRequest {
+    pub fn topic(topic: &str) -> Self {
+        Self { topic: Some(topic.trim().to_string()), theme: None }
+    }
+
+    pub fn theme(theme: &str) -> Self {
+        Self { topic: None, theme: Some(theme.trim().to_string()) }
+    }
+
+    /// A run is about one topic or one theme, not both, and neither may be blank.
+    pub fn validate(&self) -> Result<(), String> {
+        let blank = |value: &Option<String>| value.as_deref().is_some_and(|v| v.trim().is_empty());
+        if blank(&self.topic) || blank(&self.theme) {
+            return Err("topic and theme must not be empty".to_string());
+        }
+        if self.topic.is_some() && self.theme.is_some() {
+            return Err("Pass either a topic or a theme, not both".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Topics of a batch, one per line. Blank lines and lines starting with `#` are skipped.
+pub fn parse_topic_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "backend", derive(Type))]
+#[cfg_attr(feature = "backend", sqlx(type_name = "pipeline_run_status"))]
+pub enum PipelineRunStatus {
+    /// Created or resumed, no stage has started yet.
+    Queued,
+    Running,
+    Failed,
+    Completed,
+}
+
+impl From<String> for PipelineRunStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "queued" => PipelineRunStatus::Queued,
+            "running" => PipelineRunStatus::Running,
+            "failed" => PipelineRunStatus::Failed,
+            "completed" => PipelineRunStatus::Completed,
+            _ => PipelineRunStatus::Failed,
+        }
+    }
+}
+
+impl std::fmt::Display for PipelineRunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PipelineRunStatus::Queued => write!(f, "queued"),
+            PipelineRunStatus::Running => write!(f, "running"),
+            PipelineRunStatus::Failed => write!(f, "failed"),
+            PipelineRunStatus::Completed => write!(f, "completed"),
+        }
+    }
+}
+
+/// Checkpoint of a single stage inside a run: what went in, what came out and how long it took.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(Type))]
+#[cfg_attr(feature = "backend", sqlx(type_name = "pipeline_stage"))]
+pub struct PipelineStage {
+    pub id: Option<Uuid>,
+    pub run_id: Uuid,
+    pub stage: PipelineStageKind,
+    pub status: PipelineStageStatus,
+    pub attempts: i32,
+    pub input: Option<serde_json::Value>,
+    pub output: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<i64>,
+}
+
+impl PipelineStage {
+    pub fn new(run_id: Uuid, stage: PipelineStageKind) -> Self {
+        Self {
+            id: None,
+            run_id,
+            stage,
+            status: PipelineStageStatus::Running,
+            attempts: 0,
+            input: None,
+            output: None,
+            error: None,
+            started_at: None,
+            finished_at: None,
+            duration_ms: None,
+        }
+    }
+}
+
+/// Pipeline stages in execution order.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "backend", derive(Type))]
+#[cfg_attr(feature = "backend", sqlx(type_name = "pipeline_stage_kind"))]
+pub enum PipelineStageKind {
+    TopicGeneration,
+    Retrieval,
+    Research,
+    Stylizing,
+    QualityControl,
+    PostCreation,
+    Publishing,
+}
+
+impl PipelineStageKind {
+    pub const ALL: [PipelineStageKind; 7] = [
+        PipelineStageKind::TopicGeneration,
+        PipelineStageKind::Retrieval,
+        PipelineStageKind::Research,
+        PipelineStageKind::Stylizing,
+        PipelineStageKind::QualityControl,
+        PipelineStageKind::PostCreation,
+        PipelineStageKind::Publishing,
+    ];
+}
+
+impl From<String> for PipelineStageKind {
+    fn from(stage: String) -> Self {
+        match stage.as_str() {
+            "topic_generation" => PipelineStageKind::TopicGeneration,
+            "retrieval" => PipelineStageKind::Retrieval,
+            "research" => PipelineStageKind::Research,
+            "stylizing" => PipelineStageKind::Stylizing,
+            "quality_control" => PipelineStageKind::QualityControl,
+            "post_creation" => PipelineStageKind::PostCreation,
+            "publishing" => PipelineStageKind::Publishing,
+            _ => PipelineStageKind::TopicGeneration,
+        }
+    }
+}
+
+impl std::fmt::Display for PipelineStageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PipelineStageKind::TopicGeneration => write!(f, "topic_generation"),
+            PipelineStageKind::Retrieval => write!(f, "retrieval"),
+            PipelineStageKind::Research => write!(f, "research"),
+            PipelineStageKind::Stylizing => write!(f, "stylizing"),
+            PipelineStageKind::QualityControl => write!(f, "quality_control"),
+            PipelineStageKind::PostCreation => write!(f, "post_creation"),
+            PipelineStageKind::Publishing => write!(f, "publishing"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "backend", derive(Type))]
+#[cfg_attr(feature = "backend", sqlx(type_name = "pipeline_stage_status"))]
+pub enum PipelineStageStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+impl From<String> for PipelineStageStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "running" => PipelineStageStatus::Running,
+            "completed" => PipelineStageStatus::Completed,
+            "failed" => PipelineStageStatus::Failed,
+            _ => PipelineStageStatus::Failed,
+        }
+    }
+}
+
+impl std::fmt::Display for PipelineStageStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PipelineStageStatus::Running => write!(f, "running"),
+            PipelineStageStatus::Completed => write!(f, "completed"),
+            PipelineStageStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// Progress of a run as it happens, streamed to API clients.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PipelineEvent {
+    StageStarted {
+        stage: PipelineStageKind,
+        attempt: i32,
+    },
+    /// Completed stage skipped on resume, its output comes from the checkpoint.
+    StageRestored {
+        stage: PipelineStageKind,
+    },
+    StageCompleted {
+        stage: PipelineStageKind,
+        duration_ms: i64,
+    },
+    StageFailed {
+        stage: PipelineStageKind,
+        error: String,
+    },
+    Log {
+        level: String,
+        message: String,
+    },
+    RunFinished {
+        status: PipelineRunStatus,
+        post_id: Option<Uuid>,
+        error: Option<String>,
+    },
+}
+
+impl PipelineEvent {
+    /// Name of the event, used as the SSE event type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PipelineEvent::StageStarted { .. } => "stage_started",
+            PipelineEvent::StageRestored { .. } => "stage_restored",
+            PipelineEvent::StageCompleted { .. } => "stage_completed",
+            PipelineEvent::StageFailed { .. } => "stage_failed",
+            PipelineEvent::Log { .. } => "log",
+            PipelineEvent::RunFinished { .. } => "run_finished",
+        }
+    }
+}
@@ -18,7 +18,17 @@ pub struct Post {
     pub channel_id: String,
     pub scheduled_at: Option<DateTime<Utc>>,
     pub published_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
     pub meta: Option<serde_json::Value>,
+    /// Channel messages the post was published as; long posts are split into several.
+    #[serde(default)]
+    pub telegram_message_ids: Vec<i64>,
+    /// Failed sends since the status was last changed by hand.
+    #[serde(default)]
+    pub publish_attempts: i32,
+    /// Error of the last failed send.
+    #[serde(default)]
+    pub publish_error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -27,7 +37,11 @@ pub struct Post {
 pub enum PostStatus {
     Draft,
     Scheduled,
+    /// Claimed by a publisher and being sent to the channel.
+    Publishing,
     Published,
+    /// Turned down during review, never published.
+    Rejected,
 }
 
 impl From<String> for PostStatus {
@@ -35,7 +49,9 @@ impl From<String> for PostStatus {
         match status.as_str() {
             "draft" => PostStatus::Draft,
             "scheduled" => PostStatus::Scheduled,
+            "publishing" => PostStatus::Publishing,
             "published" => PostStatus::Published,
+            "rejected" => PostStatus::Rejected,
             _ => PostStatus::Draft,
         }
     }
@@ -46,7 +62,9 @@ impl std::fmt::Display for PostStatus {
         match self {
             PostStatus::Draft => write!(f, "draft"),
             PostStatus::Scheduled => write!(f, "scheduled"),
+            PostStatus::Publishing => write!(f, "publishing"),
             PostStatus::Published => write!(f, "published"),
+            PostStatus::Rejected => write!(f, "rejected"),
         }
     }
 }
@@ -72,7 +90,11 @@ impl Post {
             channel_id,
             scheduled_at: None,
             published_at: None,
+            created_at: Some(chrono::Utc::now()),
             meta: None,
+            telegram_message_ids: Vec::new(),
+            publish_attempts: 0,
+            publish_error: None,
         }
     }
 
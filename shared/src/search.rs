@@ -0,0 +1,57 @@
+use chrono::offset::Utc;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::source::SourceType;
+
+pub const DEFAULT_SEARCH_TOP_K: usize = 10;
+pub const MAX_SEARCH_TOP_K: usize = 50;
+
+/// Free-text search over the embedded chunks, with optional filters on their source.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchQuery {
+    pub query: String,
+    pub top_k: Option<usize>,
+    pub source_type: Option<SourceType>,
+    pub source_id: Option<Uuid>,
+    /// Only sources fetched at or after this time.
+    pub fetched_after: Option<DateTime<Utc>>,
+    /// Only sources fetched at or before this time.
+    pub fetched_before: Option<DateTime<Utc>>,
+    /// Weight of the semantic ranking, `ADCO_RETRIEVAL_VECTOR_WEIGHT` by default.
+    pub vector_weight: Option<f64>,
+    /// Weight of the full-text ranking, `ADCO_RETRIEVAL_TEXT_WEIGHT` by default.
+    pub text_weight: Option<f64>,
+}
+
+impl SearchQuery {
+    pub fn new(query: &str) -> Self {
+        Self {
+            query: query.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Requested number of results, 10 by default and at most 50.
+    pub fn top_k(&self) -> usize {
+        self.top_k
+            .unwrap_or(DEFAULT_SEARCH_TOP_K)
+            .clamp(1, MAX_SEARCH_TOP_K)
+    }
+}
+
+/// A chunk found by hybrid search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub chunk_id: Uuid,
+    pub source_id: Option<Uuid>,
+    pub source_title: String,
+    pub source_url: String,
+    pub source_type: Option<SourceType>,
+    pub fetched_at: Option<DateTime<Utc>>,
+    pub text: String,
+    /// Weighted reciprocal rank fusion of the semantic and full-text ranks, higher is
+    /// better. Only comparable within one search.
+    pub score: f64,
+}
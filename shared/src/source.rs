@@ -25,7 +25,27 @@ impl Source {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Source without its text, for listings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceSummary {
+    pub id: Uuid,
+    pub url: String,
+    pub title: String,
+    pub source_type: SourceType,
+    pub fetched_at: Option<DateTime<Utc>>,
+    pub text_length: i64,
+    pub chunk_count: i64,
+}
+
+/// Embedded chunk of a source, as stored in the `documents` table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceChunk {
+    pub id: Uuid,
+    pub source_id: Uuid,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "backend", derive(Type))]
 #[cfg_attr(feature = "backend", sqlx(type_name = "source_type"))]
 pub enum SourceType {
@@ -38,9 +58,10 @@ pub enum SourceType {
 impl From<String> for SourceType {
     fn from(source_type: String) -> Self {
         match source_type.as_str() {
-            "rss" => SourceType::RSS,
-            "web_page" => SourceType::WebPage,
-            "api" => SourceType::API,
+            "rss" | "RSS" => SourceType::RSS,
+            "web_page" | "WebPage" => SourceType::WebPage,
+            "api" | "API" => SourceType::API,
+            "pdf" | "PDF" => SourceType::PDF,
             _ => SourceType::RSS,
         }
     }
@@ -0,0 +1,50 @@
+use chrono::offset::Utc;
+use chrono::{DateTime, Duration};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A theme the topic generator writes about, managed in the `topic_themes` table.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TopicTheme {
+    pub id: Option<Uuid>,
+    pub name: String,
+    /// brain/cognition/neuroscience/ai/physics/biology/space/engineering
+    pub category: String,
+    /// Relative chance to be picked, 0 never picks the theme.
+    pub weight: f64,
+    pub enabled: bool,
+    /// Days the theme rests after being used.
+    pub cooldown_days: i32,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl TopicTheme {
+    /// Longest cooldown, also enforced by a CHECK on `topic_themes`.
+    pub const MAX_COOLDOWN_DAYS: i32 = 3650;
+
+    pub fn new(name: &str, category: &str) -> Self {
+        Self {
+            id: None,
+            name: name.to_string(),
+            category: category.to_string(),
+            weight: 1.0,
+            enabled: true,
+            cooldown_days: 14,
+            last_used_at: None,
+            created_at: Some(chrono::Utc::now()),
+        }
+    }
+
+    /// When the theme can be picked again, `None` if it is not resting.
+    pub fn available_at(&self) -> Option<DateTime<Utc>> {
+        self.last_used_at.map(|used| {
+            used.checked_add_signed(Duration::days(self.cooldown_days.into()))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC)
+        })
+    }
+
+    pub fn is_cooling_down(&self, now: DateTime<Utc>) -> bool {
+        self.available_at().is_some_and(|at| at > now)
+    }
+}
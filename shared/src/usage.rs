@@ -0,0 +1,87 @@
+use chrono::offset::Utc;
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "backend")]
+use sqlx::Type;
+
+use crate::pipeline::PipelineStageKind;
+use uuid::Uuid;
+
+/// One paid model call: an agent completion or an embeddings batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(Type))]
+#[cfg_attr(feature = "backend", sqlx(type_name = "llm_call"))]
+pub struct LlmCall {
+    pub id: Option<Uuid>,
+    pub run_id: Option<Uuid>,
+    pub stage: Option<PipelineStageKind>,
+    pub agent: String,
+    pub kind: LlmCallKind,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub latency_ms: i64,
+    pub cost_usd: f64,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl LlmCall {
+    pub fn new(agent: &str, kind: LlmCallKind, model: &str) -> Self {
+        Self {
+            id: None,
+            run_id: None,
+            stage: None,
+            agent: agent.to_string(),
+            kind,
+            model: model.to_string(),
+            input_tokens: 0,
+            output_tokens: 0,
+            latency_ms: 0,
+            cost_usd: 0.0,
+            created_at: Some(chrono::Utc::now()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "backend", derive(Type))]
+#[cfg_attr(feature = "backend", sqlx(type_name = "llm_call_kind"))]
+pub enum LlmCallKind {
+    Completion,
+    Embedding,
+}
+
+impl From<String> for LlmCallKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            "embedding" => LlmCallKind::Embedding,
+            _ => LlmCallKind::Completion,
+        }
+    }
+}
+
+impl std::fmt::Display for LlmCallKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LlmCallKind::Completion => write!(f, "completion"),
+            LlmCallKind::Embedding => write!(f, "embedding"),
+        }
+    }
+}
+
+/// Totals over a set of calls (a post, a day, ...).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CostSummary {
+    pub calls: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DailyCost {
+    pub day: NaiveDate,
+    #[serde(flatten)]
+    pub summary: CostSummary,
+}
@@ -0,0 +1,36 @@
+-- ADCO Pipeline runs
+-- Every orchestrator run with per-stage checkpoints, so a failed run can be resumed
+
+-- Pipeline runs: one row per orchestrator execution
+CREATE TABLE pipeline_runs (
+  id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+  status TEXT NOT NULL,           -- running/failed/completed
+  current_stage TEXT,             -- last stage that was started
+  post_id UUID REFERENCES posts(id) ON DELETE SET NULL,
+  error TEXT,
+  created_at TIMESTAMPTZ DEFAULT now(),
+  updated_at TIMESTAMPTZ DEFAULT now(),
+  finished_at TIMESTAMPTZ,
+  meta JSONB
+);
+
+-- Pipeline stages: input/output checkpoint of every stage of a run
+CREATE TABLE pipeline_stages (
+  id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+  run_id UUID NOT NULL REFERENCES pipeline_runs(id) ON DELETE CASCADE,
+  stage TEXT NOT NULL,            -- topic_generation/retrieval/research/...
+  status TEXT NOT NULL,           -- running/completed/failed
+  attempts INT NOT NULL DEFAULT 0,
+  input JSONB,
+  output JSONB,
+  error TEXT,
+  started_at TIMESTAMPTZ,
+  finished_at TIMESTAMPTZ,
+  duration_ms BIGINT,
+  UNIQUE (run_id, stage)
+);
+
+-- Indexes for performance
+CREATE INDEX idx_pipeline_runs_status ON pipeline_runs(status);
+CREATE INDEX idx_pipeline_runs_created_at ON pipeline_runs(created_at);
+CREATE INDEX idx_pipeline_stages_run_id ON pipeline_stages(run_id);
@@ -0,0 +1,20 @@
+-- ADCO LLM usage
+-- Tokens, latency and cost of every agent call and embeddings batch
+
+CREATE TABLE llm_calls (
+  id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+  run_id UUID REFERENCES pipeline_runs(id) ON DELETE SET NULL,
+  stage TEXT,                     -- pipeline stage the call was made in
+  agent TEXT NOT NULL,            -- topic_generator/researcher/.../embeddings
+  kind TEXT NOT NULL,             -- completion/embedding
+  model TEXT NOT NULL,
+  input_tokens BIGINT NOT NULL DEFAULT 0,
+  output_tokens BIGINT NOT NULL DEFAULT 0,
+  latency_ms BIGINT NOT NULL DEFAULT 0,
+  cost_usd DOUBLE PRECISION NOT NULL DEFAULT 0,
+  created_at TIMESTAMPTZ DEFAULT now()
+);
+
+-- Indexes for performance
+CREATE INDEX idx_llm_calls_run_id ON llm_calls(run_id);
+CREATE INDEX idx_llm_calls_created_at ON llm_calls(created_at);
@@ -0,0 +1,4 @@
+-- ADCO Telegram message ids
+-- Long posts are published as several messages, keep all their ids
+
+ALTER TABLE posts ADD COLUMN telegram_message_ids BIGINT[] NOT NULL DEFAULT '{}';
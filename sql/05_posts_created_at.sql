@@ -0,0 +1,5 @@
+-- ADCO Posts creation time
+-- Needed to list posts newest first in the API
+
+ALTER TABLE posts ADD COLUMN created_at TIMESTAMPTZ DEFAULT now();
+CREATE INDEX idx_posts_created_at ON posts(created_at);
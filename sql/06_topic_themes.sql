@@ -0,0 +1,77 @@
+-- ADCO topic themes
+-- Themes the topic generator picks from, weighted and rested for a while after use
+
+CREATE TABLE topic_themes (
+  id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+  name TEXT NOT NULL UNIQUE,
+  category TEXT NOT NULL,             -- brain/cognition/neuroscience/ai/physics/biology/space/engineering
+  weight DOUBLE PRECISION NOT NULL DEFAULT 1 CHECK (weight >= 0),
+  enabled BOOLEAN NOT NULL DEFAULT true,
+  cooldown_days INTEGER NOT NULL DEFAULT 14 CHECK (cooldown_days >= 0),
+  last_used_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ DEFAULT now()
+);
+
+CREATE INDEX idx_topic_themes_category ON topic_themes(category);
+
+INSERT INTO topic_themes (name, category) VALUES
+  ('brain-computer interfaces', 'brain'),
+  ('neural decoding', 'brain'),
+  ('neuroplasticity', 'brain'),
+  ('synaptic mechanisms', 'brain'),
+  ('memory consolidation', 'brain'),
+  ('predictive brain', 'brain'),
+  ('consciousness', 'brain'),
+  ('sleep and dreams', 'brain'),
+  ('cognitive biases', 'cognition'),
+  ('decision making', 'cognition'),
+  ('attention and focus', 'cognition'),
+  ('language processing', 'cognition'),
+  ('spatial navigation', 'cognition'),
+  ('temporal perception', 'cognition'),
+  ('social cognition', 'cognition'),
+  ('neurotransmitters', 'neuroscience'),
+  ('brain networks', 'neuroscience'),
+  ('neural development', 'neuroscience'),
+  ('brain rhythms', 'neuroscience'),
+  ('sensory processing', 'neuroscience'),
+  ('motor control', 'neuroscience'),
+  ('neural computation', 'neuroscience'),
+  ('artificial neural networks', 'ai'),
+  ('deep learning', 'ai'),
+  ('reinforcement learning', 'ai'),
+  ('neural architecture', 'ai'),
+  ('brain-inspired AI', 'ai'),
+  ('spiking neural networks', 'ai'),
+  ('quantum mechanics', 'physics'),
+  ('particle physics', 'physics'),
+  ('relativity', 'physics'),
+  ('thermodynamics', 'physics'),
+  ('waves and vibrations', 'physics'),
+  ('chaos theory', 'physics'),
+  ('symmetry in nature', 'physics'),
+  ('mathematical patterns', 'physics'),
+  ('evolutionary biology', 'biology'),
+  ('genetic algorithms', 'biology'),
+  ('protein folding', 'biology'),
+  ('cellular automata', 'biology'),
+  ('ecosystem dynamics', 'biology'),
+  ('biomechanics', 'biology'),
+  ('molecular biology', 'biology'),
+  ('black holes', 'space'),
+  ('dark matter', 'space'),
+  ('exoplanets', 'space'),
+  ('stellar evolution', 'space'),
+  ('cosmic rays', 'space'),
+  ('gravitational waves', 'space'),
+  ('space-time', 'space'),
+  ('astrobiology', 'space'),
+  ('cosmic inflation', 'space'),
+  ('robotics', 'engineering'),
+  ('smart materials', 'engineering'),
+  ('nanotechnology', 'engineering'),
+  ('renewable energy', 'engineering'),
+  ('architecture and design', 'engineering'),
+  ('aerodynamics', 'engineering'),
+  ('sensors and automation', 'engineering'),
+  ('biomimetics', 'engineering');
@@ -0,0 +1,6 @@
+-- ADCO post topic embeddings
+-- New topics are compared with the topics of existing posts to avoid repeating them.
+-- Embeddings are filled lazily and recomputed when the embedding model changes.
+
+ALTER TABLE posts ADD COLUMN topic_embedding REAL[];
+ALTER TABLE posts ADD COLUMN topic_embedding_model TEXT;
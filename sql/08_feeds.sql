@@ -0,0 +1,30 @@
+-- ADCO RSS/Atom feeds
+-- Feeds are polled on their own interval, every new item is scraped into sources
+
+CREATE TABLE feeds (
+  id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+  url TEXT NOT NULL UNIQUE,
+  title TEXT,
+  poll_interval_minutes INTEGER NOT NULL DEFAULT 60 CHECK (poll_interval_minutes > 0),
+  enabled BOOLEAN NOT NULL DEFAULT true,
+  etag TEXT,                      -- validators of the last response for conditional requests
+  last_modified TEXT,
+  last_polled_at TIMESTAMPTZ,
+  last_error TEXT,
+  created_at TIMESTAMPTZ DEFAULT now()
+);
+
+-- Items already seen, so they are scraped once; source_id is NULL when scraping failed
+CREATE TABLE feed_items (
+  id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+  feed_id UUID NOT NULL REFERENCES feeds(id) ON DELETE CASCADE,
+  guid TEXT NOT NULL,
+  url TEXT NOT NULL,
+  title TEXT,
+  published_at TIMESTAMPTZ,
+  source_id UUID REFERENCES sources(id) ON DELETE SET NULL,
+  created_at TIMESTAMPTZ DEFAULT now(),
+  UNIQUE (feed_id, guid)
+);
+
+CREATE INDEX idx_feed_items_feed_id ON feed_items(feed_id);
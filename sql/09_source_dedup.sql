@@ -0,0 +1,20 @@
+-- ADCO source deduplication
+-- Sources are looked up by canonical URL (no tracking params, one form per arXiv paper)
+-- and by a sha256 of their text, so known unchanged sources are not embedded again.
+
+ALTER TABLE sources ADD COLUMN canonical_url TEXT;
+ALTER TABLE sources ADD COLUMN content_hash TEXT;
+
+UPDATE sources SET content_hash = encode(sha256(convert_to(COALESCE(raw_text, ''), 'UTF8')), 'hex');
+
+-- Existing rows keep their raw URL until `cargo run -- dedup` (run once after this migration)
+-- replaces it with the canonical form and merges the duplicates; for now only the oldest
+-- of exact duplicates gets it
+UPDATE sources s SET canonical_url = s.url
+WHERE s.url IS NOT NULL
+  AND s.id = (
+    SELECT d.id FROM sources d WHERE d.url = s.url ORDER BY d.fetched_at NULLS LAST, d.id LIMIT 1
+  );
+
+CREATE UNIQUE INDEX idx_sources_canonical_url ON sources(canonical_url);
+CREATE INDEX idx_sources_content_hash ON sources(content_hash);
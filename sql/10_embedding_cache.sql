@@ -0,0 +1,12 @@
+-- ADCO embedding cache
+-- Vectors of already embedded texts, so identical chunks and queries are not sent
+-- to the embeddings API again. The key is a sha256 of the model, its dimensions and the text;
+-- last_used_at tells which entries can be pruned.
+
+CREATE TABLE embedding_cache (
+    key TEXT PRIMARY KEY,
+    model TEXT NOT NULL,
+    embedding REAL[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_used_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
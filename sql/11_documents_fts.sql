@@ -0,0 +1,9 @@
+-- ADCO full-text search over chunks
+-- Hybrid retrieval fuses these ranks with vector similarity, so exact terms like model
+-- names, acronyms and arXiv IDs are found too. The 'simple' configuration keeps words
+-- as written, without stemming or stop words.
+
+ALTER TABLE documents
+  ADD COLUMN embedded_tsv TSVECTOR GENERATED ALWAYS AS (to_tsvector('simple', embedded_text)) STORED;
+
+CREATE INDEX idx_documents_embedded_tsv ON documents USING GIN (embedded_tsv);
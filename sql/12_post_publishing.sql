@@ -0,0 +1,6 @@
+-- ADCO Post publishing attempts
+-- A post is claimed as 'publishing' before it is sent, so two publishers never send it twice.
+-- Failed sends are counted; a scheduled post goes back to draft after too many of them.
+
+ALTER TABLE posts ADD COLUMN publish_attempts INT NOT NULL DEFAULT 0;
+ALTER TABLE posts ADD COLUMN publish_error TEXT;
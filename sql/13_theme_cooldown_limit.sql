@@ -0,0 +1,5 @@
+-- ADCO theme cooldown limit
+-- A cooldown of 10 years is already "never again"; larger values overflowed date arithmetic
+
+UPDATE topic_themes SET cooldown_days = 3650 WHERE cooldown_days > 3650;
+ALTER TABLE topic_themes ADD CONSTRAINT topic_themes_cooldown_days_max CHECK (cooldown_days <= 3650);
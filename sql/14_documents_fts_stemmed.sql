@@ -0,0 +1,10 @@
+-- ADCO stemmed full-text search over chunks
+-- Ordinary query words are matched against this column: the 'russian' configuration
+-- stems Russian words, hands ASCII words to the English stemmer and drops the stop words
+-- of both, so "the" or "и" no longer match every chunk. Identifier-like terms (model
+-- names, arXiv IDs) keep using the 'simple' column from 11_documents_fts.sql.
+
+ALTER TABLE documents
+  ADD COLUMN embedded_tsv_stemmed TSVECTOR GENERATED ALWAYS AS (to_tsvector('russian', embedded_text)) STORED;
+
+CREATE INDEX idx_documents_embedded_tsv_stemmed ON documents USING GIN (embedded_tsv_stemmed);
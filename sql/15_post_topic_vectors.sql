@@ -0,0 +1,9 @@
+-- ADCO post topic embeddings as pgvector
+-- The nearest past topics are found in the database (ORDER BY topic_embedding <=> $1)
+-- instead of loading every embedding. Embeddings of another dimension are dropped and
+-- computed again on first use.
+
+ALTER TABLE posts ALTER COLUMN topic_embedding TYPE VECTOR(1536)
+  USING CASE WHEN array_length(topic_embedding, 1) = 1536 THEN topic_embedding::VECTOR(1536) END;
+
+CREATE INDEX idx_posts_topic_embedding ON posts USING hnsw (topic_embedding vector_cosine_ops);